@@ -0,0 +1,148 @@
+use crate::{ParseOptions, SourceFile, Type};
+use clang::TypeKind;
+
+/// Knows the size of the builtin C types on a target, for when libclang gave no size of its own
+/// or the declarations were read back without one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltinTypeMapper {
+    pub target_triple: String,
+}
+
+impl Default for BuiltinTypeMapper {
+    /// The platform this crate was built for.
+    fn default() -> Self {
+        let os = std::env::consts::OS;
+        let target_triple = match os {
+            "windows" => format!("{}-pc-windows-msvc", std::env::consts::ARCH),
+            "macos" => format!("{}-apple-darwin", std::env::consts::ARCH),
+            "linux" => format!("{}-unknown-linux-gnu", std::env::consts::ARCH),
+            os => format!("{}-unknown-{}", std::env::consts::ARCH, os),
+        };
+        Self { target_triple }
+    }
+}
+
+impl BuiltinTypeMapper {
+    pub fn new(target_triple: String) -> Self {
+        Self { target_triple }
+    }
+
+    /// The target passed to libclang, or the current platform if there was none.
+    pub fn from_parse_options(options: &ParseOptions) -> Self {
+        options
+            .target_triple
+            .as_ref()
+            .map(|target_triple| Self::new(target_triple.to_owned()))
+            .unwrap_or_default()
+    }
+
+    /// The target the file was parsed for, or the current platform if it was not given.
+    pub fn for_source_file(source_file: &SourceFile) -> Self {
+        source_file
+            .target_triple
+            .as_ref()
+            .map(|target_triple| Self::new(target_triple.to_owned()))
+            .unwrap_or_default()
+    }
+
+    /// The size in bytes of a builtin integer, floating point or pointer type, `None` for any
+    /// other kind.
+    pub fn map_type_size(&self, kind: TypeKind) -> Option<u8> {
+        let size = match kind {
+            TypeKind::Bool
+            | TypeKind::CharS
+            | TypeKind::CharU
+            | TypeKind::SChar
+            | TypeKind::UChar => 1,
+            TypeKind::Char16
+            | TypeKind::Short
+            | TypeKind::UShort
+            | TypeKind::Half
+            | TypeKind::Float16 => 2,
+            TypeKind::Char32 | TypeKind::Int | TypeKind::UInt | TypeKind::Float => 4,
+            TypeKind::WChar if self.is_windows() => 2,
+            TypeKind::WChar => 4,
+            // Windows is LLP64, everything else is LP64 or ILP32.
+            TypeKind::Long | TypeKind::ULong if self.is_windows() => 4,
+            TypeKind::Long | TypeKind::ULong => self.pointer_size(),
+            TypeKind::LongLong | TypeKind::ULongLong | TypeKind::Double => 8,
+            TypeKind::Int128 | TypeKind::UInt128 | TypeKind::Float128 => 16,
+            TypeKind::LongDouble => self.long_double_size(),
+            TypeKind::Pointer | TypeKind::BlockPointer | TypeKind::Nullptr => self.pointer_size(),
+            _ => return None,
+        };
+        Some(size)
+    }
+
+    /// The size in bytes of `t` after resolving typedefs, with arrays of a known length counted
+    /// as that many elements.
+    pub fn type_size(&self, t: &Type) -> Option<u64> {
+        let t = t.resolve_canonical();
+        match t.type_kind {
+            TypeKind::ConstantArray => {
+                let element_size = self.type_size(t.array_element_type.as_ref()?)?;
+                Some(element_size * t.array_size? as u64)
+            }
+            TypeKind::Elaborated => self.type_size(t.elaborated_inner()?),
+            kind => self.map_type_size(kind).map(u64::from),
+        }
+    }
+
+    fn arch(&self) -> &str {
+        self.target_triple.split('-').next().unwrap_or_default()
+    }
+
+    fn is_windows(&self) -> bool {
+        ["windows", "win32", "mingw"]
+            .iter()
+            .any(|os| self.target_triple.contains(os))
+    }
+
+    fn is_apple(&self) -> bool {
+        ["apple", "darwin", "macos", "ios"]
+            .iter()
+            .any(|os| self.target_triple.contains(os))
+    }
+
+    fn pointer_size(&self) -> u8 {
+        let arch = self.arch();
+        // ABIs like x86_64 x32 and arm64_32 run 64-bit code with 32-bit pointers.
+        if ["x32", "ilp32"]
+            .iter()
+            .any(|abi| self.target_triple.contains(abi))
+            || arch.ends_with("_32")
+        {
+            4
+        } else if arch.contains("64") || arch == "s390x" || arch == "sparcv9" {
+            8
+        } else {
+            4
+        }
+    }
+
+    fn long_double_size(&self) -> u8 {
+        let arch = self.arch();
+        if self.is_windows() {
+            8
+        } else if arch == "x86_64" {
+            16
+        } else if arch == "x86" || (arch.starts_with('i') && arch.ends_with("86")) {
+            // The 80-bit extended precision format, padded to a multiple of the alignment.
+            if self.is_apple() {
+                16
+            } else {
+                12
+            }
+        } else if arch == "aarch64" || arch.starts_with("arm64") {
+            if self.is_apple() {
+                8
+            } else {
+                16
+            }
+        } else if self.pointer_size() == 8 {
+            16
+        } else {
+            8
+        }
+    }
+}
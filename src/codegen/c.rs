@@ -0,0 +1,254 @@
+use crate::{
+    printer::TypePrinter, EntityVisitor, EnumDeclare, IncludeGuard, MemberDeclare, SourceFile,
+    TypeDeclaration, TypeDeclare,
+};
+use std::{
+    fmt::{self, Write},
+    path::Path,
+};
+
+/// Writes a `SourceFile` back out as a C header that the crate can parse again, which makes it
+/// handy for round-trip testing the extraction.
+pub struct CHeaderEmitter<'a> {
+    source_file: &'a SourceFile,
+}
+
+impl<'a> CHeaderEmitter<'a> {
+    pub fn new(source_file: &'a SourceFile) -> Self {
+        Self { source_file }
+    }
+
+    pub fn emit(&self) -> String {
+        let mut output = String::new();
+        self.write_source_file(&mut output).unwrap();
+        output
+    }
+
+    fn write_source_file(&self, output: &mut String) -> fmt::Result {
+        // The guard of the original header is kept, a file without one gets a macro guard.
+        let include_guard = match &self.source_file.include_guard {
+            Some(IncludeGuard::PragmaOnce) => {
+                writeln!(output, "#pragma once")?;
+                None
+            }
+            Some(IncludeGuard::MacroGuard { macro_name }) => Some(macro_name.to_owned()),
+            None => Some(include_guard(&self.source_file.path)),
+        };
+        if let Some(include_guard) = &include_guard {
+            writeln!(output, "#ifndef {}", include_guard)?;
+            writeln!(output, "#define {}", include_guard)?;
+        }
+        writeln!(output)?;
+
+        // Declaring every named record up front lets pointers refer to records defined later.
+        let mut has_forward_declarations = false;
+        for type_declare in self.source_file.type_declares.iter() {
+            let keyword = match type_declare {
+                TypeDeclare::Struct(_) => "struct",
+                TypeDeclare::Union(_) => "union",
+                _ => continue,
+            };
+            if let Some(name) = type_declare.name() {
+                writeln!(output, "{} {};", keyword, name)?;
+                has_forward_declarations = true;
+            }
+        }
+        for forward_declaration in self.source_file.forward_declarations.iter() {
+            let keyword = format!("{:?}", forward_declaration.kind).to_lowercase();
+            writeln!(output, "{} {};", keyword, forward_declaration.name)?;
+            has_forward_declarations = true;
+        }
+        if has_forward_declarations {
+            writeln!(output)?;
+        }
+
+        for type_declare in self.sorted_type_declares() {
+            self.write_type_declare(output, type_declare)?;
+        }
+        for variable_declare in self.source_file.variable_declares.iter() {
+            if let Some(variable_type) = &variable_declare.variable_type {
+                writeln!(
+                    output,
+                    "extern {};",
+                    TypePrinter::new(variable_type).print_declaration(&variable_declare.name)
+                )?;
+            }
+        }
+        if !self.source_file.variable_declares.is_empty() {
+            writeln!(output)?;
+        }
+        for function_declare in self.source_file.function_declares.iter() {
+            writeln!(output, "{};", function_declare.signature_string())?;
+        }
+        if !self.source_file.function_declares.is_empty() {
+            writeln!(output)?;
+        }
+
+        match include_guard {
+            Some(include_guard) => writeln!(output, "#endif /* {} */", include_guard),
+            None => Ok(()),
+        }
+    }
+
+    /// The types in an order a compiler accepts, or as declared if they contain each other by
+    /// value and no such order exists.
+    fn sorted_type_declares(&self) -> Vec<&'a TypeDeclare> {
+        self.source_file
+            .topological_sort_types()
+            .unwrap_or_else(|_| self.source_file.type_declares.iter().collect())
+    }
+
+    fn write_type_declare(&self, output: &mut String, type_declare: &TypeDeclare) -> fmt::Result {
+        let (keyword, body) = match type_declare {
+            TypeDeclare::Enum(enum_declare) => ("enum", enum_body(enum_declare, "")),
+            TypeDeclare::Struct(struct_declare) => {
+                ("struct", record_body(&struct_declare.fields, "")?)
+            }
+            TypeDeclare::Union(union_declare) => ("union", record_body(&union_declare.fields, "")?),
+            TypeDeclare::Class(class_declare) => {
+                if let Some(name) = class_declare.name() {
+                    writeln!(output, "/* C++ class {} has no C representation */", name)?;
+                    writeln!(output)?;
+                }
+                return Ok(());
+            }
+            TypeDeclare::Typedef(typedef_declare) => {
+                writeln!(
+                    output,
+                    "typedef {};",
+                    TypePrinter::new(&typedef_declare.underlying_type)
+                        .print_declaration(&typedef_declare.name)
+                )?;
+                return writeln!(output);
+            }
+            TypeDeclare::FunctionPointer(function_pointer_declare) => {
+                writeln!(
+                    output,
+                    "typedef {};",
+                    function_pointer_declare.declaration_string()
+                )?;
+                return writeln!(output);
+            }
+        };
+        let mut specifier = keyword.to_owned();
+        // A record only known through a typedef still needs a tag to be declared without a body.
+        let tag_name = type_declare.name().or_else(|| {
+            type_declare
+                .typedef_name()
+                .filter(|_| !type_declare.is_definition())
+        });
+        if let Some(tag_name) = tag_name {
+            write!(specifier, " {}", tag_name)?;
+        }
+        if type_declare.is_definition() {
+            write!(specifier, " {}", body)?;
+        }
+        match type_declare.typedef_name() {
+            Some(typedef_name) => writeln!(output, "typedef {} {};", specifier, typedef_name)?,
+            None => writeln!(output, "{};", specifier)?,
+        }
+        writeln!(output)
+    }
+}
+
+fn enum_body(enum_declare: &EnumDeclare, indent: &str) -> String {
+    let mut body = "{\n".to_owned();
+    for constant in enum_declare.constants.iter() {
+        match &constant.constant_value {
+            Some(value) => body.push_str(&format!(
+                "{}    {} = {},\n",
+                indent, constant.name, value.signed
+            )),
+            None => body.push_str(&format!("{}    {},\n", indent, constant.name)),
+        }
+    }
+    body.push_str(indent);
+    body.push('}');
+    body
+}
+
+/// The `{ ... }` part of a struct or union, with nested records and enums written inline so that
+/// anonymous members keep their place.
+fn record_body(members: &[MemberDeclare], indent: &str) -> Result<String, fmt::Error> {
+    let member_indent = format!("{}    ", indent);
+    let mut body = "{\n".to_owned();
+    // A nested record is held back until we know whether the next field is declared with it.
+    let mut pending_record: Option<(Option<&str>, String)> = None;
+    for member in members.iter() {
+        let (keyword, name, nested_body) = match member {
+            MemberDeclare::Field(field_declare) => {
+                let field_type = match &field_declare.field_type {
+                    Some(field_type) => field_type,
+                    None => continue,
+                };
+                let field_name = field_declare.name.as_deref().unwrap_or("");
+                let mut declaration = TypePrinter::new(field_type).print_declaration(field_name);
+                if let Some((record_name, record)) = pending_record.take() {
+                    match field_type.referenced_type() {
+                        Some(record_type)
+                            if record_type.is_anonymous_record()
+                                || record_name == Some(record_type.tagless_name()) =>
+                        {
+                            declaration = declaration.replacen(&record_type.type_name, &record, 1);
+                        }
+                        _ => writeln!(body, "{}{};", member_indent, record)?,
+                    }
+                }
+                if let Some(bit_width) = field_declare.bit_width {
+                    write!(declaration, " : {}", bit_width)?;
+                }
+                writeln!(body, "{}{};", member_indent, declaration)?;
+                continue;
+            }
+            MemberDeclare::Struct(struct_declare) => (
+                "struct",
+                struct_declare.name(),
+                record_body(&struct_declare.fields, &member_indent)?,
+            ),
+            MemberDeclare::Union(union_declare) => (
+                "union",
+                union_declare.name(),
+                record_body(&union_declare.fields, &member_indent)?,
+            ),
+            MemberDeclare::Enum(enum_declare) => (
+                "enum",
+                enum_declare.name(),
+                enum_body(enum_declare, &member_indent),
+            ),
+        };
+        if let Some((_, record)) = pending_record.take() {
+            writeln!(body, "{}{};", member_indent, record)?;
+        }
+        let record = match name {
+            Some(name) => format!("{} {} {}", keyword, name, nested_body),
+            None => format!("{} {}", keyword, nested_body),
+        };
+        pending_record = Some((name, record));
+    }
+    if let Some((_, record)) = pending_record.take() {
+        writeln!(body, "{}{};", member_indent, record)?;
+    }
+    write!(body, "{}}}", indent)?;
+    Ok(body)
+}
+
+fn include_guard(path: &str) -> String {
+    let file_name = Path::new(path)
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_owned());
+    let mut include_guard: String = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !include_guard.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        include_guard.insert(0, '_');
+    }
+    include_guard
+}
@@ -0,0 +1,756 @@
+use super::storage_unit_bits;
+use crate::{
+    builtin_types::BuiltinTypeMapper, Attribute, ConstantDeclare, EntityVisitor, EnumDeclare,
+    FunctionDeclare, FunctionParameter, FunctionProtoInfo, MemberDeclare, ParameterDeclare,
+    SourceFile, Type, TypeDeclaration, TypeDeclare,
+};
+use clang::{CallingConvention, TypeKind};
+use std::{
+    collections::HashSet,
+    fmt::{self, Write},
+    path::Path,
+};
+
+const CSHARP_KEYWORDS: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// Generates C# P/Invoke declarations for a `SourceFile`: structs with an explicit layout,
+/// enums, delegates for function pointers and a `NativeMethods` class importing the functions.
+pub struct PInvokeGenerator<'a> {
+    source_file: &'a SourceFile,
+    /// Structs, unions, enums and function pointer typedefs, which get a C# type of their own.
+    /// Other typedefs are replaced by their underlying type, C# has no type aliases.
+    declared_names: HashSet<&'a str>,
+    library_name: String,
+    builtin_types: BuiltinTypeMapper,
+}
+
+impl<'a> PInvokeGenerator<'a> {
+    pub fn new(source_file: &'a SourceFile) -> Self {
+        let mut declared_names = HashSet::new();
+        for type_declare in source_file.type_declares.iter() {
+            match type_declare {
+                TypeDeclare::Struct(_) | TypeDeclare::Union(_) | TypeDeclare::Enum(_)
+                    if type_declare.is_definition() =>
+                {
+                    declared_names.extend(type_declare.name());
+                    declared_names.extend(type_declare.typedef_name());
+                }
+                // A delegate cannot take `__arglist`, so variadic callbacks stay `IntPtr`.
+                TypeDeclare::FunctionPointer(declare) if !declare.is_variadic => {
+                    declared_names.insert(declare.name.as_str());
+                }
+                _ => {}
+            }
+        }
+        let library_name = Path::new(&source_file.path)
+            .file_stem()
+            .map(|file_stem| file_stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self {
+            source_file,
+            declared_names,
+            library_name,
+            builtin_types: BuiltinTypeMapper::for_source_file(source_file),
+        }
+    }
+
+    /// The library the functions are imported from, the name of the header without its
+    /// extension by default.
+    pub fn library_name(mut self, library_name: String) -> Self {
+        self.library_name = library_name;
+        self
+    }
+
+    pub fn generate(&self) -> String {
+        let mut output = String::new();
+        self.write_source_file(&mut output).unwrap();
+        output
+    }
+
+    fn write_source_file(&self, output: &mut String) -> fmt::Result {
+        writeln!(output, "using System;")?;
+        writeln!(output, "using System.Runtime.InteropServices;")?;
+        writeln!(output)?;
+
+        // C# does not care about declaration order, so types are written as they appear.
+        let mut written_names = HashSet::new();
+        for type_declare in self.source_file.type_declares.iter() {
+            if !type_declare.is_definition() {
+                continue;
+            }
+            let name = match type_declare.name().or_else(|| type_declare.typedef_name()) {
+                Some(name) => name,
+                None => continue,
+            };
+            if !written_names.insert(name) {
+                continue;
+            }
+            match type_declare {
+                TypeDeclare::Enum(enum_declare) => self.write_enum(output, name, enum_declare)?,
+                TypeDeclare::Struct(struct_declare) => self.write_record(
+                    output,
+                    "struct",
+                    name,
+                    &struct_declare.fields,
+                    &struct_declare.attributes,
+                )?,
+                TypeDeclare::Union(union_declare) => self.write_record(
+                    output,
+                    "union",
+                    name,
+                    &union_declare.fields,
+                    &union_declare.attributes,
+                )?,
+                TypeDeclare::Class(_) => {
+                    writeln!(output, "// C++ class {} has no C ABI representation", name)?;
+                    writeln!(output)?;
+                }
+                TypeDeclare::Typedef(_) => {}
+                TypeDeclare::FunctionPointer(function_pointer_declare)
+                    if function_pointer_declare.is_variadic =>
+                {
+                    writeln!(
+                        output,
+                        "// Variadic function pointer {} is passed as IntPtr",
+                        name
+                    )?;
+                    writeln!(output)?;
+                }
+                TypeDeclare::FunctionPointer(function_pointer_declare) => self.write_delegate(
+                    output,
+                    name,
+                    &function_pointer_declare.return_type,
+                    function_pointer_declare.parameter_types.iter(),
+                )?,
+            }
+        }
+        // A function pointer parameter spelled out in the signature gets a delegate named after
+        // the function and the parameter.
+        for function_declare in self.source_file.function_declares.iter() {
+            for parameter_declare in named_parameters(function_declare) {
+                let function_proto = match self.anonymous_function_pointer(parameter_declare) {
+                    Some(function_proto) => function_proto,
+                    None => continue,
+                };
+                self.write_delegate(
+                    output,
+                    &delegate_name(function_declare, parameter_declare),
+                    &function_proto.return_type,
+                    function_proto.parameter_types.iter(),
+                )?;
+            }
+        }
+
+        writeln!(output, "public static class NativeMethods")?;
+        writeln!(output, "{{")?;
+        writeln!(
+            output,
+            "    private const string LibraryName = \"{}\";",
+            self.library_name
+        )?;
+        writeln!(output)?;
+        for constant in self.source_file.constants.iter() {
+            self.write_constant(output, constant)?;
+        }
+        if !self.source_file.constants.is_empty() {
+            writeln!(output)?;
+        }
+        for variable_declare in self.source_file.variable_declares.iter() {
+            writeln!(
+                output,
+                "    // Variable {} cannot be imported, look it up with NativeLibrary.GetExport.",
+                variable_declare.name
+            )?;
+        }
+        if !self.source_file.variable_declares.is_empty() {
+            writeln!(output)?;
+        }
+        for (index, function_declare) in self.source_file.function_declares.iter().enumerate() {
+            if index > 0 {
+                writeln!(output)?;
+            }
+            self.write_function(output, function_declare)?;
+        }
+        writeln!(output, "}}")
+    }
+
+    fn write_constant(&self, output: &mut String, constant: &ConstantDeclare) -> fmt::Result {
+        let cs_type = constant
+            .constant_type
+            .as_ref()
+            .and_then(|constant_type| {
+                self.primitive_type_name(constant_type.resolve_canonical().type_kind)
+            })
+            .unwrap_or("int");
+        let value = if cs_type.starts_with('u') || cs_type == "byte" {
+            constant.value.unsigned.to_string()
+        } else {
+            constant.value.signed.to_string()
+        };
+        writeln!(
+            output,
+            "    public const {} {} = {};",
+            cs_type,
+            escape(&constant.name),
+            value
+        )
+    }
+
+    fn write_enum(
+        &self,
+        output: &mut String,
+        name: &str,
+        enum_declare: &EnumDeclare,
+    ) -> fmt::Result {
+        let underlying_type = enum_declare
+            .enum_type
+            .as_ref()
+            .and_then(|enum_type| self.primitive_type_name(enum_type.resolve_canonical().type_kind))
+            .filter(|&cs_type| cs_type != "bool" && cs_type != "char")
+            .unwrap_or("int");
+        let is_unsigned = underlying_type.starts_with('u') || underlying_type == "byte";
+        writeln!(output, "public enum {} : {}", escape(name), underlying_type)?;
+        writeln!(output, "{{")?;
+        for constant in enum_declare.constants.iter() {
+            if let Some(comment) = &constant.comment {
+                for line in comment.lines() {
+                    writeln!(output, "    /// {}", line)?;
+                }
+            }
+            match &constant.constant_value {
+                Some(value) if is_unsigned => writeln!(
+                    output,
+                    "    {} = {},",
+                    escape(&constant.name),
+                    value.unsigned
+                )?,
+                Some(value) => {
+                    writeln!(output, "    {} = {},", escape(&constant.name), value.signed)?
+                }
+                None => writeln!(output, "    {},", escape(&constant.name))?,
+            }
+        }
+        writeln!(output, "}}")?;
+        writeln!(output)
+    }
+
+    /// Structs get a sequential layout and unions an explicit one with every field at offset 0.
+    /// Named records nested in the body are written as types of their own, anonymous ones are
+    /// named after the enclosing record.
+    ///
+    /// C# has no bit-fields, so a run of them becomes one integer of their storage unit, with the
+    /// fields it holds listed in a comment.
+    fn write_record(
+        &self,
+        output: &mut String,
+        keyword: &str,
+        name: &str,
+        members: &[MemberDeclare],
+        attributes: &[Attribute],
+    ) -> fmt::Result {
+        let mut body = String::new();
+        let field_prefix = if keyword == "union" {
+            "    [FieldOffset(0)]\n"
+        } else {
+            ""
+        };
+        let mut pending_record = None;
+        let mut anonymous_count = 0;
+        let mut unnamed_count = 0;
+        // The bits used in the storage unit of the current run of bit-fields, the size of the
+        // unit and the fields in it.
+        let mut bit_field_run: Option<(u32, u32, Vec<String>)> = None;
+        let mut bit_field_count = 0;
+        for member in members.iter() {
+            let (nested_keyword, nested_name, nested_members, nested_attributes) = match member {
+                MemberDeclare::Field(field_declare) => {
+                    let field_type = match &field_declare.field_type {
+                        Some(field_type) => field_type,
+                        None => continue,
+                    };
+                    let field_name = match &field_declare.name {
+                        Some(field_name) => escape(field_name),
+                        None => {
+                            unnamed_count += 1;
+                            format!("__unnamed_{}", unnamed_count - 1)
+                        }
+                    };
+                    if let Some(bit_width) = field_declare.bit_width {
+                        let unit_bits = storage_unit_bits(&self.builtin_types, field_type);
+                        let run = match bit_field_run.take() {
+                            Some((used_bits, run_unit_bits, field_names))
+                                if run_unit_bits == unit_bits
+                                    && used_bits + bit_width <= unit_bits =>
+                            {
+                                (used_bits, run_unit_bits, field_names)
+                            }
+                            Some(run) => {
+                                write_bit_field_run(&mut body, field_prefix, bit_field_count, run)?;
+                                bit_field_count += 1;
+                                (0, unit_bits, Vec::new())
+                            }
+                            None => (0, unit_bits, Vec::new()),
+                        };
+                        let (used_bits, unit_bits, mut field_names) = run;
+                        field_names.push(format!("{}: {} bits", field_name, bit_width));
+                        bit_field_run = Some((used_bits + bit_width, unit_bits, field_names));
+                        continue;
+                    }
+                    if let Some(run) = bit_field_run.take() {
+                        write_bit_field_run(&mut body, field_prefix, bit_field_count, run)?;
+                        bit_field_count += 1;
+                    }
+                    if field_declare.is_flexible_array_member() {
+                        writeln!(
+                            body,
+                            "    // Flexible array member {} follows the struct in memory.",
+                            field_name
+                        )?;
+                        continue;
+                    }
+                    let cs_type = match pending_record.take() {
+                        Some(record_name) if field_type.is_anonymous_record() => record_name,
+                        _ => self.cs_type(field_type),
+                    };
+                    write!(body, "{}", field_prefix)?;
+                    if let Some((element_type, length)) = self.flattened_array(field_type) {
+                        writeln!(
+                            body,
+                            "    [MarshalAs(UnmanagedType.ByValArray, SizeConst = {})]",
+                            length
+                        )?;
+                        writeln!(body, "    public {}[] {};", element_type, field_name)?;
+                        continue;
+                    }
+                    if let Some(marshal_as) = self.marshal_as(field_type) {
+                        writeln!(body, "    [MarshalAs(UnmanagedType.{})]", marshal_as)?;
+                    }
+                    writeln!(body, "    public {} {};", cs_type, field_name)?;
+                    continue;
+                }
+                MemberDeclare::Struct(struct_declare) => (
+                    "struct",
+                    struct_declare.name(),
+                    &struct_declare.fields,
+                    struct_declare.attributes.as_slice(),
+                ),
+                MemberDeclare::Union(union_declare) => (
+                    "union",
+                    union_declare.name(),
+                    &union_declare.fields,
+                    union_declare.attributes.as_slice(),
+                ),
+                MemberDeclare::Enum(enum_declare) => {
+                    if let Some(enum_name) = enum_declare.name() {
+                        self.write_enum(output, enum_name, enum_declare)?;
+                    }
+                    continue;
+                }
+            };
+            let nested_name = match nested_name {
+                Some(nested_name) => nested_name.to_owned(),
+                None => {
+                    anonymous_count += 1;
+                    let anonymous_name = format!("{}__anon_{}", name, anonymous_count - 1);
+                    pending_record = Some(anonymous_name.to_owned());
+                    anonymous_name
+                }
+            };
+            self.write_record(
+                output,
+                nested_keyword,
+                &nested_name,
+                nested_members,
+                nested_attributes,
+            )?;
+        }
+        if let Some(run) = bit_field_run {
+            write_bit_field_run(&mut body, field_prefix, bit_field_count, run)?;
+        }
+
+        let layout = if keyword == "union" {
+            "Explicit"
+        } else {
+            "Sequential"
+        };
+        if attributes.contains(&Attribute::Packed) {
+            writeln!(output, "[StructLayout(LayoutKind.{}, Pack = 1)]", layout)?;
+        } else {
+            writeln!(output, "[StructLayout(LayoutKind.{})]", layout)?;
+        }
+        writeln!(output, "public struct {}", escape(name))?;
+        writeln!(output, "{{")?;
+        write!(output, "{}", body)?;
+        writeln!(output, "}}")?;
+        writeln!(output)
+    }
+
+    fn write_delegate<'t>(
+        &self,
+        output: &mut String,
+        name: &str,
+        return_type: &Type,
+        parameter_types: impl Iterator<Item = &'t Type>,
+    ) -> fmt::Result {
+        let parameters = parameter_types
+            .enumerate()
+            .map(|(index, parameter_type)| {
+                format!("{} arg{}", self.parameter_type(parameter_type, None), index)
+            })
+            .collect::<Vec<_>>();
+        writeln!(
+            output,
+            "[UnmanagedFunctionPointer(CallingConvention.Cdecl)]"
+        )?;
+        writeln!(
+            output,
+            "public delegate {} {}({});",
+            self.return_type(return_type),
+            escape(name),
+            parameters.join(", ")
+        )?;
+        writeln!(output)
+    }
+
+    fn write_function(
+        &self,
+        output: &mut String,
+        function_declare: &FunctionDeclare,
+    ) -> fmt::Result {
+        let parameters = function_declare
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(index, parameter)| match parameter {
+                FunctionParameter::Named(parameter_declare) => {
+                    let parameter_type = match &parameter_declare.parameter_type {
+                        Some(parameter_type) => {
+                            let delegate_name = self
+                                .anonymous_function_pointer(parameter_declare)
+                                .map(|_| delegate_name(function_declare, parameter_declare));
+                            self.parameter_type(parameter_type, delegate_name)
+                        }
+                        None => "IntPtr".to_owned(),
+                    };
+                    format!(
+                        "{} {}",
+                        parameter_type,
+                        escape(&parameter_declare.binding_name(index))
+                    )
+                }
+                FunctionParameter::Variadic(_) => "__arglist".to_owned(),
+            })
+            .collect::<Vec<_>>();
+        writeln!(
+            output,
+            "    [DllImport(LibraryName, CallingConvention = CallingConvention.{})]",
+            calling_convention(function_declare.calling_convention)
+        )?;
+        writeln!(
+            output,
+            "    public static extern {} {}({});",
+            function_declare
+                .return_type
+                .as_ref()
+                .map(|return_type| self.return_type(return_type))
+                .unwrap_or_else(|| "void".to_owned()),
+            escape(&function_declare.function_name),
+            parameters.join(", ")
+        )
+    }
+
+    /// The type of a function or delegate parameter with its marshalling attribute. A
+    /// `const char *` parameter takes a `string`, function pointers take a delegate.
+    fn parameter_type(&self, t: &Type, delegate_name: Option<String>) -> String {
+        let cs_type = match delegate_name {
+            Some(delegate_name) => delegate_name,
+            None if is_c_string(t) => return "[MarshalAs(UnmanagedType.LPStr)] string".to_owned(),
+            None => self.cs_type(t),
+        };
+        let marshal_as = if cs_type == "IntPtr" {
+            None
+        } else if is_function_pointer(t) {
+            Some("FunctionPtr")
+        } else {
+            self.marshal_as(t)
+        };
+        match marshal_as {
+            Some(marshal_as) => format!("[MarshalAs(UnmanagedType.{})] {}", marshal_as, cs_type),
+            None => cs_type,
+        }
+    }
+
+    fn return_type(&self, t: &Type) -> String {
+        if t.resolve_canonical().is_void() {
+            "void".to_owned()
+        } else {
+            self.cs_type(t)
+        }
+    }
+
+    /// How a field or parameter of type `t` has to be marshalled when the default does not fit:
+    /// C# marshals `bool` as a 4-byte Win32 `BOOL`.
+    fn marshal_as(&self, t: &Type) -> Option<&'static str> {
+        let resolved_type = t.resolve_canonical();
+        if resolved_type.type_kind == TypeKind::Bool {
+            Some("I1")
+        } else if is_function_pointer(t) && self.cs_type(t) != "IntPtr" {
+            Some("FunctionPtr")
+        } else {
+            None
+        }
+    }
+
+    /// The innermost element type and total length of a fixed-size array, multi-dimensional
+    /// arrays are flattened as they are contiguous in memory anyway.
+    fn flattened_array(&self, t: &Type) -> Option<(String, usize)> {
+        if t.resolve_canonical().type_kind != TypeKind::ConstantArray {
+            return None;
+        }
+        let mut element_type = t.resolve_canonical();
+        let mut length = 1;
+        while let Some(inner_type) = &element_type.array_element_type {
+            length *= element_type.array_size.unwrap_or(0);
+            element_type = inner_type;
+        }
+        Some((self.cs_type(element_type), length))
+    }
+
+    /// The function prototype of a parameter declared as a function pointer rather than through
+    /// a typedef that could name its delegate.
+    fn anonymous_function_pointer<'p>(
+        &self,
+        parameter_declare: &'p ParameterDeclare,
+    ) -> Option<&'p FunctionProtoInfo> {
+        let parameter_type = parameter_declare.parameter_type.as_ref()?;
+        if parameter_type.type_kind != TypeKind::Pointer {
+            return None;
+        }
+        parameter_type
+            .pointee_type
+            .as_ref()?
+            .resolve_canonical()
+            .function_proto
+            .as_ref()
+    }
+
+    fn cs_type(&self, t: &Type) -> String {
+        if let Some(inner_type) = t.elaborated_inner() {
+            return self.cs_type(inner_type);
+        }
+        match t.type_kind {
+            TypeKind::Pointer | TypeKind::VariableArray => "IntPtr".to_owned(),
+            TypeKind::ConstantArray | TypeKind::IncompleteArray => format!(
+                "{}[]",
+                t.array_element_type
+                    .as_ref()
+                    .map(|element_type| self.cs_type(element_type))
+                    .unwrap_or_else(|| "byte".to_owned())
+            ),
+            TypeKind::Typedef | TypeKind::Elaborated | TypeKind::Record | TypeKind::Enum => {
+                let name = t.tagless_name();
+                if self.declared_names.contains(name) {
+                    escape(name)
+                } else if let Some(canonical_type) = &t.canonical_type {
+                    self.cs_type(canonical_type)
+                } else {
+                    "IntPtr".to_owned()
+                }
+            }
+            kind => self
+                .primitive_type_name(kind)
+                .unwrap_or("IntPtr")
+                .to_owned(),
+        }
+    }
+
+    /// C `long` is as wide as `int` on Windows and 32-bit targets, and as C# `long` on LP64 ones.
+    fn primitive_type_name(&self, kind: TypeKind) -> Option<&'static str> {
+        let long_is_32_bits = || self.builtin_types.map_type_size(kind) == Some(4);
+        match kind {
+            TypeKind::Long if long_is_32_bits() => Some("int"),
+            TypeKind::ULong if long_is_32_bits() => Some("uint"),
+            kind => csharp_primitive_type_name(kind),
+        }
+    }
+}
+
+fn named_parameters(function_declare: &FunctionDeclare) -> impl Iterator<Item = &ParameterDeclare> {
+    function_declare
+        .parameters
+        .iter()
+        .filter_map(|parameter| match parameter {
+            FunctionParameter::Named(parameter_declare) => Some(parameter_declare),
+            FunctionParameter::Variadic(_) => None,
+        })
+}
+
+fn delegate_name(
+    function_declare: &FunctionDeclare,
+    parameter_declare: &ParameterDeclare,
+) -> String {
+    format!(
+        "{}_{}",
+        function_declare.function_name,
+        function_declare.parameter_binding_name(parameter_declare)
+    )
+}
+
+fn is_function_pointer(t: &Type) -> bool {
+    t.resolve_canonical()
+        .pointee_type
+        .as_ref()
+        .map(|pointee_type| pointee_type.resolve_canonical().function_proto.is_some())
+        .unwrap_or(false)
+}
+
+/// `const char *`, which is taken to be a NUL-terminated string.
+fn is_c_string(t: &Type) -> bool {
+    match &t.resolve_canonical().pointee_type {
+        Some(pointee_type) => {
+            pointee_type.is_const
+                && matches!(
+                    pointee_type.resolve_canonical().type_kind,
+                    TypeKind::CharS | TypeKind::CharU
+                )
+        }
+        None => false,
+    }
+}
+
+fn write_bit_field_run(
+    body: &mut String,
+    field_prefix: &str,
+    bit_field_count: usize,
+    (_, unit_bits, field_names): (u32, u32, Vec<String>),
+) -> fmt::Result {
+    let unit_type = match unit_bits {
+        8 => "byte",
+        16 => "ushort",
+        64 => "ulong",
+        _ => "uint",
+    };
+    writeln!(body, "{}    // {}", field_prefix, field_names.join(", "))?;
+    writeln!(
+        body,
+        "    public {} __bitfield_{};",
+        unit_type, bit_field_count
+    )
+}
+
+fn calling_convention(calling_convention: Option<CallingConvention>) -> &'static str {
+    match calling_convention {
+        Some(CallingConvention::Stdcall) => "StdCall",
+        Some(CallingConvention::Fastcall) => "FastCall",
+        Some(CallingConvention::Thiscall) => "ThisCall",
+        _ => "Cdecl",
+    }
+}
+
+/// C# integer types have fixed sizes, `long` is taken to be 64 bits wide as on LP64 targets.
+/// `PInvokeGenerator::primitive_type_name` corrects that for the target.
+fn csharp_primitive_type_name(kind: TypeKind) -> Option<&'static str> {
+    match kind {
+        TypeKind::Bool => Some("bool"),
+        TypeKind::CharS | TypeKind::SChar => Some("sbyte"),
+        TypeKind::CharU | TypeKind::UChar => Some("byte"),
+        TypeKind::WChar | TypeKind::Char16 => Some("char"),
+        TypeKind::Char32 => Some("uint"),
+        TypeKind::Short => Some("short"),
+        TypeKind::UShort => Some("ushort"),
+        TypeKind::Int => Some("int"),
+        TypeKind::UInt => Some("uint"),
+        TypeKind::Long | TypeKind::LongLong => Some("long"),
+        TypeKind::ULong | TypeKind::ULongLong => Some("ulong"),
+        TypeKind::Float => Some("float"),
+        TypeKind::Double => Some("double"),
+        TypeKind::Void => Some("void"),
+        _ => None,
+    }
+}
+
+fn escape(name: &str) -> String {
+    if CSHARP_KEYWORDS.contains(&name) {
+        format!("@{}", name)
+    } else {
+        name.to_owned()
+    }
+}
@@ -0,0 +1,23 @@
+mod c;
+mod csharp;
+mod python;
+mod rust;
+mod zig;
+
+use crate::{builtin_types::BuiltinTypeMapper, Type};
+
+pub use c::CHeaderEmitter;
+pub use csharp::PInvokeGenerator;
+pub use python::CtypesGenerator;
+pub(crate) use rust::integer_type_name;
+pub use rust::RustFfiGenerator;
+pub use zig::ZigFfiGenerator;
+
+/// The size of the integer a bit-field of type `t` is allocated in, which depends on the target
+/// for `long`. Enums and anything else without a builtin size take that of `int`.
+pub(crate) fn storage_unit_bits(builtin_types: &BuiltinTypeMapper, t: &Type) -> u32 {
+    builtin_types
+        .map_type_size(t.resolve_canonical().type_kind)
+        .map(|size| u32::from(size) * 8)
+        .unwrap_or(32)
+}
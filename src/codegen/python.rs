@@ -0,0 +1,483 @@
+use crate::{
+    Attribute, EntityVisitor, EnumDeclare, FunctionDeclare, FunctionParameter, MemberDeclare,
+    SourceFile, Type, TypeDeclaration, TypeDeclare,
+};
+use clang::TypeKind;
+use std::{
+    collections::HashSet,
+    fmt::{self, Write},
+    path::Path,
+};
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Generates a Python module declaring the types and functions of a `SourceFile` with `ctypes`.
+/// Every struct and union class is declared up front and gets its `_fields_` later, so records
+/// can point to each other in any order.
+pub struct CtypesGenerator<'a> {
+    source_file: &'a SourceFile,
+    /// Structs, unions, enums and typedefs, which get a Python name of their own.
+    declared_names: HashSet<&'a str>,
+    library_name: String,
+}
+
+impl<'a> CtypesGenerator<'a> {
+    pub fn new(source_file: &'a SourceFile) -> Self {
+        let mut declared_names = HashSet::new();
+        for type_declare in source_file.type_declares.iter() {
+            declared_names.extend(type_declare.name());
+            declared_names.extend(type_declare.typedef_name());
+        }
+        let library_name = Path::new(&source_file.path)
+            .file_stem()
+            .map(|file_stem| file_stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self {
+            source_file,
+            declared_names,
+            library_name,
+        }
+    }
+
+    /// The library the functions are loaded from, the name of the header without its extension
+    /// by default.
+    pub fn library_name(mut self, library_name: String) -> Self {
+        self.library_name = library_name;
+        self
+    }
+
+    pub fn generate(&self) -> String {
+        let mut output = String::new();
+        self.write_source_file(&mut output).unwrap();
+        output
+    }
+
+    fn write_source_file(&self, output: &mut String) -> fmt::Result {
+        writeln!(output, "from ctypes import *")?;
+        writeln!(output, "from ctypes.util import find_library")?;
+        writeln!(output)?;
+        writeln!(
+            output,
+            "_lib = CDLL(find_library(\"{0}\") or \"{0}\")",
+            self.library_name
+        )?;
+        writeln!(output)?;
+
+        let mut has_constants = false;
+        for constant in self.source_file.constants.iter() {
+            writeln!(
+                output,
+                "{} = {}",
+                escape(&constant.name),
+                constant.value.signed
+            )?;
+            has_constants = true;
+        }
+        for macro_definition in self.source_file.macro_definitions.iter() {
+            if let Some(value) = macro_definition.evaluate_unsigned() {
+                writeln!(output, "{} = {}", escape(&macro_definition.name), value)?;
+                has_constants = true;
+            } else if let Some(value) = macro_definition.evaluate() {
+                writeln!(output, "{} = {}", escape(&macro_definition.name), value)?;
+                has_constants = true;
+            }
+        }
+        if has_constants {
+            writeln!(output)?;
+        }
+
+        let type_declares = self
+            .source_file
+            .topological_sort_types()
+            .unwrap_or_else(|_| self.source_file.type_declares.iter().collect());
+        let mut declared_records = HashSet::new();
+        for &type_declare in type_declares.iter() {
+            let base_class = match type_declare {
+                TypeDeclare::Struct(_) => "Structure",
+                TypeDeclare::Union(_) => "Union",
+                _ => continue,
+            };
+            if let Some(name) = type_declare.name().or_else(|| type_declare.typedef_name()) {
+                if declared_records.insert(name) {
+                    write_class(output, name, base_class)?;
+                }
+            }
+        }
+
+        let mut written_names = HashSet::new();
+        for type_declare in type_declares {
+            let (name, typedef_name) = match type_declare.name() {
+                Some(name) => (name, type_declare.typedef_name()),
+                None => match type_declare.typedef_name() {
+                    Some(typedef_name) => (typedef_name, None),
+                    None => continue,
+                },
+            };
+            if !type_declare.is_definition() || !written_names.insert(name) {
+                continue;
+            }
+            match type_declare {
+                TypeDeclare::Enum(enum_declare) => self.write_enum(output, name, enum_declare)?,
+                TypeDeclare::Struct(struct_declare) => self.write_fields(
+                    output,
+                    name,
+                    &struct_declare.fields,
+                    &struct_declare.attributes,
+                )?,
+                TypeDeclare::Union(union_declare) => self.write_fields(
+                    output,
+                    name,
+                    &union_declare.fields,
+                    &union_declare.attributes,
+                )?,
+                TypeDeclare::Class(_) => {
+                    writeln!(output, "# C++ class {} has no C ABI representation", name)?;
+                    writeln!(output)?;
+                    continue;
+                }
+                TypeDeclare::Typedef(typedef_declare) => {
+                    // `typedef struct point point;` names the class that is already there.
+                    if typedef_declare.underlying_type.tagless_name() != name {
+                        writeln!(
+                            output,
+                            "{} = {}",
+                            escape(name),
+                            self.ctypes_type(&typedef_declare.underlying_type)
+                        )?;
+                        writeln!(output)?;
+                    }
+                }
+                TypeDeclare::FunctionPointer(function_pointer_declare) => {
+                    writeln!(
+                        output,
+                        "{} = {}",
+                        escape(name),
+                        self.function_type(
+                            &function_pointer_declare.return_type,
+                            &function_pointer_declare.parameter_types,
+                        )
+                    )?;
+                    writeln!(output)?;
+                }
+            }
+            if let Some(typedef_name) = typedef_name.filter(|&typedef_name| typedef_name != name) {
+                writeln!(output, "{} = {}", escape(typedef_name), escape(name))?;
+                writeln!(output)?;
+            }
+        }
+
+        for variable_declare in self.source_file.variable_declares.iter() {
+            if let Some(variable_type) = &variable_declare.variable_type {
+                writeln!(
+                    output,
+                    "{} = {}.in_dll(_lib, \"{}\")",
+                    escape(&variable_declare.name),
+                    self.ctypes_type(variable_type),
+                    variable_declare.name
+                )?;
+            }
+        }
+        if !self.source_file.variable_declares.is_empty() {
+            writeln!(output)?;
+        }
+        for (index, function_declare) in self.source_file.function_declares.iter().enumerate() {
+            if index > 0 {
+                writeln!(output)?;
+            }
+            self.write_function(output, function_declare)?;
+        }
+        Ok(())
+    }
+
+    /// ctypes has no enum types, an enum becomes an alias of its integer type and its constants
+    /// module level integers.
+    fn write_enum(
+        &self,
+        output: &mut String,
+        name: &str,
+        enum_declare: &EnumDeclare,
+    ) -> fmt::Result {
+        let integer_type = enum_declare
+            .enum_type
+            .as_ref()
+            .and_then(|enum_type| {
+                ctypes_primitive_type_name(enum_type.resolve_canonical().type_kind)
+            })
+            .unwrap_or("c_int");
+        let is_unsigned = integer_type.starts_with("c_u");
+        writeln!(output, "{} = {}", escape(name), integer_type)?;
+        for constant in enum_declare.constants.iter() {
+            if let Some(value) = &constant.constant_value {
+                if is_unsigned {
+                    writeln!(output, "{} = {}", escape(&constant.name), value.unsigned)?;
+                } else {
+                    writeln!(output, "{} = {}", escape(&constant.name), value.signed)?;
+                }
+            }
+        }
+        writeln!(output)
+    }
+
+    /// Assigns the `_fields_` of a declared record class. Records nested in it get classes of
+    /// their own first, anonymous ones are named after the enclosing record and listed in
+    /// `_anonymous_` so that their fields can be accessed directly, as in C.
+    fn write_fields(
+        &self,
+        output: &mut String,
+        name: &str,
+        members: &[MemberDeclare],
+        attributes: &[Attribute],
+    ) -> fmt::Result {
+        let mut fields = Vec::new();
+        let mut anonymous_fields = Vec::new();
+        let mut pending_record = None;
+        let mut unnamed_count = 0;
+        for member in members.iter() {
+            let (base_class, nested_name, nested_members, nested_attributes) = match member {
+                MemberDeclare::Field(field_declare) => {
+                    let field_type = match &field_declare.field_type {
+                        Some(field_type) => field_type,
+                        None => continue,
+                    };
+                    let field_name = match &field_declare.name {
+                        Some(field_name) => field_name.to_owned(),
+                        None => {
+                            unnamed_count += 1;
+                            format!("_unnamed_{}", unnamed_count - 1)
+                        }
+                    };
+                    let ctypes_type = match pending_record.take() {
+                        Some(record_name) if field_type.is_anonymous_record() => {
+                            anonymous_fields.push(format!("\"{}\"", field_name));
+                            record_name
+                        }
+                        _ if field_declare.is_flexible_array_member() => format!(
+                            "{} * 0",
+                            field_type
+                                .array_element_type
+                                .as_ref()
+                                .map(|element_type| self.ctypes_type(element_type))
+                                .unwrap_or_else(|| "c_ubyte".to_owned())
+                        ),
+                        _ => self.ctypes_type(field_type),
+                    };
+                    match field_declare.bit_width {
+                        Some(bit_width) => fields.push(format!(
+                            "(\"{}\", {}, {})",
+                            field_name, ctypes_type, bit_width
+                        )),
+                        None => fields.push(format!("(\"{}\", {})", field_name, ctypes_type)),
+                    }
+                    continue;
+                }
+                MemberDeclare::Struct(struct_declare) => (
+                    "Structure",
+                    struct_declare.name(),
+                    &struct_declare.fields,
+                    struct_declare.attributes.as_slice(),
+                ),
+                MemberDeclare::Union(union_declare) => (
+                    "Union",
+                    union_declare.name(),
+                    &union_declare.fields,
+                    union_declare.attributes.as_slice(),
+                ),
+                MemberDeclare::Enum(enum_declare) => {
+                    if let Some(enum_name) = enum_declare.name() {
+                        self.write_enum(output, enum_name, enum_declare)?;
+                    }
+                    continue;
+                }
+            };
+            let nested_name = match nested_name {
+                Some(nested_name) => nested_name.to_owned(),
+                None => {
+                    let anonymous_name = format!("{}__anon_{}", name, unnamed_count);
+                    unnamed_count += 1;
+                    pending_record = Some(escape(&anonymous_name));
+                    anonymous_name
+                }
+            };
+            write_class(output, &nested_name, base_class)?;
+            self.write_fields(output, &nested_name, nested_members, nested_attributes)?;
+        }
+
+        // `_pack_` and `_anonymous_` only take effect when set before `_fields_`.
+        if attributes.contains(&Attribute::Packed) {
+            writeln!(output, "{}._pack_ = 1", escape(name))?;
+        }
+        if !anonymous_fields.is_empty() {
+            writeln!(
+                output,
+                "{}._anonymous_ = ({},)",
+                escape(name),
+                anonymous_fields.join(", ")
+            )?;
+        }
+        if fields.is_empty() {
+            writeln!(output, "{}._fields_ = []", escape(name))?;
+        } else {
+            writeln!(output, "{}._fields_ = [", escape(name))?;
+            for field in fields.iter() {
+                writeln!(output, "    {},", field)?;
+            }
+            writeln!(output, "]")?;
+        }
+        writeln!(output)
+    }
+
+    fn write_function(
+        &self,
+        output: &mut String,
+        function_declare: &FunctionDeclare,
+    ) -> fmt::Result {
+        let name = escape(&function_declare.function_name);
+        let argument_types = function_declare
+            .parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                FunctionParameter::Named(parameter_declare) => Some(
+                    parameter_declare
+                        .parameter_type
+                        .as_ref()
+                        .map(|parameter_type| self.ctypes_type(parameter_type))
+                        .unwrap_or_else(|| "c_void_p".to_owned()),
+                ),
+                // ctypes passes extra arguments of a variadic function as they are given.
+                FunctionParameter::Variadic(_) => None,
+            })
+            .collect::<Vec<_>>();
+        writeln!(output, "{} = _lib.{}", name, function_declare.function_name)?;
+        writeln!(
+            output,
+            "{}.argtypes = [{}]",
+            name,
+            argument_types.join(", ")
+        )?;
+        writeln!(
+            output,
+            "{}.restype = {}",
+            name,
+            function_declare
+                .return_type
+                .as_ref()
+                .map(|return_type| self.return_type(return_type))
+                .unwrap_or_else(|| "None".to_owned())
+        )
+    }
+
+    fn return_type(&self, t: &Type) -> String {
+        if t.resolve_canonical().is_void() {
+            "None".to_owned()
+        } else {
+            self.ctypes_type(t)
+        }
+    }
+
+    fn function_type(&self, return_type: &Type, parameter_types: &[Type]) -> String {
+        let mut types = vec![self.return_type(return_type)];
+        types.extend(
+            parameter_types
+                .iter()
+                .map(|parameter_type| self.ctypes_type(parameter_type)),
+        );
+        format!("CFUNCTYPE({})", types.join(", "))
+    }
+
+    fn ctypes_type(&self, t: &Type) -> String {
+        if let Some(inner_type) = t.elaborated_inner() {
+            return self.ctypes_type(inner_type);
+        }
+        match t.type_kind {
+            TypeKind::Pointer => match &t.pointee_type {
+                Some(pointee_type) => self.pointer_type(pointee_type),
+                None => "c_void_p".to_owned(),
+            },
+            TypeKind::ConstantArray | TypeKind::IncompleteArray => format!(
+                "({} * {})",
+                t.array_element_type
+                    .as_ref()
+                    .map(|element_type| self.ctypes_type(element_type))
+                    .unwrap_or_else(|| "c_ubyte".to_owned()),
+                t.array_size.unwrap_or(0)
+            ),
+            TypeKind::VariableArray => "c_void_p".to_owned(),
+            TypeKind::Typedef | TypeKind::Elaborated | TypeKind::Record | TypeKind::Enum => {
+                let name = t.tagless_name();
+                if self.declared_names.contains(name) {
+                    escape(name)
+                } else if let Some(canonical_type) = &t.canonical_type {
+                    self.ctypes_type(canonical_type)
+                } else {
+                    "c_void_p".to_owned()
+                }
+            }
+            kind => ctypes_primitive_type_name(kind)
+                .unwrap_or("c_void_p")
+                .to_owned(),
+        }
+    }
+
+    /// `char *` becomes `c_char_p` and `void *` `c_void_p`, as ctypes converts those to and
+    /// from Python values. Function pointers become `CFUNCTYPE` prototypes.
+    fn pointer_type(&self, pointee_type: &Type) -> String {
+        let resolved_type = pointee_type.resolve_canonical();
+        if let Some(function_proto) = &resolved_type.function_proto {
+            return self
+                .function_type(&function_proto.return_type, &function_proto.parameter_types);
+        }
+        match resolved_type.type_kind {
+            TypeKind::Void => "c_void_p".to_owned(),
+            TypeKind::CharS | TypeKind::CharU => "c_char_p".to_owned(),
+            TypeKind::WChar => "c_wchar_p".to_owned(),
+            _ => match self.ctypes_type(pointee_type).as_str() {
+                "c_void_p" => "c_void_p".to_owned(),
+                pointee => format!("POINTER({})", pointee),
+            },
+        }
+    }
+}
+
+fn write_class(output: &mut String, name: &str, base_class: &str) -> fmt::Result {
+    writeln!(output, "class {}({}):", escape(name), base_class)?;
+    writeln!(output, "    pass")?;
+    writeln!(output)
+}
+
+/// ctypes integer types follow the C types of the platform, so they map one to one.
+fn ctypes_primitive_type_name(kind: TypeKind) -> Option<&'static str> {
+    match kind {
+        TypeKind::Bool => Some("c_bool"),
+        TypeKind::CharS | TypeKind::CharU => Some("c_char"),
+        TypeKind::SChar => Some("c_byte"),
+        TypeKind::UChar => Some("c_ubyte"),
+        TypeKind::WChar => Some("c_wchar"),
+        TypeKind::Char16 => Some("c_uint16"),
+        TypeKind::Char32 => Some("c_uint32"),
+        TypeKind::Short => Some("c_short"),
+        TypeKind::UShort => Some("c_ushort"),
+        TypeKind::Int => Some("c_int"),
+        TypeKind::UInt => Some("c_uint"),
+        TypeKind::Long => Some("c_long"),
+        TypeKind::ULong => Some("c_ulong"),
+        TypeKind::LongLong => Some("c_longlong"),
+        TypeKind::ULongLong => Some("c_ulonglong"),
+        TypeKind::Float => Some("c_float"),
+        TypeKind::Double => Some("c_double"),
+        TypeKind::LongDouble => Some("c_longdouble"),
+        _ => None,
+    }
+}
+
+fn escape(name: &str) -> String {
+    if PYTHON_KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_owned()
+    }
+}
@@ -0,0 +1,1276 @@
+use super::storage_unit_bits;
+use crate::{
+    builtin_types::BuiltinTypeMapper,
+    memory_model::{FunctionOwnership, MemoryModel, OwnershipHints},
+    Attribute, ConstantDeclare, ConstexprDeclare, ConstexprValue, DeclSpec, EntityVisitor,
+    EnumDeclare, FieldDeclare, FunctionDeclare, FunctionParameter, FunctionPointerTypeDeclare,
+    MacroDefinition, MemberDeclare, ParameterDeclare, SourceFile, Type, TypeDeclaration,
+    TypeDeclare,
+};
+use clang::{CallingConvention, TypeKind};
+use std::{
+    collections::HashSet,
+    fmt::{self, Write},
+};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+pub struct RustFfiGenerator<'a> {
+    source_file: &'a SourceFile,
+    declared_names: HashSet<&'a str>,
+    use_bitflags: bool,
+    dll_name: Option<String>,
+    ownership_hints: OwnershipHints,
+    type_mapper: Option<BuiltinTypeMapper>,
+}
+
+impl<'a> RustFfiGenerator<'a> {
+    pub fn new(source_file: &'a SourceFile) -> Self {
+        let mut declared_names = HashSet::new();
+        for type_declare in source_file.type_declares.iter() {
+            declared_names.extend(type_declare.name());
+            declared_names.extend(type_declare.typedef_name());
+            if let TypeDeclare::Struct(struct_declare) = type_declare {
+                Self::collect_member_names(&struct_declare.fields, &mut declared_names);
+            } else if let TypeDeclare::Union(union_declare) = type_declare {
+                Self::collect_member_names(&union_declare.fields, &mut declared_names);
+            }
+        }
+        Self {
+            source_file,
+            declared_names,
+            use_bitflags: false,
+            dll_name: None,
+            ownership_hints: OwnershipHints::default(),
+            type_mapper: source_file
+                .target_triple
+                .as_ref()
+                .map(|target_triple| BuiltinTypeMapper::new(target_triple.to_owned())),
+        }
+    }
+
+    /// Emits enums that look like bit flags, see `EnumDeclare::is_flags`, as invocations of the
+    /// `bitflags!` macro from the `bitflags` crate.
+    pub fn bitflags(mut self, use_bitflags: bool) -> Self {
+        self.use_bitflags = use_bitflags;
+        self
+    }
+
+    /// Links the `__declspec(dllimport)` functions against the named DLL.
+    pub fn dll_name(mut self, dll_name: String) -> Self {
+        self.dll_name = Some(dll_name);
+        self
+    }
+
+    /// The naming conventions the `// SAFETY:` comments on functions are guessed from, see
+    /// `MemoryModel::classify`.
+    pub fn ownership_hints(mut self, ownership_hints: OwnershipHints) -> Self {
+        self.ownership_hints = ownership_hints;
+        self
+    }
+
+    /// Spells `long` and `wchar_t` as fixed-size integers of their size on the mapper's target
+    /// rather than as `std::os::raw` types, which follow the platform the bindings are compiled
+    /// for. Files parsed for a given target get a mapper for it by default.
+    pub fn type_mapper(mut self, type_mapper: BuiltinTypeMapper) -> Self {
+        self.type_mapper = Some(type_mapper);
+        self
+    }
+
+    fn collect_member_names(members: &'a [MemberDeclare], declared_names: &mut HashSet<&'a str>) {
+        for member in members.iter() {
+            match member {
+                MemberDeclare::Field(_) => {}
+                MemberDeclare::Struct(struct_declare) => {
+                    declared_names.extend(struct_declare.name());
+                    Self::collect_member_names(&struct_declare.fields, declared_names);
+                }
+                MemberDeclare::Union(union_declare) => {
+                    declared_names.extend(union_declare.name());
+                    Self::collect_member_names(&union_declare.fields, declared_names);
+                }
+                MemberDeclare::Enum(enum_declare) => declared_names.extend(enum_declare.name()),
+            }
+        }
+    }
+
+    pub fn generate(&self) -> String {
+        let mut output = String::new();
+        self.write_source_file(&mut output).unwrap();
+        output
+    }
+
+    fn write_source_file(&self, output: &mut String) -> fmt::Result {
+        // Rust does not care about item order, but bindings read better with dependencies first.
+        let type_declares = self
+            .source_file
+            .topological_sort_types()
+            .unwrap_or_else(|_| self.source_file.type_declares.iter().collect());
+        for type_declare in type_declares {
+            let (name, typedef_name) = match type_declare.name() {
+                Some(name) => (name, type_declare.typedef_name()),
+                None => match type_declare.typedef_name() {
+                    Some(typedef_name) => (typedef_name, None),
+                    None => continue,
+                },
+            };
+            if !type_declare.is_definition() {
+                if !self.has_definition(name) {
+                    self.write_opaque(output, name)?;
+                }
+            } else {
+                match type_declare {
+                    TypeDeclare::Enum(enum_declare) => {
+                        self.write_enum(output, name, enum_declare)?
+                    }
+                    TypeDeclare::Struct(struct_declare) => {
+                        let flexible_array_member = struct_declare.flexible_array_member_field();
+                        if let Some(field_declare) = flexible_array_member {
+                            writeln!(
+                                output,
+                                "#[doc = \"This struct has a flexible array member `{}`, allocate \
+                                 it with `{}::alloc`.\"]",
+                                field_declare.name().unwrap_or_default(),
+                                escape(name)
+                            )?;
+                        }
+                        self.write_record(
+                            output,
+                            "struct",
+                            name,
+                            &struct_declare.fields,
+                            &struct_declare.attributes,
+                        )?;
+                        if let Some(field_declare) = flexible_array_member {
+                            self.write_flexible_array_alloc(output, name, field_declare)?;
+                        }
+                    }
+                    TypeDeclare::Union(union_declare) => self.write_record(
+                        output,
+                        "union",
+                        name,
+                        &union_declare.fields,
+                        &union_declare.attributes,
+                    )?,
+                    TypeDeclare::Class(_) => {
+                        writeln!(output, "// C++ class {} has no C ABI representation", name)?;
+                        writeln!(output)?;
+                        continue;
+                    }
+                    TypeDeclare::Typedef(typedef_declare) => {
+                        writeln!(
+                            output,
+                            "pub type {} = {};",
+                            escape(name),
+                            self.rust_type(&typedef_declare.underlying_type)
+                        )?;
+                        writeln!(output)?;
+                    }
+                    TypeDeclare::FunctionPointer(function_pointer_declare) => {
+                        writeln!(
+                            output,
+                            "pub type {} = Option<{}>;",
+                            escape(name),
+                            self.function_pointer_declare_type(function_pointer_declare)
+                        )?;
+                        writeln!(output)?;
+                    }
+                }
+            }
+            if let Some(typedef_name) = typedef_name.filter(|&typedef_name| typedef_name != name) {
+                writeln!(
+                    output,
+                    "pub type {} = {};",
+                    escape(typedef_name),
+                    escape(name)
+                )?;
+                writeln!(output)?;
+            }
+        }
+        for constant in self.source_file.constants.iter() {
+            self.write_constant(output, constant)?;
+        }
+        for constexpr_declare in self.source_file.constexpr_declares.iter() {
+            self.write_constexpr(output, constexpr_declare)?;
+        }
+        // Macros defined without a body, like include guards, only matter to `#ifdef`.
+        let macro_definitions = self
+            .source_file
+            .macro_definitions
+            .iter()
+            .filter(|macro_definition| !macro_definition.body_tokens.is_empty())
+            .collect::<Vec<_>>();
+        for macro_definition in macro_definitions.iter() {
+            self.write_macro_definition(output, macro_definition)?;
+        }
+        if !self.source_file.constants.is_empty()
+            || !self.source_file.constexpr_declares.is_empty()
+            || !macro_definitions.is_empty()
+        {
+            writeln!(output)?;
+        }
+        let mut abis = Vec::new();
+        for function_declare in self.source_file.function_declares.iter() {
+            let abi = abi_name(function_declare.calling_convention);
+            if !abis.contains(&abi) {
+                abis.push(abi);
+            }
+        }
+        for (index, abi) in abis.iter().enumerate() {
+            if index > 0 {
+                writeln!(output)?;
+            }
+            let function_declares = self
+                .source_file
+                .function_declares
+                .iter()
+                .filter(|function_declare| abi_name(function_declare.calling_convention) == *abi)
+                .collect::<Vec<_>>();
+            if let Some(dll_name) = &self.dll_name {
+                if function_declares
+                    .iter()
+                    .any(|function_declare| function_declare.declspec == Some(DeclSpec::DllImport))
+                {
+                    writeln!(output, "#[link(name = \"{}\")]", dll_name)?;
+                }
+            }
+            writeln!(output, "extern \"{}\" {{", abi)?;
+            for function_declare in function_declares {
+                self.write_function(output, function_declare)?;
+            }
+            writeln!(output, "}}")?;
+        }
+        if !self.source_file.macro_functions.is_empty() {
+            if !abis.is_empty() {
+                writeln!(output)?;
+            }
+            writeln!(output, "// Function-like macros have no FFI equivalent:")?;
+            for macro_function in self.source_file.macro_functions.iter() {
+                writeln!(
+                    output,
+                    "// #define {}({}) {}",
+                    macro_function.name,
+                    macro_function.parameter_names.join(", "),
+                    macro_function.body_tokens.join(" ")
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn has_definition(&self, name: &str) -> bool {
+        self.source_file.type_declares.iter().any(|type_declare| {
+            type_declare.is_definition()
+                && (type_declare.name() == Some(name) || type_declare.typedef_name() == Some(name))
+        })
+    }
+
+    /// An empty enum cannot be instantiated, so the type is only usable behind a pointer.
+    fn write_opaque(&self, output: &mut String, name: &str) -> fmt::Result {
+        writeln!(output, "pub enum {} {{}} // opaque", escape(name))?;
+        writeln!(output)
+    }
+
+    fn write_constant(&self, output: &mut String, constant: &ConstantDeclare) -> fmt::Result {
+        let rust_type = constant
+            .constant_type
+            .as_ref()
+            .and_then(|constant_type| {
+                integer_type_name(constant_type.resolve_canonical().type_kind)
+            })
+            .unwrap_or("i32");
+        let value = if rust_type.starts_with('i') {
+            constant.value.signed.to_string()
+        } else {
+            constant.value.unsigned.to_string()
+        };
+        writeln!(
+            output,
+            "pub const {}: {} = {};",
+            escape(&constant.name),
+            rust_type,
+            value
+        )
+    }
+
+    fn write_macro_definition(
+        &self,
+        output: &mut String,
+        macro_definition: &MacroDefinition,
+    ) -> fmt::Result {
+        if let Some(value) = macro_definition.evaluate_unsigned() {
+            return writeln!(
+                output,
+                "pub const {}: u64 = {};",
+                escape(&macro_definition.name),
+                value
+            );
+        }
+        match macro_definition.evaluate() {
+            Some(value) if value >= 0 => writeln!(
+                output,
+                "pub const {}: usize = {};",
+                escape(&macro_definition.name),
+                value
+            ),
+            Some(value) => writeln!(
+                output,
+                "pub const {}: i64 = {};",
+                escape(&macro_definition.name),
+                value
+            ),
+            None => writeln!(
+                output,
+                "// could not evaluate: #define {} {}",
+                macro_definition.name,
+                macro_definition.body_tokens.join(" ")
+            ),
+        }
+    }
+
+    fn write_constexpr(
+        &self,
+        output: &mut String,
+        constexpr_declare: &ConstexprDeclare,
+    ) -> fmt::Result {
+        let type_kind = constexpr_declare.value_type.resolve_canonical().type_kind;
+        let (rust_type, value) = match constexpr_declare.value {
+            Some(ConstexprValue::Bool(value)) => ("bool", value.to_string()),
+            Some(ConstexprValue::Integer(value)) => match integer_type_name(type_kind) {
+                Some(rust_type) if rust_type.starts_with('u') => {
+                    (rust_type, (value as u64).to_string())
+                }
+                rust_type => (rust_type.unwrap_or("i64"), value.to_string()),
+            },
+            Some(ConstexprValue::Float(value)) if value.is_finite() => {
+                let rust_type = if type_kind == TypeKind::Float {
+                    "f32"
+                } else {
+                    "f64"
+                };
+                (rust_type, format!("{:?}", value))
+            }
+            _ => {
+                return writeln!(
+                    output,
+                    "// constexpr {} has no value Rust can represent",
+                    constexpr_declare.name
+                )
+            }
+        };
+        writeln!(
+            output,
+            "pub const {}: {} = {};",
+            escape(&constexpr_declare.name),
+            rust_type,
+            value
+        )
+    }
+
+    /// A Rust enum cannot have two variants with the same discriminant, so an enum with aliased
+    /// constants becomes a newtype around its integer type with one constant per C constant.
+    fn write_enum_newtype(
+        &self,
+        output: &mut String,
+        name: &str,
+        repr: &str,
+        enum_declare: &EnumDeclare,
+    ) -> fmt::Result {
+        writeln!(output, "#[repr(transparent)]")?;
+        writeln!(output, "#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]")?;
+        writeln!(output, "pub struct {}(pub {});", escape(name), repr)?;
+        writeln!(output)?;
+        // Scoped enum constants are only reachable through the enum, as in C++.
+        let indent = if enum_declare.is_scoped {
+            writeln!(output, "impl {} {{", escape(name))?;
+            "    "
+        } else {
+            ""
+        };
+        for constant in enum_declare.constants.iter() {
+            let value = match &constant.constant_value {
+                Some(value) if repr.starts_with('i') => value.signed.to_string(),
+                Some(value) => value.unsigned.to_string(),
+                None => continue,
+            };
+            if let Some(comment) = &constant.comment {
+                for line in comment.lines() {
+                    writeln!(output, "{}/// {}", indent, line)?;
+                }
+            }
+            writeln!(
+                output,
+                "{}pub const {}: {} = {}({});",
+                indent,
+                escape(&constant.name),
+                escape(name),
+                escape(name),
+                value
+            )?;
+        }
+        if enum_declare.is_scoped {
+            writeln!(output, "}}")?;
+        }
+        writeln!(output)
+    }
+
+    fn write_enum_bitflags(
+        &self,
+        output: &mut String,
+        name: &str,
+        repr: &str,
+        enum_declare: &EnumDeclare,
+    ) -> fmt::Result {
+        writeln!(output, "bitflags::bitflags! {{")?;
+        writeln!(output, "    #[repr(transparent)]")?;
+        writeln!(
+            output,
+            "    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]"
+        )?;
+        writeln!(output, "    pub struct {}: {} {{", escape(name), repr)?;
+        for constant in enum_declare.constants.iter() {
+            let value = match &constant.constant_value {
+                Some(value) if repr.starts_with('i') => value.signed.to_string(),
+                Some(value) => value.unsigned.to_string(),
+                None => continue,
+            };
+            if let Some(comment) = &constant.comment {
+                for line in comment.lines() {
+                    writeln!(output, "        /// {}", line)?;
+                }
+            }
+            writeln!(
+                output,
+                "        const {} = {};",
+                escape(&constant.name),
+                value
+            )?;
+        }
+        writeln!(output, "    }}")?;
+        writeln!(output, "}}")?;
+        writeln!(output)?;
+        if !enum_declare.is_scoped {
+            for constant in enum_declare.constants.iter() {
+                writeln!(
+                    output,
+                    "pub const {}: {} = {}::{};",
+                    escape(&constant.name),
+                    escape(name),
+                    escape(name),
+                    escape(&constant.name)
+                )?;
+            }
+            writeln!(output)?;
+        }
+        Ok(())
+    }
+
+    fn write_enum(
+        &self,
+        output: &mut String,
+        name: &str,
+        enum_declare: &EnumDeclare,
+    ) -> fmt::Result {
+        let repr = enum_declare.rust_repr().unwrap_or("u32");
+        if enum_declare.constants.is_empty() {
+            writeln!(output, "pub type {} = {};", escape(name), repr)?;
+            return writeln!(output);
+        }
+        if self.use_bitflags && enum_declare.is_flags() {
+            return self.write_enum_bitflags(output, name, repr, enum_declare);
+        }
+        if enum_declare.has_duplicate_values() {
+            return self.write_enum_newtype(output, name, repr, enum_declare);
+        }
+        writeln!(output, "#[repr({})]", repr)?;
+        writeln!(output, "#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]")?;
+        writeln!(output, "pub enum {} {{", escape(name))?;
+        for constant in enum_declare.constants.iter() {
+            if let Some(comment) = &constant.comment {
+                for line in comment.lines() {
+                    writeln!(output, "    /// {}", line)?;
+                }
+            }
+            match &constant.constant_value {
+                Some(value) if repr.starts_with('i') => {
+                    writeln!(output, "    {} = {},", escape(&constant.name), value.signed)?
+                }
+                Some(value) => writeln!(
+                    output,
+                    "    {} = {},",
+                    escape(&constant.name),
+                    value.unsigned
+                )?,
+                None => writeln!(output, "    {},", escape(&constant.name))?,
+            }
+        }
+        writeln!(output, "}}")?;
+        writeln!(output)?;
+        if !enum_declare.is_scoped {
+            for constant in enum_declare.constants.iter() {
+                writeln!(
+                    output,
+                    "pub const {}: {} = {}::{};",
+                    escape(&constant.name),
+                    escape(name),
+                    escape(name),
+                    escape(&constant.name)
+                )?;
+            }
+            writeln!(output)?;
+        }
+        Ok(())
+    }
+
+    fn write_record(
+        &self,
+        output: &mut String,
+        keyword: &str,
+        name: &str,
+        members: &[MemberDeclare],
+        attributes: &[Attribute],
+    ) -> fmt::Result {
+        let mut fields = Vec::new();
+        self.collect_fields(output, keyword, name, members, &mut fields)?;
+
+        let keyword = if fields.is_empty() { "struct" } else { keyword };
+        let has_flexible_array_member = members.iter().any(|member| match member {
+            MemberDeclare::Field(field_declare) => field_declare.is_flexible_array_member(),
+            _ => false,
+        });
+        write_deprecated(output, "", attributes)?;
+        if attributes.contains(&Attribute::Packed) {
+            writeln!(output, "#[repr(C, packed)]")?;
+        } else {
+            writeln!(output, "#[repr(C)]")?;
+        }
+        // Atomics are neither `Copy` nor `Clone`, and a copy of a struct with a flexible array
+        // member would leave its elements behind.
+        if !has_flexible_array_member
+            && !fields
+                .iter()
+                .any(|field| field.rust_type.starts_with("std::sync::atomic::"))
+        {
+            writeln!(output, "#[derive(Copy, Clone)]")?;
+        }
+        writeln!(output, "pub {} {} {{", keyword, escape(name))?;
+        let type_mapper = self.type_mapper.clone().unwrap_or_default();
+        let mut bit_field_runs = Vec::new();
+        let mut bit_field_run: Option<BitFieldRun> = None;
+        for (index, field) in fields.iter().enumerate() {
+            if let Some((bit_width, field_type)) = field.bit_field {
+                let unit_bits = storage_unit_bits(&type_mapper, field_type);
+                // Every member of a union starts at offset 0, so each bit-field gets a unit.
+                let run = match bit_field_run.take() {
+                    Some(run)
+                        if keyword == "struct"
+                            && bit_width != 0
+                            && run.unit_bits == unit_bits
+                            && run.used_bits + bit_width <= unit_bits =>
+                    {
+                        run
+                    }
+                    run => {
+                        if let Some(run) = run {
+                            run.write_field(output)?;
+                            bit_field_runs.push(run);
+                        }
+                        // A zero-width bit-field only closes the unit before it.
+                        if bit_width == 0 {
+                            continue;
+                        }
+                        BitFieldRun::new(bit_field_runs.len(), unit_bits)
+                    }
+                };
+                bit_field_run = Some(run.push(&field.name, bit_width, field_type));
+                continue;
+            }
+            if let Some(run) = bit_field_run.take() {
+                run.write_field(output)?;
+                bit_field_runs.push(run);
+            }
+            if has_flexible_array_member && index == fields.len() - 1 {
+                writeln!(
+                    output,
+                    "    // Flexible array member. Safety: the elements follow the struct, which \
+                     has to be allocated with room for them."
+                )?;
+            }
+            write_deprecated(output, "    ", field.attributes)?;
+            writeln!(output, "    pub {}: {},", field.name, field.rust_type)?;
+        }
+        if let Some(run) = bit_field_run.take() {
+            run.write_field(output)?;
+            bit_field_runs.push(run);
+        }
+        writeln!(output, "}}")?;
+        writeln!(output)?;
+        let accessors = bit_field_runs
+            .iter()
+            .flat_map(|run| run.accessors(keyword == "union"))
+            .collect::<Vec<_>>();
+        if !accessors.is_empty() {
+            writeln!(output, "impl {} {{", escape(name))?;
+            write!(output, "{}", accessors.join("\n"))?;
+            writeln!(output, "}}")?;
+            writeln!(output)?;
+        }
+        Ok(())
+    }
+
+    /// Heap allocation for a struct ending in a flexible array member, which needs room for the
+    /// elements past the end of the struct and can therefore neither live on the stack nor in a
+    /// `Box`.
+    fn write_flexible_array_alloc(
+        &self,
+        output: &mut String,
+        name: &str,
+        field_declare: &FieldDeclare,
+    ) -> fmt::Result {
+        let element_type = field_declare
+            .field_type
+            .as_ref()
+            .map(|field_type| self.element_type(field_type))
+            .unwrap_or_else(|| "u8".to_owned());
+        let field_name = field_declare.name().unwrap_or_default();
+        writeln!(output, "impl {} {{", escape(name))?;
+        writeln!(
+            output,
+            "    /// The layout of the struct followed by `len` elements of `{}`.",
+            field_name
+        )?;
+        writeln!(
+            output,
+            "    pub fn layout(len: usize) -> std::alloc::Layout {{"
+        )?;
+        writeln!(
+            output,
+            "        let size = std::mem::size_of::<Self>() + len * std::mem::size_of::<{}>();",
+            element_type
+        )?;
+        writeln!(
+            output,
+            "        std::alloc::Layout::from_size_align(size, std::mem::align_of::<Self>()).unwrap()"
+        )?;
+        writeln!(output, "    }}")?;
+        writeln!(output)?;
+        writeln!(
+            output,
+            "    /// Allocates a zeroed struct with room for `len` elements of `{}`, release it \
+             with `dealloc`.",
+            field_name
+        )?;
+        writeln!(output, "    pub fn alloc(len: usize) -> *mut Self {{")?;
+        writeln!(output, "        let layout = Self::layout(len);")?;
+        writeln!(
+            output,
+            "        let ptr = unsafe {{ std::alloc::alloc_zeroed(layout) }} as *mut Self;"
+        )?;
+        writeln!(output, "        if ptr.is_null() {{")?;
+        writeln!(
+            output,
+            "            std::alloc::handle_alloc_error(layout);"
+        )?;
+        writeln!(output, "        }}")?;
+        writeln!(output, "        ptr")?;
+        writeln!(output, "    }}")?;
+        writeln!(output)?;
+        writeln!(output, "    /// # Safety")?;
+        writeln!(output, "    ///")?;
+        writeln!(
+            output,
+            "    /// `ptr` must have been returned by `alloc` with the same `len`."
+        )?;
+        writeln!(
+            output,
+            "    pub unsafe fn dealloc(ptr: *mut Self, len: usize) {{"
+        )?;
+        writeln!(
+            output,
+            "        std::alloc::dealloc(ptr as *mut u8, Self::layout(len));"
+        )?;
+        writeln!(output, "    }}")?;
+        writeln!(output, "}}")?;
+        writeln!(output)
+    }
+
+    /// Writes the records nested in `members` and collects the fields of the record itself. The
+    /// fields of an anonymous struct inside a struct are hoisted into the parent, as C lets them
+    /// be accessed from there.
+    fn collect_fields<'m>(
+        &self,
+        output: &mut String,
+        keyword: &str,
+        name: &str,
+        members: &'m [MemberDeclare],
+        fields: &mut Vec<RustField<'m>>,
+    ) -> fmt::Result {
+        let mut pending_anonymous = None;
+        let mut hoisted_index = None;
+        for (index, member) in members.iter().enumerate() {
+            let nested_record = match member {
+                MemberDeclare::Field(_) if hoisted_index == Some(index) => continue,
+                MemberDeclare::Field(field_declare) => {
+                    let field_type = match &field_declare.field_type {
+                        Some(field_type) => field_type,
+                        None => continue,
+                    };
+                    let rust_type = match pending_anonymous.take() {
+                        Some(anonymous_name) if field_type.is_anonymous_record() => anonymous_name,
+                        Some(anonymous_name) => {
+                            fields.push(RustField::anonymous(fields.len(), anonymous_name));
+                            self.rust_type(field_type)
+                        }
+                        None => self.rust_type(field_type),
+                    };
+                    let field_name = match &field_declare.name {
+                        Some(field_name) => escape(field_name),
+                        None if field_declare.is_anonymous => format!("__anon_{}", fields.len()),
+                        None => format!("__unnamed_{}", fields.len()),
+                    };
+                    fields.push(RustField {
+                        name: field_name,
+                        rust_type,
+                        bit_field: field_declare
+                            .bit_width
+                            .map(|bit_width| (bit_width, field_type)),
+                        attributes: &field_declare.attributes,
+                    });
+                    continue;
+                }
+                MemberDeclare::Struct(struct_declare) => (
+                    "struct",
+                    struct_declare.name(),
+                    &struct_declare.fields,
+                    struct_declare.attributes.as_slice(),
+                ),
+                MemberDeclare::Union(union_declare) => (
+                    "union",
+                    union_declare.name(),
+                    &union_declare.fields,
+                    union_declare.attributes.as_slice(),
+                ),
+                // Like a top level one, an enum declared in a record body is an item of its own.
+                MemberDeclare::Enum(enum_declare) => {
+                    if let Some(enum_name) = enum_declare.name() {
+                        self.write_enum(output, enum_name, enum_declare)?;
+                    }
+                    continue;
+                }
+            };
+            if let Some(anonymous_name) = pending_anonymous.take() {
+                fields.push(RustField::anonymous(fields.len(), anonymous_name));
+            }
+            let (nested_keyword, nested_name, nested_members, nested_attributes) = nested_record;
+            let is_anonymous_member = matches!(
+                members.get(index + 1),
+                Some(MemberDeclare::Field(field_declare)) if field_declare.is_anonymous
+            );
+            if keyword == "struct" && nested_keyword == "struct" && is_anonymous_member {
+                self.collect_fields(output, keyword, name, nested_members, fields)?;
+                hoisted_index = Some(index + 1);
+                continue;
+            }
+            let nested_name = match nested_name {
+                Some(nested_name) => nested_name.to_owned(),
+                None => {
+                    let anonymous_name = format!("{}__anon_{}", name, fields.len());
+                    pending_anonymous = Some(anonymous_name.to_owned());
+                    anonymous_name
+                }
+            };
+            self.write_record(
+                output,
+                nested_keyword,
+                &nested_name,
+                nested_members,
+                nested_attributes,
+            )?;
+        }
+        if let Some(anonymous_name) = pending_anonymous.take() {
+            fields.push(RustField::anonymous(fields.len(), anonymous_name));
+        }
+        Ok(())
+    }
+
+    fn write_function(
+        &self,
+        output: &mut String,
+        function_declare: &FunctionDeclare,
+    ) -> fmt::Result {
+        let parameters = function_declare
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(index, parameter)| match parameter {
+                FunctionParameter::Named(parameter_declare) => format!(
+                    "{}: {}",
+                    escape(&parameter_declare.binding_name(index)),
+                    self.parameter_type(parameter_declare)
+                ),
+                FunctionParameter::Variadic(_) => "...".to_owned(),
+            })
+            .collect::<Vec<_>>();
+        self.write_safety_comment(output, function_declare)?;
+        write_deprecated(output, "    ", &function_declare.attributes)?;
+        write!(
+            output,
+            "    pub fn {}({})",
+            escape(&function_declare.function_name),
+            parameters.join(", ")
+        )?;
+        if function_declare.is_noreturn() {
+            write!(output, " -> !")?;
+        } else if let Some(return_type) = function_declare
+            .return_type
+            .as_ref()
+            .filter(|return_type| !return_type.is_void())
+        {
+            write!(output, " -> {}", self.rust_type(return_type))?;
+        }
+        writeln!(output, ";")
+    }
+
+    /// Spells out what the caller has to guarantee about the pointers a function takes or
+    /// returns, as far as `MemoryModel` can tell.
+    fn write_safety_comment(
+        &self,
+        output: &mut String,
+        function_declare: &FunctionDeclare,
+    ) -> fmt::Result {
+        let quoted_names = |parameter_declares: Vec<&ParameterDeclare>| {
+            parameter_declares
+                .iter()
+                .map(|parameter_declare| {
+                    format!(
+                        "`{}`",
+                        function_declare.parameter_binding_name(parameter_declare)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match MemoryModel::classify(function_declare, &self.ownership_hints) {
+            FunctionOwnership::Allocator => {
+                let deallocator = MemoryModel::matching_deallocator(
+                    function_declare,
+                    &self.source_file.function_declares,
+                    &self.ownership_hints,
+                );
+                match deallocator {
+                    Some(deallocator) => writeln!(
+                        output,
+                        "    // SAFETY: the caller owns the returned object and must release it \
+                         with `{}`.",
+                        deallocator.function_name
+                    ),
+                    None => writeln!(
+                        output,
+                        "    // SAFETY: the caller owns the returned object and must release it."
+                    ),
+                }
+            }
+            FunctionOwnership::Deallocator => {
+                let owned_parameter = MemoryModel::owned_parameter(function_declare).unwrap();
+                writeln!(
+                    output,
+                    "    // SAFETY: takes ownership of `{}`, which must not be used afterwards.",
+                    function_declare.parameter_binding_name(owned_parameter)
+                )
+            }
+            FunctionOwnership::Transformer => writeln!(
+                output,
+                "    // SAFETY: {} must be valid for writes for the duration of the call.",
+                quoted_names(MemoryModel::mutable_parameters(function_declare))
+            ),
+            FunctionOwnership::Observer => {
+                let pointer_parameters =
+                    MemoryModel::pointer_parameters(function_declare).collect::<Vec<_>>();
+                if !pointer_parameters.is_empty() {
+                    return writeln!(
+                        output,
+                        "    // SAFETY: {} must be valid for reads for the duration of the call.",
+                        quoted_names(pointer_parameters)
+                    );
+                }
+                // Function pointers and records passed by value that hold pointers.
+                let unsafe_parameters = function_declare
+                    .parameters
+                    .iter()
+                    .filter_map(|parameter| match parameter {
+                        FunctionParameter::Named(parameter_declare) => Some(parameter_declare),
+                        FunctionParameter::Variadic(_) => None,
+                    })
+                    .filter(|parameter_declare| {
+                        parameter_declare
+                            .parameter_type
+                            .as_ref()
+                            .map(|parameter_type| {
+                                self.source_file.type_requires_unsafe(parameter_type)
+                            })
+                            .unwrap_or(false)
+                    })
+                    .collect::<Vec<_>>();
+                if unsafe_parameters.is_empty() {
+                    return Ok(());
+                }
+                writeln!(
+                    output,
+                    "    // SAFETY: the pointers held by {} must be valid for the duration of the \
+                     call.",
+                    quoted_names(unsafe_parameters)
+                )
+            }
+        }
+    }
+
+    fn rust_type(&self, t: &Type) -> String {
+        if let Some(value_type_name) = t.atomic_value_type_name() {
+            return self.atomic_type(value_type_name);
+        }
+        if let Some(inner_type) = t.elaborated_inner() {
+            return self.rust_type(inner_type);
+        }
+        match t.type_kind {
+            TypeKind::Pointer => match &t.pointee_type {
+                Some(pointee_type) => self.pointer_type(pointee_type),
+                None => "*mut std::os::raw::c_void".to_owned(),
+            },
+            TypeKind::ConstantArray | TypeKind::IncompleteArray => {
+                format!("[{}; {}]", self.element_type(t), t.array_size.unwrap_or(0))
+            }
+            TypeKind::VariableArray => format!("*mut {}", self.element_type(t)),
+            // A block is an Objective-C object with its own calling convention, not a function.
+            TypeKind::BlockPointer => format!(
+                "/* block pointers cannot be represented in Rust: {} */ *mut std::os::raw::c_void",
+                t.type_name
+            ),
+            TypeKind::FunctionPrototype | TypeKind::FunctionNoPrototype => {
+                self.function_pointer_type(t)
+            }
+            TypeKind::Typedef | TypeKind::Elaborated | TypeKind::Record | TypeKind::Enum => {
+                let name = t.tagless_name();
+                if self.declared_names.contains(name) {
+                    escape(name)
+                } else if let Some(canonical_type) = &t.canonical_type {
+                    self.rust_type(canonical_type)
+                } else {
+                    escape(name)
+                }
+            }
+            _ => self
+                .builtin_type(t)
+                .or_else(|| t.rust_type_name().map(str::to_owned))
+                .unwrap_or_else(|| "std::os::raw::c_void".to_owned()),
+        }
+    }
+
+    /// The builtin types whose size depends on the target, sized for the one of `type_mapper`.
+    fn builtin_type(&self, t: &Type) -> Option<String> {
+        let type_mapper = self.type_mapper.as_ref()?;
+        let prefix = match t.type_kind {
+            TypeKind::Long => "i",
+            TypeKind::ULong => "u",
+            // `wchar_t` is unsigned where it is 16 bits wide, i.e. on Windows.
+            TypeKind::WChar => match type_mapper.map_type_size(TypeKind::WChar)? {
+                2 => "u",
+                _ => "i",
+            },
+            _ => return None,
+        };
+        Some(format!(
+            "{}{}",
+            prefix,
+            u32::from(type_mapper.map_type_size(t.type_kind)?) * 8
+        ))
+    }
+
+    fn atomic_type(&self, value_type_name: &str) -> String {
+        if let Some(atomic_type_name) = atomic_type_name(value_type_name) {
+            atomic_type_name.to_owned()
+        } else if value_type_name.ends_with('*') {
+            "std::sync::atomic::AtomicPtr<std::os::raw::c_void>".to_owned()
+        } else {
+            let value_type_name = value_type_name
+                .trim_start_matches("struct ")
+                .trim_start_matches("union ")
+                .trim_start_matches("enum ");
+            format!("/* _Atomic */ {}", escape(value_type_name))
+        }
+    }
+
+    fn pointer_type(&self, pointee_type: &Type) -> String {
+        let resolved_type = pointee_type.resolve_canonical();
+        if resolved_type.function_proto.is_some() {
+            format!("Option<{}>", self.function_pointer_type(resolved_type))
+        } else {
+            let mutability = if pointee_type.is_const {
+                "const"
+            } else {
+                "mut"
+            };
+            let pointee = if resolved_type.is_void() {
+                "std::os::raw::c_void".to_owned()
+            } else {
+                self.rust_type(pointee_type)
+            };
+            format!("*{} {}", mutability, pointee)
+        }
+    }
+
+    /// An array parameter is passed as a pointer to its first element, never by value.
+    fn parameter_type(&self, parameter_declare: &ParameterDeclare) -> String {
+        match &parameter_declare.parameter_type {
+            Some(parameter_type) if parameter_declare.is_array_parameter() => {
+                let element_type = parameter_type.array_element_type.as_ref().or_else(|| {
+                    parameter_type
+                        .resolve_canonical()
+                        .array_element_type
+                        .as_ref()
+                });
+                match element_type {
+                    Some(element_type) => self.pointer_type(element_type),
+                    None => self.rust_type(parameter_type),
+                }
+            }
+            Some(parameter_type) => self.rust_type(parameter_type),
+            None => "std::os::raw::c_void".to_owned(),
+        }
+    }
+
+    fn element_type(&self, t: &Type) -> String {
+        t.array_element_type
+            .as_ref()
+            .map(|element_type| self.rust_type(element_type))
+            .unwrap_or_else(|| "u8".to_owned())
+    }
+
+    fn function_pointer_declare_type(
+        &self,
+        function_pointer_declare: &FunctionPointerTypeDeclare,
+    ) -> String {
+        let mut parameter_types = function_pointer_declare
+            .parameter_types
+            .iter()
+            .map(|parameter_type| self.rust_type(parameter_type))
+            .collect::<Vec<_>>();
+        if function_pointer_declare.is_variadic {
+            parameter_types.push("...".to_owned());
+        }
+        let mut function_type = format!("unsafe extern \"C\" fn({})", parameter_types.join(", "));
+        if !function_pointer_declare.return_type.is_void() {
+            function_type += " -> ";
+            function_type += &self.rust_type(&function_pointer_declare.return_type);
+        }
+        function_type
+    }
+
+    fn function_pointer_type(&self, t: &Type) -> String {
+        let function_proto = match &t.function_proto {
+            Some(function_proto) => function_proto,
+            None => return "*mut std::os::raw::c_void".to_owned(),
+        };
+        let parameter_types = function_proto
+            .parameter_types
+            .iter()
+            .map(|parameter_type| self.rust_type(parameter_type))
+            .collect::<Vec<_>>();
+        let mut function_type = format!("unsafe extern \"C\" fn({})", parameter_types.join(", "));
+        if !function_proto.return_type.is_void() {
+            function_type += " -> ";
+            function_type += &self.rust_type(&function_proto.return_type);
+        }
+        function_type
+    }
+}
+
+fn write_deprecated(output: &mut String, indent: &str, attributes: &[Attribute]) -> fmt::Result {
+    for attribute in attributes.iter() {
+        if let Attribute::Deprecated { message } = attribute {
+            return match message {
+                Some(message) => writeln!(output, "{}#[deprecated(note = {:?})]", indent, message),
+                None => writeln!(output, "{}#[deprecated]", indent),
+            };
+        }
+    }
+    Ok(())
+}
+
+fn abi_name(calling_convention: Option<CallingConvention>) -> &'static str {
+    match calling_convention {
+        Some(CallingConvention::Stdcall) => "system",
+        Some(CallingConvention::Fastcall) => "fastcall",
+        Some(CallingConvention::Thiscall) => "thiscall",
+        Some(CallingConvention::Vectorcall) => "vectorcall",
+        Some(CallingConvention::Win64) => "win64",
+        Some(CallingConvention::SysV64) => "sysv64",
+        Some(CallingConvention::Aapcs) => "aapcs",
+        _ => "C",
+    }
+}
+
+fn atomic_type_name(value_type_name: &str) -> Option<&'static str> {
+    match value_type_name {
+        "_Bool" | "bool" => Some("std::sync::atomic::AtomicBool"),
+        "char" | "signed char" => Some("std::sync::atomic::AtomicI8"),
+        "unsigned char" => Some("std::sync::atomic::AtomicU8"),
+        "short" => Some("std::sync::atomic::AtomicI16"),
+        "unsigned short" => Some("std::sync::atomic::AtomicU16"),
+        "int" => Some("std::sync::atomic::AtomicI32"),
+        "unsigned int" => Some("std::sync::atomic::AtomicU32"),
+        "long" => Some("std::sync::atomic::AtomicIsize"),
+        "unsigned long" => Some("std::sync::atomic::AtomicUsize"),
+        "long long" => Some("std::sync::atomic::AtomicI64"),
+        "unsigned long long" => Some("std::sync::atomic::AtomicU64"),
+        _ => None,
+    }
+}
+
+pub(crate) fn integer_type_name(kind: TypeKind) -> Option<&'static str> {
+    match kind {
+        TypeKind::CharS | TypeKind::SChar => Some("i8"),
+        TypeKind::CharU | TypeKind::UChar | TypeKind::Bool => Some("u8"),
+        TypeKind::Short => Some("i16"),
+        TypeKind::UShort => Some("u16"),
+        TypeKind::Int => Some("i32"),
+        TypeKind::UInt => Some("u32"),
+        TypeKind::Long | TypeKind::LongLong => Some("i64"),
+        TypeKind::ULong | TypeKind::ULongLong => Some("u64"),
+        _ => None,
+    }
+}
+
+/// A field of a generated record, collected before the record is written so that bit-fields can
+/// be packed into storage units.
+struct RustField<'m> {
+    name: String,
+    rust_type: String,
+    /// The width and C type of a bit-field.
+    bit_field: Option<(u32, &'m Type)>,
+    attributes: &'m [Attribute],
+}
+
+impl RustField<'_> {
+    /// The field holding an anonymous record, named after its position among the fields.
+    fn anonymous(index: usize, rust_type: String) -> Self {
+        Self {
+            name: format!("__anon_{}", index),
+            rust_type,
+            bit_field: None,
+            attributes: &[],
+        }
+    }
+}
+
+/// Adjacent bit-fields sharing one storage unit. Rust has no bit-fields, so the unit becomes an
+/// unsigned integer field of its size and each bit-field a getter and setter on the record.
+/// A new unit is started wherever the type size changes or the next bit-field does not fit,
+/// which gives the C layout for the common case of bit-fields of one type with no gaps.
+struct BitFieldRun {
+    storage_name: String,
+    unit_bits: u32,
+    used_bits: u32,
+    /// The name, width, offset in the unit and Rust type of each named bit-field.
+    bit_fields: Vec<(String, u32, u32, String)>,
+}
+
+impl BitFieldRun {
+    fn new(index: usize, unit_bits: u32) -> Self {
+        Self {
+            storage_name: format!("__bitfield_{}", index),
+            unit_bits,
+            used_bits: 0,
+            bit_fields: Vec::new(),
+        }
+    }
+
+    fn push(mut self, field_name: &str, bit_width: u32, field_type: &Type) -> Self {
+        // An unnamed bit-field only takes up its bits.
+        if !field_name.starts_with("__unnamed_") {
+            let kind = field_type.resolve_canonical().type_kind;
+            let value_type = if kind == TypeKind::Bool {
+                "bool".to_owned()
+            } else {
+                let is_signed = integer_type_name(kind)
+                    .map(|rust_type| rust_type.starts_with('i'))
+                    .unwrap_or(false);
+                format!("{}{}", if is_signed { "i" } else { "u" }, self.unit_bits)
+            };
+            self.bit_fields
+                .push((field_name.to_owned(), bit_width, self.used_bits, value_type));
+        }
+        self.used_bits += bit_width;
+        self
+    }
+
+    fn write_field(&self, output: &mut String) -> fmt::Result {
+        let bit_fields = self
+            .bit_fields
+            .iter()
+            .map(|(field_name, bit_width, _, _)| format!("{}: {} bits", field_name, bit_width))
+            .collect::<Vec<_>>();
+        if !bit_fields.is_empty() {
+            writeln!(output, "    // bit-fields {}", bit_fields.join(", "))?;
+        }
+        writeln!(
+            output,
+            "    pub {}: u{},",
+            self.storage_name, self.unit_bits
+        )
+    }
+
+    /// A getter and a setter for each bit-field. Reading a union field is unsafe, even if it is
+    /// a plain integer.
+    fn accessors(&self, is_union: bool) -> Vec<String> {
+        let storage = if is_union {
+            format!("unsafe {{ self.{} }}", self.storage_name)
+        } else {
+            format!("self.{}", self.storage_name)
+        };
+        let mut accessors = Vec::new();
+        for (field_name, bit_width, bit_offset, value_type) in self.bit_fields.iter() {
+            let mask = u128::MAX >> (128 - bit_width);
+            let value = if value_type == "bool" {
+                format!("({} >> {}) & 1 != 0", storage, bit_offset)
+            } else if value_type.starts_with('i') {
+                // Shifting the bit-field to the top of the unit and back sign extends it.
+                format!(
+                    "(({} << {}) as {}) >> {}",
+                    storage,
+                    self.unit_bits - bit_offset - bit_width,
+                    value_type,
+                    self.unit_bits - bit_width
+                )
+            } else {
+                format!("({} >> {}) & {:#x}", storage, bit_offset, mask)
+            };
+            accessors.push(format!(
+                "    pub fn {}(&self) -> {} {{\n        {}\n    }}\n",
+                field_name, value_type, value
+            ));
+            let value = format!(
+                "({} & !({:#x} << {})) | (((value as u{}) & {:#x}) << {})",
+                storage, mask, bit_offset, self.unit_bits, mask, bit_offset
+            );
+            accessors.push(format!(
+                "    pub fn set_{}(&mut self, value: {}) {{\n        self.{} = {};\n    }}\n",
+                field_name, value_type, self.storage_name, value
+            ));
+        }
+        accessors
+    }
+}
+
+fn escape(name: &str) -> String {
+    if RUST_KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_owned()
+    }
+}
@@ -0,0 +1,685 @@
+use super::storage_unit_bits;
+use crate::{
+    builtin_types::BuiltinTypeMapper, Attribute, ConstantDeclare, EntityVisitor,
+    EnumConstantDeclare, EnumDeclare, FunctionDeclare, FunctionParameter, MemberDeclare,
+    SourceFile, Type, TypeDeclaration, TypeDeclare,
+};
+use clang::{CallingConvention, TypeKind};
+use std::{
+    collections::HashSet,
+    fmt::{self, Write},
+};
+
+/// Keywords, plus the primitive names that Zig does not allow declarations to shadow.
+const ZIG_KEYWORDS: &[&str] = &[
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "linksection",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "try",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "volatile",
+    "while",
+    "type",
+    "void",
+    "bool",
+    "null",
+    "undefined",
+    "true",
+    "false",
+];
+
+/// Generates Zig declarations for the types, functions and variables of a `SourceFile`, for
+/// use without `@cImport`.
+pub struct ZigFfiGenerator<'a> {
+    source_file: &'a SourceFile,
+    declared_names: HashSet<&'a str>,
+    /// Types that are only forward declared, which Zig models as `opaque {}`.
+    opaque_names: HashSet<&'a str>,
+    builtin_types: BuiltinTypeMapper,
+}
+
+impl<'a> ZigFfiGenerator<'a> {
+    pub fn new(source_file: &'a SourceFile) -> Self {
+        let mut declared_names = HashSet::new();
+        for type_declare in source_file.type_declares.iter() {
+            declared_names.extend(type_declare.name());
+            declared_names.extend(type_declare.typedef_name());
+        }
+        let opaque_names = source_file
+            .type_declares
+            .iter()
+            .filter_map(|type_declare| type_declare.name())
+            .filter(|&name| {
+                !source_file.type_declares.iter().any(|type_declare| {
+                    type_declare.is_definition()
+                        && (type_declare.name() == Some(name)
+                            || type_declare.typedef_name() == Some(name))
+                })
+            })
+            .collect();
+        Self {
+            source_file,
+            declared_names,
+            opaque_names,
+            builtin_types: BuiltinTypeMapper::for_source_file(source_file),
+        }
+    }
+
+    pub fn generate(&self) -> String {
+        let mut output = String::new();
+        self.write_source_file(&mut output).unwrap();
+        output
+    }
+
+    fn write_source_file(&self, output: &mut String) -> fmt::Result {
+        let type_declares = self
+            .source_file
+            .topological_sort_types()
+            .unwrap_or_else(|_| self.source_file.type_declares.iter().collect());
+        let mut written_names = HashSet::new();
+        for type_declare in type_declares {
+            let (name, typedef_name) = match type_declare.name() {
+                Some(name) => (name, type_declare.typedef_name()),
+                None => match type_declare.typedef_name() {
+                    Some(typedef_name) => (typedef_name, None),
+                    None => continue,
+                },
+            };
+            if !type_declare.is_definition() {
+                // Zig has no forward declarations, an undefined type stays opaque.
+                if self.opaque_names.contains(name) && written_names.insert(name) {
+                    writeln!(output, "pub const {} = opaque {{}};", escape(name))?;
+                    writeln!(output)?;
+                }
+                continue;
+            }
+            if !written_names.insert(name) {
+                continue;
+            }
+            match type_declare {
+                TypeDeclare::Enum(enum_declare) => self.write_enum(output, name, enum_declare)?,
+                TypeDeclare::Struct(struct_declare) => self.write_record(
+                    output,
+                    "struct",
+                    name,
+                    &struct_declare.fields,
+                    &struct_declare.attributes,
+                )?,
+                TypeDeclare::Union(union_declare) => self.write_record(
+                    output,
+                    "union",
+                    name,
+                    &union_declare.fields,
+                    &union_declare.attributes,
+                )?,
+                TypeDeclare::Class(_) => {
+                    writeln!(output, "// C++ class {} has no C ABI representation", name)?;
+                    writeln!(output)?;
+                    continue;
+                }
+                TypeDeclare::Typedef(typedef_declare) => {
+                    writeln!(
+                        output,
+                        "pub const {} = {};",
+                        escape(name),
+                        self.zig_type(&typedef_declare.underlying_type)
+                    )?;
+                    writeln!(output)?;
+                }
+                TypeDeclare::FunctionPointer(function_pointer_declare) => {
+                    writeln!(
+                        output,
+                        "pub const {} = ?*const {};",
+                        escape(name),
+                        self.function_type(
+                            function_pointer_declare.parameter_types.iter(),
+                            function_pointer_declare.is_variadic,
+                            &function_pointer_declare.return_type,
+                        )
+                    )?;
+                    writeln!(output)?;
+                }
+            }
+            if let Some(typedef_name) = typedef_name.filter(|&typedef_name| typedef_name != name) {
+                writeln!(
+                    output,
+                    "pub const {} = {};",
+                    escape(typedef_name),
+                    escape(name)
+                )?;
+                writeln!(output)?;
+            }
+        }
+        for constant in self.source_file.constants.iter() {
+            self.write_constant(output, constant)?;
+        }
+        if !self.source_file.constants.is_empty() {
+            writeln!(output)?;
+        }
+        for variable_declare in self.source_file.variable_declares.iter() {
+            if let Some(variable_type) = &variable_declare.variable_type {
+                let keyword = if variable_type.is_const {
+                    "const"
+                } else {
+                    "var"
+                };
+                writeln!(
+                    output,
+                    "pub extern {} {}: {};",
+                    keyword,
+                    escape(&variable_declare.name),
+                    self.zig_type(variable_type)
+                )?;
+            }
+        }
+        if !self.source_file.variable_declares.is_empty() {
+            writeln!(output)?;
+        }
+        for function_declare in self.source_file.function_declares.iter() {
+            self.write_function(output, function_declare)?;
+        }
+        Ok(())
+    }
+
+    fn write_constant(&self, output: &mut String, constant: &ConstantDeclare) -> fmt::Result {
+        let zig_type = constant
+            .constant_type
+            .as_ref()
+            .map(|constant_type| self.zig_type(constant_type.resolve_canonical()))
+            .unwrap_or_else(|| "c_int".to_owned());
+        let value = if zig_type.starts_with("c_u") || zig_type.starts_with('u') {
+            constant.value.unsigned.to_string()
+        } else {
+            constant.value.signed.to_string()
+        };
+        writeln!(
+            output,
+            "pub const {}: {} = {};",
+            escape(&constant.name),
+            zig_type,
+            value
+        )
+    }
+
+    /// C enums become non-exhaustive Zig enums, so values outside the declared constants stay
+    /// representable. Zig rejects two tags with the same value, so an enum with aliased
+    /// constants becomes its integer type with one constant per C constant instead.
+    fn write_enum(
+        &self,
+        output: &mut String,
+        name: &str,
+        enum_declare: &EnumDeclare,
+    ) -> fmt::Result {
+        let tag_type = enum_declare
+            .enum_type
+            .as_ref()
+            .map(|enum_type| self.zig_type(enum_type.resolve_canonical()))
+            .unwrap_or_else(|| "c_uint".to_owned());
+        let is_unsigned = tag_type.starts_with("c_u") || tag_type.starts_with('u');
+        let constant_value = |constant: &EnumConstantDeclare| {
+            constant.constant_value.as_ref().map(|value| {
+                if is_unsigned {
+                    value.unsigned.to_string()
+                } else {
+                    value.signed.to_string()
+                }
+            })
+        };
+        if enum_declare.constants.is_empty() || enum_declare.has_duplicate_values() {
+            writeln!(output, "pub const {} = {};", escape(name), tag_type)?;
+            for constant in enum_declare.constants.iter() {
+                if let Some(value) = constant_value(constant) {
+                    writeln!(
+                        output,
+                        "pub const {}: {} = {};",
+                        escape(&constant.name),
+                        escape(name),
+                        value
+                    )?;
+                }
+            }
+            return writeln!(output);
+        }
+        writeln!(output, "pub const {} = enum({}) {{", escape(name), tag_type)?;
+        for constant in enum_declare.constants.iter() {
+            if let Some(comment) = &constant.comment {
+                for line in comment.lines() {
+                    writeln!(output, "    /// {}", line)?;
+                }
+            }
+            match constant_value(constant) {
+                Some(value) => writeln!(output, "    {} = {},", escape(&constant.name), value)?,
+                None => writeln!(output, "    {},", escape(&constant.name))?,
+            }
+        }
+        writeln!(output, "    _,")?;
+        writeln!(output, "}};")?;
+        writeln!(output)?;
+        if !enum_declare.is_scoped {
+            for constant in enum_declare.constants.iter() {
+                writeln!(
+                    output,
+                    "pub const {} = {}.{};",
+                    escape(&constant.name),
+                    escape(name),
+                    escape(&constant.name)
+                )?;
+            }
+            writeln!(output)?;
+        }
+        Ok(())
+    }
+
+    fn write_record(
+        &self,
+        output: &mut String,
+        keyword: &str,
+        name: &str,
+        members: &[MemberDeclare],
+        attributes: &[Attribute],
+    ) -> fmt::Result {
+        let body = self.record_body(output, keyword, members, attributes, "")?;
+        writeln!(output, "pub const {} = {};", escape(name), body)?;
+        writeln!(output)
+    }
+
+    /// The `extern struct { ... }` type of a record. Named records nested in it are written to
+    /// `output` as declarations of their own first, as C puts them in the enclosing scope;
+    /// anonymous ones are spelled out inline as the type of the field that holds them.
+    ///
+    /// A struct with bit-fields becomes a `packed struct` with a `uN` field per bit-field. Padding
+    /// fields fill up the storage unit of each run of bit-fields the way C compilers do, which
+    /// gives the C layout for the common case of integer fields with no gaps between them.
+    fn record_body(
+        &self,
+        output: &mut String,
+        keyword: &str,
+        members: &[MemberDeclare],
+        attributes: &[Attribute],
+        indent: &str,
+    ) -> Result<String, fmt::Error> {
+        let has_bit_fields = members.iter().any(|member| match member {
+            MemberDeclare::Field(field_declare) => field_declare.bit_width.is_some(),
+            _ => false,
+        });
+        let layout = if has_bit_fields && keyword == "struct" {
+            "packed"
+        } else {
+            "extern"
+        };
+        let is_packed = attributes.contains(&Attribute::Packed);
+        let member_indent = format!("{}    ", indent);
+        let mut body = String::new();
+        writeln!(body, "{} {} {{", layout, keyword)?;
+        let mut pending_record = None;
+        let mut unnamed_count = 0;
+        // The bits used so far in the storage unit of the current run of bit-fields, and the
+        // size of that unit.
+        let mut bit_field_run: Option<(u32, u32)> = None;
+        let mut padding_count = 0;
+        for member in members.iter() {
+            let (nested_keyword, nested_declare_name, nested_members, nested_attributes) =
+                match member {
+                    MemberDeclare::Field(field_declare) => {
+                        let field_type = match &field_declare.field_type {
+                            Some(field_type) => field_type,
+                            None => continue,
+                        };
+                        let zig_type = match (pending_record.take(), field_declare.bit_width) {
+                            (Some(record), _) if field_type.is_anonymous_record() => record,
+                            (_, Some(bit_width)) => {
+                                let sign = if is_signed(field_type) { 'i' } else { 'u' };
+                                format!("{}{}", sign, bit_width)
+                            }
+                            (_, None) => self.zig_type(field_type),
+                        };
+                        if layout == "packed" {
+                            let run = bit_field_run.take();
+                            let unit_bits = field_declare.bit_width.map(|bit_width| {
+                                (
+                                    bit_width,
+                                    storage_unit_bits(&self.builtin_types, field_type),
+                                )
+                            });
+                            let used_bits = match (run, unit_bits) {
+                                (
+                                    Some((used_bits, run_unit_bits)),
+                                    Some((bit_width, unit_bits)),
+                                ) if run_unit_bits == unit_bits
+                                    && used_bits + bit_width <= unit_bits =>
+                                {
+                                    used_bits
+                                }
+                                (Some((used_bits, run_unit_bits)), _) => {
+                                    write_padding(
+                                        &mut body,
+                                        &member_indent,
+                                        &mut padding_count,
+                                        run_unit_bits - used_bits,
+                                    )?;
+                                    0
+                                }
+                                (None, _) => 0,
+                            };
+                            bit_field_run = unit_bits
+                                .map(|(bit_width, unit_bits)| (used_bits + bit_width, unit_bits));
+                        }
+                        let field_name = match &field_declare.name {
+                            Some(field_name) => escape(field_name),
+                            None => {
+                                unnamed_count += 1;
+                                format!("unnamed_{}", unnamed_count - 1)
+                            }
+                        };
+                        // `align(1)` on every field is how Zig spells `__attribute__((packed))`
+                        // for an extern struct.
+                        let align = if is_packed && layout == "extern" {
+                            " align(1)"
+                        } else {
+                            ""
+                        };
+                        writeln!(
+                            body,
+                            "{}{}: {}{},",
+                            member_indent, field_name, zig_type, align
+                        )?;
+                        continue;
+                    }
+                    MemberDeclare::Struct(struct_declare) => (
+                        "struct",
+                        struct_declare.name(),
+                        &struct_declare.fields,
+                        struct_declare.attributes.as_slice(),
+                    ),
+                    MemberDeclare::Union(union_declare) => (
+                        "union",
+                        union_declare.name(),
+                        &union_declare.fields,
+                        union_declare.attributes.as_slice(),
+                    ),
+                    MemberDeclare::Enum(enum_declare) => {
+                        if let Some(enum_name) = enum_declare.name() {
+                            self.write_enum(output, enum_name, enum_declare)?;
+                        }
+                        continue;
+                    }
+                };
+            match nested_declare_name {
+                Some(nested_name) => self.write_record(
+                    output,
+                    nested_keyword,
+                    nested_name,
+                    nested_members,
+                    nested_attributes,
+                )?,
+                None => {
+                    pending_record = Some(self.record_body(
+                        output,
+                        nested_keyword,
+                        nested_members,
+                        nested_attributes,
+                        &member_indent,
+                    )?)
+                }
+            }
+        }
+        if let Some((used_bits, unit_bits)) = bit_field_run {
+            write_padding(
+                &mut body,
+                &member_indent,
+                &mut padding_count,
+                unit_bits - used_bits,
+            )?;
+        }
+        write!(body, "{}}}", indent)?;
+        Ok(body)
+    }
+
+    fn write_function(
+        &self,
+        output: &mut String,
+        function_declare: &FunctionDeclare,
+    ) -> fmt::Result {
+        let parameters = function_declare
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(index, parameter)| match parameter {
+                FunctionParameter::Named(parameter_declare) => format!(
+                    "{}: {}",
+                    escape(&parameter_declare.binding_name(index)),
+                    parameter_declare
+                        .parameter_type
+                        .as_ref()
+                        .map(|parameter_type| self.zig_type(parameter_type))
+                        .unwrap_or_else(|| "anyopaque".to_owned())
+                ),
+                FunctionParameter::Variadic(_) => "...".to_owned(),
+            })
+            .collect::<Vec<_>>();
+        let return_type = if function_declare.is_noreturn() {
+            "noreturn".to_owned()
+        } else {
+            function_declare
+                .return_type
+                .as_ref()
+                .map(|return_type| self.return_type(return_type))
+                .unwrap_or_else(|| "void".to_owned())
+        };
+        writeln!(
+            output,
+            "pub extern fn {}({}) {}{};",
+            escape(&function_declare.function_name),
+            parameters.join(", "),
+            call_conv(function_declare.calling_convention)
+                .map(|call_conv| format!("callconv({}) ", call_conv))
+                .unwrap_or_default(),
+            return_type
+        )
+    }
+
+    fn zig_type(&self, t: &Type) -> String {
+        if let Some(inner_type) = t.elaborated_inner() {
+            return self.zig_type(inner_type);
+        }
+        match t.type_kind {
+            TypeKind::Pointer => match &t.pointee_type {
+                Some(pointee_type) => self.pointer_type(pointee_type),
+                None => "?*anyopaque".to_owned(),
+            },
+            TypeKind::ConstantArray | TypeKind::IncompleteArray => {
+                format!("[{}]{}", t.array_size.unwrap_or(0), self.element_type(t))
+            }
+            TypeKind::VariableArray => format!("[*c]{}", self.element_type(t)),
+            TypeKind::FunctionPrototype | TypeKind::FunctionNoPrototype => {
+                match &t.function_proto {
+                    Some(function_proto) => self.function_type(
+                        function_proto.parameter_types.iter(),
+                        false,
+                        &function_proto.return_type,
+                    ),
+                    None => "anyopaque".to_owned(),
+                }
+            }
+            TypeKind::Typedef | TypeKind::Elaborated | TypeKind::Record | TypeKind::Enum => {
+                let name = t.tagless_name();
+                if self.declared_names.contains(name) {
+                    escape(name)
+                } else if let Some(canonical_type) = &t.canonical_type {
+                    self.zig_type(canonical_type)
+                } else {
+                    escape(name)
+                }
+            }
+            kind => zig_primitive_type_name(kind)
+                .unwrap_or("anyopaque")
+                .to_owned(),
+        }
+    }
+
+    fn return_type(&self, t: &Type) -> String {
+        if t.is_void() {
+            "void".to_owned()
+        } else {
+            self.zig_type(t)
+        }
+    }
+
+    /// `?*anyopaque` for `void *`, an optional single-item pointer for opaque types and function
+    /// pointers, and a C pointer, which allows both indexing and null, for everything else.
+    fn pointer_type(&self, pointee_type: &Type) -> String {
+        let resolved_type = pointee_type.resolve_canonical();
+        let constness = if pointee_type.is_const { "const " } else { "" };
+        if resolved_type.function_proto.is_some() {
+            format!("?*const {}", self.zig_type(resolved_type))
+        } else if resolved_type.is_void() {
+            format!("?*{}anyopaque", constness)
+        } else if self.opaque_names.contains(pointee_type.tagless_name())
+            || self.opaque_names.contains(resolved_type.tagless_name())
+        {
+            format!("?*{}{}", constness, self.zig_type(pointee_type))
+        } else {
+            format!("[*c]{}{}", constness, self.zig_type(pointee_type))
+        }
+    }
+
+    fn element_type(&self, t: &Type) -> String {
+        t.array_element_type
+            .as_ref()
+            .map(|element_type| self.zig_type(element_type))
+            .unwrap_or_else(|| "u8".to_owned())
+    }
+
+    fn function_type<'t>(
+        &self,
+        parameter_types: impl Iterator<Item = &'t Type>,
+        is_variadic: bool,
+        return_type: &Type,
+    ) -> String {
+        let mut parameter_types = parameter_types
+            .map(|parameter_type| self.zig_type(parameter_type))
+            .collect::<Vec<_>>();
+        if is_variadic {
+            parameter_types.push("...".to_owned());
+        }
+        format!(
+            "fn ({}) callconv(.C) {}",
+            parameter_types.join(", "),
+            self.return_type(return_type)
+        )
+    }
+}
+
+fn is_signed(t: &Type) -> bool {
+    matches!(
+        t.resolve_canonical().type_kind,
+        TypeKind::CharS
+            | TypeKind::SChar
+            | TypeKind::Short
+            | TypeKind::Int
+            | TypeKind::Long
+            | TypeKind::LongLong
+            | TypeKind::Int128
+    )
+}
+
+fn write_padding(
+    body: &mut String,
+    indent: &str,
+    padding_count: &mut usize,
+    bits: u32,
+) -> fmt::Result {
+    if bits == 0 {
+        return Ok(());
+    }
+    writeln!(body, "{}padding_{}: u{},", indent, padding_count, bits)?;
+    *padding_count += 1;
+    Ok(())
+}
+
+fn call_conv(calling_convention: Option<CallingConvention>) -> Option<&'static str> {
+    match calling_convention {
+        Some(CallingConvention::Stdcall) => Some(".Stdcall"),
+        Some(CallingConvention::Fastcall) => Some(".Fastcall"),
+        Some(CallingConvention::Thiscall) => Some(".Thiscall"),
+        Some(CallingConvention::Vectorcall) => Some(".Vectorcall"),
+        Some(CallingConvention::Aapcs) => Some(".AAPCS"),
+        _ => None,
+    }
+}
+
+fn zig_primitive_type_name(kind: TypeKind) -> Option<&'static str> {
+    match kind {
+        TypeKind::Bool => Some("bool"),
+        TypeKind::CharS | TypeKind::CharU | TypeKind::UChar => Some("u8"),
+        TypeKind::SChar => Some("i8"),
+        TypeKind::WChar => Some("c_int"),
+        TypeKind::Char16 => Some("u16"),
+        TypeKind::Char32 => Some("u32"),
+        TypeKind::Short => Some("c_short"),
+        TypeKind::UShort => Some("c_ushort"),
+        TypeKind::Int => Some("c_int"),
+        TypeKind::UInt => Some("c_uint"),
+        TypeKind::Long => Some("c_long"),
+        TypeKind::ULong => Some("c_ulong"),
+        TypeKind::LongLong => Some("c_longlong"),
+        TypeKind::ULongLong => Some("c_ulonglong"),
+        TypeKind::Int128 => Some("i128"),
+        TypeKind::UInt128 => Some("u128"),
+        TypeKind::Float => Some("f32"),
+        TypeKind::Double => Some("f64"),
+        TypeKind::LongDouble => Some("c_longdouble"),
+        TypeKind::Void => Some("anyopaque"),
+        _ => None,
+    }
+}
+
+fn escape(name: &str) -> String {
+    if ZIG_KEYWORDS.contains(&name) {
+        format!("@\"{}\"", name)
+    } else {
+        name.to_owned()
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    graph::TypeGraph, EntityVisitor, FunctionParameter, MemberDeclare, SourceFile, Type,
+    TypeDeclaration, TypeDeclare,
+};
+use clang::TypeKind;
+use std::{collections::HashSet, fmt};
+
+/// A type used by a declaration in the file without being declared in it, usually because the
+/// header that declares it was not included.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnresolvedTypeRef {
+    /// The function, variable or type using the type. Fields are given as `record.field`.
+    pub entity_name: String,
+    pub type_name: String,
+}
+
+impl fmt::Display for UnresolvedTypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} refers to undeclared type {}",
+            self.entity_name, self.type_name
+        )
+    }
+}
+
+/// Looks for types referenced from function signatures, variables, fields and typedefs that
+/// have no declaration in the `SourceFile`.
+pub struct CompletenessChecker<'a> {
+    type_graph: TypeGraph,
+    forward_declared_names: HashSet<&'a str>,
+    unresolved_type_refs: Vec<UnresolvedTypeRef>,
+}
+
+impl<'a> CompletenessChecker<'a> {
+    pub fn check(source_file: &'a SourceFile) -> Vec<UnresolvedTypeRef> {
+        // The type graph has a node for every type declared in the file, nested ones included.
+        let mut checker = Self {
+            type_graph: TypeGraph::new(source_file),
+            forward_declared_names: source_file
+                .forward_declarations
+                .iter()
+                .map(|forward_declaration| forward_declaration.name.as_str())
+                .collect(),
+            unresolved_type_refs: Vec::new(),
+        };
+
+        for type_declare in source_file.type_declares.iter() {
+            let name = match type_declare.name().or_else(|| type_declare.typedef_name()) {
+                Some(name) => name,
+                None => continue,
+            };
+            match type_declare {
+                TypeDeclare::Struct(declare) => checker.check_members(name, &declare.fields),
+                TypeDeclare::Union(declare) => checker.check_members(name, &declare.fields),
+                TypeDeclare::Class(declare) => checker.check_members(name, &declare.fields),
+                TypeDeclare::Typedef(declare) => checker.check_type(name, &declare.underlying_type),
+                TypeDeclare::FunctionPointer(declare) => {
+                    for t in declare.signature_types() {
+                        checker.check_type(name, t);
+                    }
+                }
+                TypeDeclare::Enum(_) => {}
+            }
+        }
+        for function_declare in source_file.function_declares.iter() {
+            let name = &function_declare.function_name;
+            if let Some(return_type) = &function_declare.return_type {
+                checker.check_type(name, return_type);
+            }
+            for parameter in function_declare.parameters.iter() {
+                if let FunctionParameter::Named(parameter_declare) = parameter {
+                    if let Some(parameter_type) = &parameter_declare.parameter_type {
+                        checker.check_type(name, parameter_type);
+                    }
+                }
+            }
+        }
+        for variable_declare in source_file.variable_declares.iter() {
+            if let Some(variable_type) = &variable_declare.variable_type {
+                checker.check_type(&variable_declare.name, variable_type);
+            }
+        }
+        checker.unresolved_type_refs
+    }
+
+    fn check_members(&mut self, record_name: &str, members: &[MemberDeclare]) {
+        for member in members.iter() {
+            match member {
+                MemberDeclare::Field(field_declare) => {
+                    if let Some(field_type) = &field_declare.field_type {
+                        let entity_name = match &field_declare.name {
+                            Some(field_name) => format!("{}.{}", record_name, field_name),
+                            None => record_name.to_owned(),
+                        };
+                        self.check_type(&entity_name, field_type);
+                    }
+                }
+                MemberDeclare::Struct(declare) => self.check_members(record_name, &declare.fields),
+                MemberDeclare::Union(declare) => self.check_members(record_name, &declare.fields),
+                MemberDeclare::Enum(_) => {}
+            }
+        }
+    }
+
+    fn check_type(&mut self, entity_name: &str, t: &Type) {
+        // Function pointers carry types of their own in the prototype.
+        let base_type = t.base_type();
+        if let TypeKind::FunctionPrototype | TypeKind::FunctionNoPrototype = base_type.type_kind {
+            if let Some(function_proto) = &base_type.function_proto {
+                self.check_type(entity_name, &function_proto.return_type);
+                for parameter_type in function_proto.parameter_types.iter() {
+                    self.check_type(entity_name, parameter_type);
+                }
+            }
+            return;
+        }
+        let referenced_type = match t.referenced_type() {
+            Some(referenced_type) if !referenced_type.is_anonymous_record() => referenced_type,
+            _ => return,
+        };
+        let type_name = referenced_type.tagless_name();
+        if self.type_graph.contains(type_name) || self.forward_declared_names.contains(type_name) {
+            return;
+        }
+        let unresolved_type_ref = UnresolvedTypeRef {
+            entity_name: entity_name.to_owned(),
+            type_name: type_name.to_owned(),
+        };
+        if !self.unresolved_type_refs.contains(&unresolved_type_ref) {
+            self.unresolved_type_refs.push(unresolved_type_ref);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{EntityVisitor, SourceFile, TypeDeclaration};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+/// The type declarations of a `SourceFile` ordered so that every type comes after the types it
+/// holds by value. Types only referenced through pointers may come later, their forward
+/// declarations are yielded up front instead. Other redeclarations of a type are skipped. See
+/// `SourceFile::dependency_ordered_types`.
+pub struct DependencyOrder<'a> {
+    source_file: &'a SourceFile,
+    indices: std::vec::IntoIter<usize>,
+}
+
+impl<'a> DependencyOrder<'a> {
+    fn new(source_file: &'a SourceFile) -> Self {
+        let type_declares = &source_file.type_declares;
+        // Redeclarations of a type are left out, only the one preferred by name takes part.
+        let is_node: Vec<bool> = type_declares
+            .iter()
+            .enumerate()
+            .map(|(index, type_declare)| {
+                match type_declare.name().or_else(|| type_declare.typedef_name()) {
+                    Some(name) => source_file.find_type_declare(name) == Some(index),
+                    None => true,
+                }
+            })
+            .collect();
+
+        // Kahn's algorithm over the by-value edges, taking the earliest declared type whenever
+        // several are ready so that unrelated types keep the order of the file.
+        let mut dependents = vec![Vec::new(); type_declares.len()];
+        let mut in_degrees = vec![0; type_declares.len()];
+        for index in (0..type_declares.len()).filter(|&index| is_node[index]) {
+            let dependencies = type_declares[index]
+                .dependency_names()
+                .into_iter()
+                .filter(|&(_, by_value)| by_value)
+                .filter_map(|(name, _)| source_file.find_type_declare(name))
+                .filter(|&dependency_index| dependency_index != index && is_node[dependency_index])
+                .collect::<HashSet<_>>();
+            for dependency_index in dependencies {
+                dependents[dependency_index].push(index);
+                in_degrees[index] += 1;
+            }
+        }
+        let mut ready = (0..type_declares.len())
+            .filter(|&index| is_node[index] && in_degrees[index] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
+        let mut ordered = Vec::with_capacity(type_declares.len());
+        while let Some(Reverse(index)) = ready.pop() {
+            ordered.push(index);
+            for &dependent_index in dependents[index].iter() {
+                in_degrees[dependent_index] -= 1;
+                if in_degrees[dependent_index] == 0 {
+                    ready.push(Reverse(dependent_index));
+                }
+            }
+        }
+        // Types containing each other by value cannot be ordered, they keep their place in the
+        // file. `topological_sort_types` reports them as an error.
+        ordered.extend(
+            (0..type_declares.len()).filter(|&index| is_node[index] && in_degrees[index] > 0),
+        );
+
+        let mut positions = vec![usize::MAX; type_declares.len()];
+        for (position, &index) in ordered.iter().enumerate() {
+            positions[index] = position;
+        }
+        // A type pointing to one defined further down needs that type forward declared first.
+        let mut stubs = Vec::new();
+        for (position, &index) in ordered.iter().enumerate() {
+            for name in type_declares[index].referenced_type_names() {
+                let is_defined_later = source_file
+                    .find_type_declare(name)
+                    .map(|dependency_index| positions[dependency_index] > position)
+                    .unwrap_or(false);
+                let stub_index = type_declares.iter().position(|type_declare| {
+                    !type_declare.is_definition()
+                        && (type_declare.name() == Some(name)
+                            || type_declare.typedef_name() == Some(name))
+                });
+                if let Some(stub_index) = stub_index.filter(|_| is_defined_later) {
+                    if !stubs.contains(&stub_index) && !ordered[..position].contains(&stub_index) {
+                        stubs.push(stub_index);
+                    }
+                }
+            }
+        }
+        ordered.retain(|index| !stubs.contains(index));
+        stubs.extend(ordered);
+        Self {
+            source_file,
+            indices: stubs.into_iter(),
+        }
+    }
+}
+
+impl<'a> Iterator for DependencyOrder<'a> {
+    type Item = &'a dyn TypeDeclaration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices
+            .next()
+            .map(|index| &self.source_file.type_declares[index] as &dyn TypeDeclaration)
+    }
+}
+
+impl SourceFile {
+    /// The type declarations in an order a C header could declare them in: a type held by value
+    /// comes before the types holding it, a type only pointed to may come after them, preceded
+    /// by its forward declaration when the file has one.
+    pub fn dependency_ordered_types(&self) -> DependencyOrder<'_> {
+        DependencyOrder::new(self)
+    }
+}
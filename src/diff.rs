@@ -0,0 +1,245 @@
+use crate::{
+    printer::TypePrinter, EntityVisitor, FunctionDeclare, FunctionParameter, SourceFile,
+    TypeDeclaration, TypeDeclare,
+};
+use std::{collections::BTreeSet, fmt};
+
+/// The API differences between two versions of a header, keyed by USR or, failing that, by name.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ApiDiff {
+    pub added_functions: Vec<String>,
+    pub removed_functions: Vec<String>,
+    pub changed_functions: Vec<FunctionChange>,
+    pub added_types: Vec<String>,
+    pub removed_types: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FunctionChange {
+    pub name: String,
+    pub old_signature: String,
+    pub new_signature: String,
+}
+
+impl ApiDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_functions.is_empty()
+            && self.removed_functions.is_empty()
+            && self.changed_functions.is_empty()
+            && self.added_types.is_empty()
+            && self.removed_types.is_empty()
+    }
+
+    /// Whether existing callers could break, i.e. anything was removed or changed.
+    pub fn is_breaking(&self) -> bool {
+        !self.removed_functions.is_empty()
+            || !self.changed_functions.is_empty()
+            || !self.removed_types.is_empty()
+    }
+}
+
+impl fmt::Display for ApiDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for name in self.removed_functions.iter() {
+            writeln!(f, "- function {}", name)?;
+        }
+        for name in self.added_functions.iter() {
+            writeln!(f, "+ function {}", name)?;
+        }
+        for change in self.changed_functions.iter() {
+            writeln!(f, "~ function {}", change.name)?;
+            writeln!(f, "    old: {}", change.old_signature)?;
+            writeln!(f, "    new: {}", change.new_signature)?;
+        }
+        for name in self.removed_types.iter() {
+            writeln!(f, "- type {}", name)?;
+        }
+        for name in self.added_types.iter() {
+            writeln!(f, "+ type {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+impl SourceFile {
+    /// Compares this header against a newer version of it. Parameter names are not part of the
+    /// comparison, only the return and parameter types are.
+    pub fn api_diff(&self, other: &SourceFile) -> ApiDiff {
+        let mut api_diff = ApiDiff::default();
+        for function_declare in self.function_declares.iter() {
+            match find_function(other, function_declare) {
+                None => api_diff
+                    .removed_functions
+                    .push(function_declare.function_name.to_owned()),
+                Some(other_function_declare) => {
+                    if type_signature(function_declare) != type_signature(other_function_declare) {
+                        api_diff.changed_functions.push(FunctionChange {
+                            name: function_declare.function_name.to_owned(),
+                            old_signature: function_declare.signature_string(),
+                            new_signature: other_function_declare.signature_string(),
+                        });
+                    }
+                }
+            }
+        }
+        for function_declare in other.function_declares.iter() {
+            if find_function(self, function_declare).is_none() {
+                api_diff
+                    .added_functions
+                    .push(function_declare.function_name.to_owned());
+            }
+        }
+        let old_type_names = type_names(self);
+        let new_type_names = type_names(other);
+        api_diff.removed_types = old_type_names
+            .difference(&new_type_names)
+            .cloned()
+            .collect();
+        api_diff.added_types = new_type_names
+            .difference(&old_type_names)
+            .cloned()
+            .collect();
+        api_diff
+    }
+
+    /// A hash of the API surface for telling cheaply whether it changed: the names and type
+    /// signatures of functions, and the names and member counts of types. Locations, comments
+    /// and the order of declarations do not affect it. The hash is FNV-1a over a fixed encoding,
+    /// so it stays the same across Rust releases and targets.
+    pub fn compute_hash(&self) -> u64 {
+        let mut functions = self
+            .function_declares
+            .iter()
+            .map(|function_declare| {
+                (
+                    function_declare.function_name.as_str(),
+                    function_declare.parameters.len() as u64,
+                    type_signature(function_declare),
+                )
+            })
+            .collect::<Vec<_>>();
+        functions.sort();
+        let mut types = self
+            .type_declares
+            .iter()
+            .map(|type_declare| {
+                let (keyword, member_count) = match type_declare {
+                    TypeDeclare::Enum(declare) => ("enum", declare.constants.len()),
+                    TypeDeclare::Struct(declare) => ("struct", declare.fields.len()),
+                    TypeDeclare::Union(declare) => ("union", declare.fields.len()),
+                    TypeDeclare::Class(declare) => ("class", declare.fields.len()),
+                    TypeDeclare::Typedef(_) => ("typedef", 0),
+                    TypeDeclare::FunctionPointer(_) => ("function pointer", 0),
+                };
+                (
+                    type_declare.name(),
+                    type_declare.typedef_name(),
+                    keyword,
+                    member_count as u64,
+                )
+            })
+            .collect::<Vec<_>>();
+        types.sort();
+        let mut hasher = Fnv1a::new();
+        hasher.write_u64(functions.len() as u64);
+        for (function_name, parameter_count, signature) in functions.iter() {
+            hasher.write_str(function_name);
+            hasher.write_u64(*parameter_count);
+            hasher.write_str(signature);
+        }
+        hasher.write_u64(types.len() as u64);
+        for (name, typedef_name, keyword, member_count) in types.iter() {
+            hasher.write_optional_str(*name);
+            hasher.write_optional_str(*typedef_name);
+            hasher.write_str(keyword);
+            hasher.write_u64(*member_count);
+        }
+        hasher.finish()
+    }
+}
+
+/// 64-bit FNV-1a. Strings are prefixed with their length so that adjacent ones cannot run into
+/// each other.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_u64(value.len() as u64);
+        self.write(value.as_bytes());
+    }
+
+    fn write_optional_str(&mut self, value: Option<&str>) {
+        match value {
+            Some(value) => {
+                self.write(&[1]);
+                self.write_str(value);
+            }
+            None => self.write(&[0]),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+fn find_function<'a>(
+    source_file: &'a SourceFile,
+    function_declare: &FunctionDeclare,
+) -> Option<&'a FunctionDeclare> {
+    source_file.function_declares.iter().find(|candidate| {
+        match (candidate.usr(), function_declare.usr()) {
+            (Some(usr), Some(other_usr)) => usr == other_usr,
+            _ => candidate.function_name == function_declare.function_name,
+        }
+    })
+}
+
+/// The return and parameter types with names left out, e.g. `int(char *, ...)`.
+fn type_signature(function_declare: &FunctionDeclare) -> String {
+    let return_type = function_declare
+        .return_type
+        .as_ref()
+        .map(|return_type| TypePrinter::new(return_type).print())
+        .unwrap_or_default();
+    let parameter_types = function_declare
+        .parameters
+        .iter()
+        .map(|parameter| match parameter {
+            FunctionParameter::Named(parameter_declare) => parameter_declare
+                .parameter_type
+                .as_ref()
+                .map(|parameter_type| TypePrinter::new(parameter_type).print())
+                .unwrap_or_default(),
+            FunctionParameter::Variadic(_) => "...".to_owned(),
+        })
+        .collect::<Vec<_>>();
+    format!("{}({})", return_type, parameter_types.join(", "))
+}
+
+fn type_names(source_file: &SourceFile) -> BTreeSet<String> {
+    source_file
+        .type_declares
+        .iter()
+        .filter_map(|type_declare| type_declare.name().or_else(|| type_declare.typedef_name()))
+        .map(|name| name.to_owned())
+        .collect()
+}
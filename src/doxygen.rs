@@ -0,0 +1,103 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DoxygenComment {
+    pub brief: Option<String>,
+    pub params: HashMap<String, String>,
+    pub returns: Option<String>,
+}
+
+impl Hash for DoxygenComment {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.brief.hash(state);
+        // Sorted, as the iteration order of a `HashMap` differs between equal maps.
+        let mut params = self.params.iter().collect::<Vec<_>>();
+        params.sort_unstable();
+        params.hash(state);
+        self.returns.hash(state);
+    }
+}
+
+enum Section {
+    Brief,
+    Param(String),
+    Returns,
+    Other,
+}
+
+impl DoxygenComment {
+    /// Parses the raw text of a `/** ... */`, `/*! ... */`, `///` or `//!` comment. The first
+    /// paragraph is taken as the brief description unless an explicit `@brief` is present.
+    pub fn parse(raw_comment: &str) -> Self {
+        let mut comment = Self::default();
+        let mut section = None;
+        for line in raw_comment.lines().map(strip_comment_markers) {
+            if line.is_empty() {
+                section = Some(Section::Other);
+                continue;
+            }
+            let (command, rest) = match line.strip_prefix('@').or_else(|| line.strip_prefix('\\')) {
+                Some(command_line) => split_word(command_line),
+                None => {
+                    let current_section = section.get_or_insert(Section::Brief);
+                    comment.append(current_section, line);
+                    continue;
+                }
+            };
+            section = Some(match command {
+                "brief" | "short" => Section::Brief,
+                "return" | "returns" | "result" => Section::Returns,
+                _ if command.starts_with("param") => {
+                    let (name, _) = split_word(rest);
+                    Section::Param(name.to_owned())
+                }
+                _ => Section::Other,
+            });
+            let rest = match section {
+                Some(Section::Param(_)) => split_word(rest).1,
+                _ => rest,
+            };
+            if let Some(current_section) = &section {
+                comment.append(current_section, rest);
+            }
+        }
+        comment
+    }
+
+    fn append(&mut self, section: &Section, text: &str) {
+        let target = match section {
+            Section::Brief => self.brief.get_or_insert_with(String::new),
+            Section::Returns => self.returns.get_or_insert_with(String::new),
+            Section::Param(name) => self.params.entry(name.to_owned()).or_default(),
+            Section::Other => return,
+        };
+        if !target.is_empty() && !text.is_empty() {
+            target.push(' ');
+        }
+        target.push_str(text);
+    }
+}
+
+fn strip_comment_markers(line: &str) -> &str {
+    let line = line.trim();
+    let line = ["/**", "/*!", "///", "//!", "/*"]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+        .unwrap_or(line);
+    let line = line.strip_suffix("*/").unwrap_or(line).trim();
+    line.strip_prefix('*').unwrap_or(line).trim()
+}
+
+fn split_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    match text.find(char::is_whitespace) {
+        Some(index) => (&text[..index], text[index..].trim()),
+        None => (text, ""),
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{EntityVisitor, SourceFile, TypeDeclaration};
+use std::{collections::HashSet, fs, io, path::Path};
+
+/// The symbols a library exports, as kept in an `.exports` file: one name per line, with blank
+/// lines and `#` comments ignored.
+#[derive(Debug, Default)]
+pub struct ExportList {
+    pub symbols: HashSet<String>,
+}
+
+impl ExportList {
+    pub fn from_file(path: &Path) -> Result<ExportList, io::Error> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    pub fn parse(content: &str) -> ExportList {
+        ExportList {
+            symbols: content
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| line.to_owned())
+                .collect(),
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.symbols.contains(name)
+    }
+}
+
+impl SourceFile {
+    /// Keeps only the functions, variables and types named in `export_list`. A type is kept if
+    /// either its tag name or its typedef name is listed.
+    pub fn retain_exported(&mut self, export_list: &ExportList) {
+        self.function_declares
+            .retain(|function_declare| export_list.contains(&function_declare.function_name));
+        self.variable_declares
+            .retain(|variable_declare| export_list.contains(&variable_declare.name));
+        self.type_declares.retain(|type_declare| {
+            type_declare
+                .name()
+                .into_iter()
+                .chain(type_declare.typedef_name())
+                .any(|name| export_list.contains(name))
+        });
+    }
+}
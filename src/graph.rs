@@ -0,0 +1,397 @@
+use crate::{
+    EntityVisitor, FunctionDeclare, FunctionParameter, MemberDeclare, SourceFile, Type,
+    TypeDeclaration, TypeDeclare,
+};
+use clang::TypeKind;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Write,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum NodeShape {
+    Type,
+    Function,
+}
+
+pub struct DotGraphEmitter<'a> {
+    source_file: &'a SourceFile,
+    nodes: BTreeSet<(String, NodeShape)>,
+    edges: BTreeSet<(String, String, String)>,
+}
+
+impl<'a> DotGraphEmitter<'a> {
+    pub fn new(source_file: &'a SourceFile) -> Self {
+        Self {
+            source_file,
+            nodes: BTreeSet::new(),
+            edges: BTreeSet::new(),
+        }
+    }
+
+    pub fn emit(mut self) -> String {
+        for type_declare in self.source_file.type_declares.iter() {
+            let name = match type_declare.name().or_else(|| type_declare.typedef_name()) {
+                Some(name) => name.to_owned(),
+                None => continue,
+            };
+            self.nodes.insert((name.to_owned(), NodeShape::Type));
+            if let Some(typedef_name) = type_declare
+                .typedef_name()
+                .filter(|&typedef_name| typedef_name != name)
+            {
+                self.nodes
+                    .insert((typedef_name.to_owned(), NodeShape::Type));
+                self.edges.insert((
+                    typedef_name.to_owned(),
+                    name.to_owned(),
+                    "typedef".to_owned(),
+                ));
+            }
+            match type_declare {
+                TypeDeclare::Enum(_) => {}
+                TypeDeclare::Struct(struct_declare) => {
+                    self.add_member_edges(&name, &struct_declare.fields)
+                }
+                TypeDeclare::Union(union_declare) => {
+                    self.add_member_edges(&name, &union_declare.fields)
+                }
+                TypeDeclare::Class(class_declare) => {
+                    self.add_member_edges(&name, &class_declare.fields)
+                }
+                TypeDeclare::Typedef(typedef_declare) => {
+                    self.add_type_edge(&name, &typedef_declare.underlying_type, "typedef")
+                }
+                TypeDeclare::FunctionPointer(function_pointer_declare) => {
+                    self.add_type_edge(&name, &function_pointer_declare.return_type, "returns");
+                    for parameter_type in function_pointer_declare.parameter_types.iter() {
+                        self.add_type_edge(&name, parameter_type, "parameter");
+                    }
+                }
+            }
+        }
+        for function_declare in self.source_file.function_declares.iter() {
+            self.add_function_edges(function_declare);
+        }
+
+        let mut output = String::new();
+        writeln!(output, "digraph types {{").unwrap();
+        for (name, shape) in self.nodes.iter() {
+            let shape = match shape {
+                NodeShape::Type => "box",
+                NodeShape::Function => "ellipse",
+            };
+            writeln!(output, "    {} [shape={}];", quote(name), shape).unwrap();
+        }
+        for (from, to, label) in self.edges.iter() {
+            writeln!(
+                output,
+                "    {} -> {} [label={}];",
+                quote(from),
+                quote(to),
+                quote(label)
+            )
+            .unwrap();
+        }
+        writeln!(output, "}}").unwrap();
+        output
+    }
+
+    fn add_member_edges(&mut self, record_name: &str, members: &[MemberDeclare]) {
+        for member in members.iter() {
+            match member {
+                MemberDeclare::Field(field_declare) => {
+                    if let Some(field_type) = &field_declare.field_type {
+                        let label = field_declare.name.as_deref().unwrap_or("");
+                        self.add_type_edge(record_name, field_type, label);
+                    }
+                }
+                MemberDeclare::Struct(struct_declare) => {
+                    let nested_name = struct_declare.name().unwrap_or(record_name).to_owned();
+                    self.add_nested_record(record_name, &nested_name, &struct_declare.fields);
+                }
+                MemberDeclare::Union(union_declare) => {
+                    let nested_name = union_declare.name().unwrap_or(record_name).to_owned();
+                    self.add_nested_record(record_name, &nested_name, &union_declare.fields);
+                }
+                MemberDeclare::Enum(enum_declare) => {
+                    if let Some(enum_name) = enum_declare.name() {
+                        self.nodes.insert((enum_name.to_owned(), NodeShape::Type));
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_nested_record(
+        &mut self,
+        record_name: &str,
+        nested_name: &str,
+        members: &[MemberDeclare],
+    ) {
+        if nested_name != record_name {
+            self.nodes.insert((nested_name.to_owned(), NodeShape::Type));
+        }
+        self.add_member_edges(nested_name, members);
+    }
+
+    fn add_function_edges(&mut self, function_declare: &FunctionDeclare) {
+        let function_name = &function_declare.function_name;
+        self.nodes
+            .insert((function_name.to_owned(), NodeShape::Function));
+        if let Some(return_type) = &function_declare.return_type {
+            self.add_type_edge(function_name, return_type, "returns");
+        }
+        for (index, parameter) in function_declare.parameters.iter().enumerate() {
+            if let FunctionParameter::Named(parameter_declare) = parameter {
+                if let Some(parameter_type) = &parameter_declare.parameter_type {
+                    let label = parameter_declare.binding_name(index);
+                    self.add_type_edge(function_name, parameter_type, &label);
+                }
+            }
+        }
+    }
+
+    fn add_type_edge(&mut self, from: &str, t: &Type, label: &str) {
+        if let Some(referenced_type) = t.referenced_type() {
+            if referenced_type.is_anonymous_record() {
+                return;
+            }
+            let type_name = referenced_type.tagless_name();
+            self.nodes.insert((type_name.to_owned(), NodeShape::Type));
+            self.edges
+                .insert((from.to_owned(), type_name.to_owned(), label.to_owned()));
+        }
+    }
+}
+
+/// Which types of a `SourceFile` refer to which. A type holding another by value, through a field
+/// or as a typedef of it, depends on it: the two cannot be declared the other way round. A type
+/// pointing to another only refers to it weakly, a forward declaration is enough for that. Nested
+/// records with a name are types of their own, the fields of anonymous ones belong to the record
+/// around them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeGraph {
+    /// The types each declared type holds by value. Every declared type has an entry.
+    pub value_edges: HashMap<String, Vec<String>>,
+    /// The types each declared type points to, including through function pointers.
+    pub pointer_edges: HashMap<String, Vec<String>>,
+    /// The declared types in declaration order, so that walks over the graph are reproducible.
+    names: Vec<String>,
+}
+
+impl TypeGraph {
+    pub fn new(source_file: &SourceFile) -> Self {
+        let mut type_graph = Self::default();
+        for type_declare in source_file.type_declares.iter() {
+            let name = match type_declare.name().or_else(|| type_declare.typedef_name()) {
+                Some(name) => name,
+                None => continue,
+            };
+            type_graph.add_node(name);
+            if let Some(typedef_name) = type_declare
+                .typedef_name()
+                .filter(|&typedef_name| typedef_name != name)
+            {
+                type_graph.add_node(typedef_name);
+                type_graph.add_edge(typedef_name, name, true);
+            }
+            match type_declare {
+                TypeDeclare::Enum(_) => {}
+                TypeDeclare::Struct(declare) => type_graph.add_member_edges(name, &declare.fields),
+                TypeDeclare::Union(declare) => type_graph.add_member_edges(name, &declare.fields),
+                TypeDeclare::Class(declare) => type_graph.add_member_edges(name, &declare.fields),
+                TypeDeclare::Typedef(declare) => {
+                    type_graph.add_type_edges(name, &declare.underlying_type, true)
+                }
+                TypeDeclare::FunctionPointer(declare) => {
+                    for t in declare.signature_types() {
+                        type_graph.add_type_edges(name, t, false);
+                    }
+                }
+            }
+        }
+        type_graph
+    }
+
+    /// Whether the file declares a type named `name`, as a tag or a typedef name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.value_edges.contains_key(name)
+    }
+
+    /// The types `name` refers to, directly or through other types, by value or by pointer. It
+    /// only contains `name` itself when the type refers back to itself.
+    pub fn reachable_from(&self, name: &str) -> HashSet<String> {
+        Self::walk(name, |name| {
+            self.value_edges
+                .get(name)
+                .into_iter()
+                .chain(self.pointer_edges.get(name))
+                .flatten()
+                .map(|name| name.as_str())
+                .collect()
+        })
+    }
+
+    /// The types referring to `name`, directly or through other types, by value or by pointer.
+    pub fn dependents_of(&self, name: &str) -> HashSet<String> {
+        let mut referrers: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edges in [&self.value_edges, &self.pointer_edges] {
+            for (from, to_names) in edges.iter() {
+                for to in to_names.iter() {
+                    referrers.entry(to).or_default().push(from);
+                }
+            }
+        }
+        Self::walk(name, |name| {
+            referrers.get(name).cloned().unwrap_or_default()
+        })
+    }
+
+    /// Whether no type holds itself by value, directly or through other types. Such a type would
+    /// have an infinite size, so unlike cycles through pointers this is always an error.
+    pub fn is_cycle_free(&self) -> bool {
+        self.value_cycle().is_none()
+    }
+
+    /// The first cycle of types holding each other by value, in declaration and field order,
+    /// starting and ending with the same type.
+    pub fn value_cycle(&self) -> Option<Vec<String>> {
+        fn visit<'a>(
+            type_graph: &'a TypeGraph,
+            name: &'a str,
+            path: &mut Vec<&'a str>,
+            visited: &mut HashSet<&'a str>,
+        ) -> Option<Vec<String>> {
+            if let Some(start) = path.iter().position(|&path_name| path_name == name) {
+                return Some(
+                    path[start..]
+                        .iter()
+                        .chain(Some(&name))
+                        .map(|&path_name| path_name.to_owned())
+                        .collect(),
+                );
+            }
+            if !visited.insert(name) {
+                return None;
+            }
+            path.push(name);
+            for dependency_name in type_graph.value_edges.get(name).into_iter().flatten() {
+                if let Some(cycle) = visit(type_graph, dependency_name, path, visited) {
+                    return Some(cycle);
+                }
+            }
+            path.pop();
+            None
+        }
+
+        let mut visited = HashSet::new();
+        self.names
+            .iter()
+            .find_map(|name| visit(self, name, &mut Vec::new(), &mut visited))
+    }
+
+    fn walk<'a>(name: &'a str, neighbours: impl Fn(&str) -> Vec<&'a str>) -> HashSet<String> {
+        let mut reached = HashSet::new();
+        let mut pending = neighbours(name);
+        while let Some(name) = pending.pop() {
+            if reached.insert(name.to_owned()) {
+                pending.extend(neighbours(name));
+            }
+        }
+        reached
+    }
+
+    fn add_node(&mut self, name: &str) {
+        if !self.value_edges.contains_key(name) {
+            self.value_edges.insert(name.to_owned(), Vec::new());
+            self.pointer_edges.insert(name.to_owned(), Vec::new());
+            self.names.push(name.to_owned());
+        }
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, by_value: bool) {
+        // `typedef struct foo foo;` names one type twice, it does not contain itself.
+        if by_value && from == to {
+            return;
+        }
+        let edges = if by_value {
+            &mut self.value_edges
+        } else {
+            &mut self.pointer_edges
+        };
+        let to_names = edges.entry(from.to_owned()).or_default();
+        if !to_names.iter().any(|to_name| to_name == to) {
+            to_names.push(to.to_owned());
+        }
+    }
+
+    fn add_member_edges(&mut self, record_name: &str, members: &[MemberDeclare]) {
+        for member in members.iter() {
+            match member {
+                MemberDeclare::Field(field_declare) => {
+                    if let Some(field_type) = &field_declare.field_type {
+                        self.add_type_edges(record_name, field_type, true);
+                    }
+                }
+                MemberDeclare::Struct(struct_declare) => self.add_nested_record(
+                    record_name,
+                    struct_declare.name(),
+                    &struct_declare.fields,
+                ),
+                MemberDeclare::Union(union_declare) => {
+                    self.add_nested_record(record_name, union_declare.name(), &union_declare.fields)
+                }
+                MemberDeclare::Enum(enum_declare) => {
+                    if let Some(enum_name) = enum_declare.name() {
+                        self.add_node(enum_name);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_nested_record(
+        &mut self,
+        record_name: &str,
+        nested_name: Option<&str>,
+        members: &[MemberDeclare],
+    ) {
+        match nested_name {
+            Some(nested_name) => {
+                self.add_node(nested_name);
+                self.add_member_edges(nested_name, members);
+            }
+            None => self.add_member_edges(record_name, members),
+        }
+    }
+
+    fn add_type_edges(&mut self, from: &str, t: &Type, by_value: bool) {
+        if let Some(pointee_type) = &t.pointee_type {
+            self.add_type_edges(from, pointee_type, false);
+        } else if let Some(element_type) = &t.array_element_type {
+            self.add_type_edges(from, element_type, by_value);
+        } else if let TypeKind::FunctionPrototype | TypeKind::FunctionNoPrototype = t.type_kind {
+            if let Some(function_proto) = &t.function_proto {
+                self.add_type_edges(from, &function_proto.return_type, false);
+                for parameter_type in function_proto.parameter_types.iter() {
+                    self.add_type_edges(from, parameter_type, false);
+                }
+            }
+        } else if let TypeKind::Typedef | TypeKind::Elaborated | TypeKind::Record | TypeKind::Enum =
+            t.type_kind
+        {
+            // A typedef'd type is referred to under both of its names.
+            if !t.is_anonymous_record() {
+                self.add_edge(from, t.tagless_name(), by_value);
+            }
+            if let Some(canonical_type) = &t.canonical_type {
+                self.add_type_edges(from, canonical_type, by_value);
+            }
+        }
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,221 @@
+use crate::{
+    builtin_types::BuiltinTypeMapper, EntityVisitor, FieldDeclare, MemberDeclare, SourceFile,
+    StructDeclare, TypeDeclaration, TypeDeclare, UnionDeclare,
+};
+use std::fmt::Write;
+
+/// Renders the memory layout of a struct or union as a table of field offsets and sizes, with
+/// the padding the compiler inserts spelled out as rows of its own.
+pub struct LayoutReport<'a> {
+    source_file: &'a SourceFile,
+    type_mapper: BuiltinTypeMapper,
+    /// Offset, size, name and type of every row.
+    rows: Vec<[String; 4]>,
+}
+
+impl<'a> LayoutReport<'a> {
+    pub fn for_struct(struct_declare: &StructDeclare, source_file: &'a SourceFile) -> String {
+        let mut report = Self {
+            source_file,
+            type_mapper: BuiltinTypeMapper::for_source_file(source_file),
+            rows: Vec::new(),
+        };
+        let mut end_bits = 0;
+        for field_declare in fields(&struct_declare.fields) {
+            let bit_offset = field_declare.bit_offset.unwrap_or(end_bits);
+            report.push_padding(end_bits, bit_offset);
+            report.push_field(field_declare);
+            end_bits = end_bits.max(bit_offset + report.field_bits(field_declare).unwrap_or(0));
+        }
+        if let Some(size_bytes) = struct_declare.size_bytes {
+            report.push_padding(end_bits, size_bytes * 8);
+        }
+        report.render(
+            "struct",
+            struct_declare
+                .name()
+                .or_else(|| struct_declare.typedef_name()),
+            struct_declare.size_bytes,
+            struct_declare.align_bytes,
+        )
+    }
+
+    pub fn for_union(union_declare: &UnionDeclare, source_file: &'a SourceFile) -> String {
+        let mut report = Self {
+            source_file,
+            type_mapper: BuiltinTypeMapper::for_source_file(source_file),
+            rows: Vec::new(),
+        };
+        let mut end_bits = 0;
+        for field_declare in fields(&union_declare.fields) {
+            report.push_field(field_declare);
+            end_bits = end_bits.max(report.field_bits(field_declare).unwrap_or(0));
+        }
+        if let Some(size_bytes) = union_declare.size_bytes {
+            report.push_padding(end_bits, size_bytes * 8);
+        }
+        report.render(
+            "union",
+            union_declare
+                .name()
+                .or_else(|| union_declare.typedef_name()),
+            union_declare.size_bytes,
+            union_declare.align_bytes,
+        )
+    }
+
+    fn push_field(&mut self, field_declare: &FieldDeclare) {
+        let offset = match field_declare.bit_offset {
+            // Bit-fields rarely start on a byte boundary, so their offset is `byte.bit`.
+            Some(bit_offset) if field_declare.is_bit_field => {
+                format!("{}.{}", bit_offset / 8, bit_offset % 8)
+            }
+            Some(bit_offset) => (bit_offset / 8).to_string(),
+            None => "?".to_owned(),
+        };
+        let size = match field_declare.bit_width {
+            Some(bit_width) => format!("{} bits", bit_width),
+            None => self
+                .field_bits(field_declare)
+                .map(|bits| (bits / 8).to_string())
+                .unwrap_or_else(|| "?".to_owned()),
+        };
+        let name = field_declare
+            .name()
+            .unwrap_or(if field_declare.is_anonymous {
+                "<anonymous>"
+            } else {
+                "<unnamed>"
+            });
+        let mut type_name = field_declare
+            .field_type
+            .as_ref()
+            .map(|field_type| field_type.type_name.to_owned())
+            .unwrap_or_else(|| "?".to_owned());
+        if field_declare.is_bit_field {
+            type_name.push_str(" (bit-field)");
+        }
+        self.rows.push([offset, size, name.to_owned(), type_name]);
+    }
+
+    /// Adds a row for the whole bytes between the end of one field and the start of the next.
+    fn push_padding(&mut self, from_bits: u64, to_bits: u64) {
+        let from_bytes = from_bits.div_ceil(8);
+        let to_bytes = to_bits / 8;
+        if to_bytes > from_bytes {
+            self.rows.push([
+                from_bytes.to_string(),
+                (to_bytes - from_bytes).to_string(),
+                "*** padding ***".to_owned(),
+                String::new(),
+            ]);
+        }
+    }
+
+    /// How many bits the field occupies. libclang has no size for records that are incomplete
+    /// where the field is declared, so those are looked up in the file, and declarations read
+    /// back from JSON may have none at all, so builtin types fall back to their size on the
+    /// target.
+    fn field_bits(&self, field_declare: &FieldDeclare) -> Option<u64> {
+        if let Some(bit_width) = field_declare.bit_width {
+            return Some(bit_width as u64);
+        }
+        if field_declare.is_flexible_array_member {
+            return Some(0);
+        }
+        let size_bytes = field_declare.size_bytes.or_else(|| {
+            let field_type = field_declare.field_type.as_ref()?;
+            if let Some(size_bytes) = self.type_mapper.type_size(field_type) {
+                return Some(size_bytes);
+            }
+            let index = self
+                .source_file
+                .find_type_declare(field_type.tagless_name())?;
+            match &self.source_file.type_declares[index] {
+                TypeDeclare::Struct(declare) => declare.size_bytes,
+                TypeDeclare::Union(declare) => declare.size_bytes,
+                _ => None,
+            }
+        })?;
+        Some(size_bytes * 8)
+    }
+
+    fn render(
+        &self,
+        keyword: &str,
+        name: Option<&str>,
+        size_bytes: Option<u64>,
+        align_bytes: Option<u64>,
+    ) -> String {
+        let header = [
+            "offset".to_owned(),
+            "size".to_owned(),
+            "name".to_owned(),
+            "type".to_owned(),
+        ];
+        let mut widths = [0; 4];
+        for row in std::iter::once(&header).chain(self.rows.iter()) {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        let mut output = String::new();
+        writeln!(
+            output,
+            "{} {}: size {}, align {}",
+            keyword,
+            name.unwrap_or("<anonymous>"),
+            optional_bytes(size_bytes),
+            optional_bytes(align_bytes)
+        )
+        .unwrap();
+        for row in std::iter::once(&header).chain(self.rows.iter()) {
+            let line = format!(
+                "{:>offset_width$}  {:>size_width$}  {:name_width$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                offset_width = widths[0],
+                size_width = widths[1],
+                name_width = widths[2]
+            );
+            writeln!(output, "{}", line.trim_end()).unwrap();
+        }
+        output
+    }
+}
+
+impl SourceFile {
+    /// Layout tables of every struct and union defined in the file, separated by empty lines.
+    pub fn layout_report(&self) -> String {
+        self.type_declares
+            .iter()
+            .filter(|type_declare| type_declare.is_definition())
+            .filter_map(|type_declare| match type_declare {
+                TypeDeclare::Struct(declare) => Some(LayoutReport::for_struct(declare, self)),
+                TypeDeclare::Union(declare) => Some(LayoutReport::for_union(declare, self)),
+                TypeDeclare::Enum(_)
+                | TypeDeclare::Class(_)
+                | TypeDeclare::Typedef(_)
+                | TypeDeclare::FunctionPointer(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The fields stored directly in a record. Anonymous members are listed as fields too, their
+/// own fields are laid out relative to them.
+fn fields(members: &[MemberDeclare]) -> impl Iterator<Item = &FieldDeclare> {
+    members.iter().filter_map(|member| match member {
+        MemberDeclare::Field(field_declare) => Some(field_declare),
+        MemberDeclare::Struct(_) | MemberDeclare::Union(_) | MemberDeclare::Enum(_) => None,
+    })
+}
+
+fn optional_bytes(bytes: Option<u64>) -> String {
+    bytes
+        .map(|bytes| bytes.to_string())
+        .unwrap_or_else(|| "?".to_owned())
+}
@@ -0,0 +1,5241 @@
+//! Walks the libclang AST of C and C++ headers into plain Rust declarations.
+
+pub mod builtin_types;
+pub mod codegen;
+pub mod completeness;
+pub mod dependency_order;
+pub mod diff;
+#[cfg(feature = "parse_doxygen")]
+pub mod doxygen;
+pub mod export_list;
+pub mod graph;
+pub mod layout;
+pub mod markdown;
+pub mod memory_model;
+pub mod modules;
+pub mod output_dir;
+pub mod preprocessor;
+pub mod printer;
+pub mod registry;
+#[cfg(feature = "serde")]
+mod serde_remote;
+pub mod statistics;
+pub mod tree;
+pub mod validate;
+
+use clang::{
+    source, Accessibility, CallingConvention, Clang, Entity, EntityKind, EvaluationResult,
+    ExceptionSpecification, Index, Linkage, SourceError, StorageClass, TypeKind,
+};
+#[cfg(feature = "parse_doxygen")]
+use doxygen::DoxygenComment;
+use graph::TypeGraph;
+use memory_model::OwnershipHints;
+use printer::TypePrinter;
+use registry::RegistryCache;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::{self, Debug},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+// The clang crate refuses to create a second `Clang` while one is alive, so concurrent callers of
+// `parse_file` take turns instead of failing.
+static CLANG_LOCK: Mutex<()> = Mutex::new(());
+
+pub trait EntityVisitor: Debug {
+    fn name(&self) -> Option<&str>;
+    fn set_name(&mut self, new_name: String);
+    fn entity_kind(&self) -> EntityKind;
+
+    #[inline]
+    fn linkage(&self) -> Option<Linkage> {
+        None
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        None
+    }
+
+    fn visit_entity(
+        &mut self,
+        current_entity: &Entity,
+        parent_entity: &Entity,
+    ) -> Result<(), AstError>;
+
+    fn visited(mut self, current_entity: &Entity, parent_entity: &Entity) -> Result<Self, AstError>
+    where
+        Self: Sized,
+    {
+        self.visit_entity(current_entity, parent_entity)?;
+        Ok(self)
+    }
+
+    fn check_entity_kind(&self, entity: &Entity) -> Result<(), AstError> {
+        if entity.get_kind() == self.entity_kind() {
+            Ok(())
+        } else {
+            Err(AstError::unexpected_entity_kind(entity))
+        }
+    }
+}
+
+pub trait TypeDeclaration: EntityVisitor {
+    fn typedef_name(&self) -> Option<&str>;
+    fn set_typedef_name(&mut self, new_typedef_name: String);
+    fn is_definition(&self) -> bool;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceLocation {
+    pub path: String,
+    pub line_number: u32,
+    pub column_number: u32,
+}
+
+impl SourceLocation {
+    pub fn new(loc: (String, u32, u32)) -> Self {
+        Self {
+            path: loc.0,
+            line_number: loc.1,
+            column_number: loc.2,
+        }
+    }
+
+    pub fn from_clang(loc: &source::SourceLocation) -> Self {
+        Self::new(loc.get_presumed_location())
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.path, self.line_number, self.column_number
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceRange {
+    pub start: SourceLocation,
+    pub end: SourceLocation,
+}
+
+impl SourceRange {
+    pub fn from_clang(range: &source::SourceRange) -> Self {
+        Self {
+            start: SourceLocation::from_clang(&range.get_start()),
+            end: SourceLocation::from_clang(&range.get_end()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AstError {
+    UnexpectedEntityKind {
+        entity_kind: EntityKind,
+        location: Option<SourceLocation>,
+    },
+    MissingName {
+        entity_kind: EntityKind,
+        location: Option<SourceLocation>,
+    },
+    MissingType {
+        entity_kind: EntityKind,
+        location: Option<SourceLocation>,
+    },
+}
+
+impl AstError {
+    pub fn unexpected_entity_kind(entity: &Entity) -> Self {
+        AstError::UnexpectedEntityKind {
+            entity_kind: entity.get_kind(),
+            location: Self::entity_location(entity),
+        }
+    }
+
+    pub fn missing_name(entity: &Entity) -> Self {
+        AstError::MissingName {
+            entity_kind: entity.get_kind(),
+            location: Self::entity_location(entity),
+        }
+    }
+
+    pub fn missing_type(entity: &Entity) -> Self {
+        AstError::MissingType {
+            entity_kind: entity.get_kind(),
+            location: Self::entity_location(entity),
+        }
+    }
+
+    fn entity_location(entity: &Entity) -> Option<SourceLocation> {
+        entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location))
+    }
+}
+
+impl fmt::Display for AstError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (message, entity_kind, location) = match self {
+            AstError::UnexpectedEntityKind {
+                entity_kind,
+                location,
+            } => ("Unexpected entity", entity_kind, location),
+            AstError::MissingName {
+                entity_kind,
+                location,
+            } => ("Missing name of entity", entity_kind, location),
+            AstError::MissingType {
+                entity_kind,
+                location,
+            } => ("Missing type of entity", entity_kind, location),
+        };
+        write!(f, "{} {:?}", message, entity_kind)?;
+        if let Some(location) = location {
+            write!(f, " at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for AstError {}
+
+#[derive(Debug)]
+pub enum MergeError {
+    ConflictingDefinition {
+        name: String,
+        usr: String,
+        first_location: Option<SourceLocation>,
+        second_location: Option<SourceLocation>,
+    },
+}
+
+impl MergeError {
+    fn conflicting_definition(first: &TypeDeclare, second: &TypeDeclare) -> Self {
+        MergeError::ConflictingDefinition {
+            name: first
+                .name()
+                .or_else(|| first.typedef_name())
+                .unwrap_or_default()
+                .to_owned(),
+            usr: first.usr().unwrap_or_default().to_owned(),
+            first_location: first.location().cloned(),
+            second_location: second.location().cloned(),
+        }
+    }
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MergeError::ConflictingDefinition {
+                name,
+                usr,
+                first_location,
+                second_location,
+            } => {
+                write!(f, "Conflicting definitions of {} ({})", name, usr)?;
+                if let (Some(first_location), Some(second_location)) =
+                    (first_location, second_location)
+                {
+                    write!(f, " at {} and {}", first_location, second_location)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for MergeError {}
+
+/// Types that contain each other by value, which no C compiler accepts. `type_names` lists the
+/// cycle in field order, starting and ending with the same type.
+#[derive(Debug)]
+pub struct CycleError {
+    pub type_names: Vec<String>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Types contain each other by value: {}",
+            self.type_names.join(" -> ")
+        )
+    }
+}
+
+impl Error for CycleError {}
+
+#[derive(Debug)]
+pub enum ParseError {
+    ClangUnavailable(String),
+    ParseFailure { path: PathBuf, error: SourceError },
+    Ast(AstError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::ClangUnavailable(message) => write!(f, "{}", message),
+            ParseError::ParseFailure { path, error } => {
+                write!(f, "Failed to parse {}: {}", path.display(), error)
+            }
+            ParseError::Ast(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::ClangUnavailable(_) => None,
+            ParseError::ParseFailure { error, .. } => Some(error),
+            ParseError::Ast(err) => Some(err),
+        }
+    }
+}
+
+impl From<AstError> for ParseError {
+    fn from(err: AstError) -> Self {
+        ParseError::Ast(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LanguageStandard {
+    #[default]
+    Default,
+    C89,
+    C99,
+    C11,
+    C17,
+    Cxx98,
+    Cxx03,
+    Cxx11,
+    Cxx14,
+    Cxx17,
+    Cxx20,
+}
+
+impl LanguageStandard {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "c89" | "c90" => Ok(LanguageStandard::C89),
+            "c99" => Ok(LanguageStandard::C99),
+            "c11" => Ok(LanguageStandard::C11),
+            "c17" | "c18" => Ok(LanguageStandard::C17),
+            "c++98" => Ok(LanguageStandard::Cxx98),
+            "c++03" => Ok(LanguageStandard::Cxx03),
+            "c++11" => Ok(LanguageStandard::Cxx11),
+            "c++14" => Ok(LanguageStandard::Cxx14),
+            "c++17" => Ok(LanguageStandard::Cxx17),
+            "c++20" => Ok(LanguageStandard::Cxx20),
+            _ => Err(format!("Unknown language standard: {}", name)),
+        }
+    }
+
+    /// The value passed to `-std=`, or `None` to leave the compiler default in place.
+    pub fn as_flag_value(self) -> Option<&'static str> {
+        match self {
+            LanguageStandard::Default => None,
+            LanguageStandard::C89 => Some("c89"),
+            LanguageStandard::C99 => Some("c99"),
+            LanguageStandard::C11 => Some("c11"),
+            LanguageStandard::C17 => Some("c17"),
+            LanguageStandard::Cxx98 => Some("c++98"),
+            LanguageStandard::Cxx03 => Some("c++03"),
+            LanguageStandard::Cxx11 => Some("c++11"),
+            LanguageStandard::Cxx14 => Some("c++14"),
+            LanguageStandard::Cxx17 => Some("c++17"),
+            LanguageStandard::Cxx20 => Some("c++20"),
+        }
+    }
+
+    pub fn is_cxx(self) -> bool {
+        matches!(
+            self,
+            LanguageStandard::Cxx98
+                | LanguageStandard::Cxx03
+                | LanguageStandard::Cxx11
+                | LanguageStandard::Cxx14
+                | LanguageStandard::Cxx17
+                | LanguageStandard::Cxx20
+        )
+    }
+
+    /// The standard a file is parsed with when none is asked for: C++17 for C++ and C11 for C,
+    /// going by the `--language` value if there is one and by the file extension otherwise.
+    pub fn detect(path: &Path, language: Option<&str>) -> Self {
+        match language {
+            Some(language) if language.starts_with("c++") => LanguageStandard::Cxx17,
+            Some(language) if language.starts_with('c') => LanguageStandard::C11,
+            Some(_) => LanguageStandard::Default,
+            None => match path.extension().and_then(|extension| extension.to_str()) {
+                Some("hpp" | "hh" | "hxx" | "h++" | "cpp" | "cc" | "cxx" | "c++") => {
+                    LanguageStandard::Cxx17
+                }
+                Some("h" | "c") => LanguageStandard::C11,
+                _ => LanguageStandard::Default,
+            },
+        }
+    }
+}
+
+/// Which top-level declarations of a translation unit are collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterMode {
+    /// Only declarations written in the parsed file itself.
+    #[default]
+    MainFile,
+    /// Declarations from every included file as well.
+    AllFiles,
+    /// Declarations from the parsed file and the headers it includes, except system headers.
+    SkipSystemHeaders,
+}
+
+impl FilterMode {
+    pub fn accepts(self, entity: &Entity) -> bool {
+        match self {
+            FilterMode::MainFile => entity.is_in_main_file(),
+            FilterMode::AllFiles => true,
+            FilterMode::SkipSystemHeaders => !entity.is_in_system_header(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub include_paths: Vec<PathBuf>,
+    pub defines: Vec<(String, Option<String>)>,
+    pub language: Option<String>,
+    pub language_standard: LanguageStandard,
+    pub target_triple: Option<String>,
+    pub skip_function_bodies: bool,
+    pub filter_mode: FilterMode,
+    /// Treat ordinary comments as documentation too, not only `/** */` and `///` ones.
+    pub parse_all_comments: bool,
+}
+
+impl ParseOptions {
+    pub fn builder() -> ParseOptionsBuilder {
+        ParseOptionsBuilder::default()
+    }
+
+    /// The command line arguments handed to libclang for these options.
+    pub fn arguments(&self) -> Vec<String> {
+        let mut arguments = Vec::new();
+        if let Some(language) = &self.language {
+            arguments.push(format!("--language={}", language));
+        }
+        if let Some(language_standard) = self.language_standard.as_flag_value() {
+            arguments.push(format!("-std={}", language_standard));
+        }
+        if let Some(target_triple) = &self.target_triple {
+            arguments.push(format!("--target={}", target_triple));
+        }
+        for include_path in self.include_paths.iter() {
+            arguments.push(format!("-I{}", include_path.display()));
+        }
+        for (name, value) in self.defines.iter() {
+            match value {
+                Some(value) => arguments.push(format!("-D{}={}", name, value)),
+                None => arguments.push(format!("-D{}", name)),
+            }
+        }
+        if self.parse_all_comments {
+            arguments.push("-fparse-all-comments".to_owned());
+        }
+        arguments
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ParseOptionsBuilder {
+    options: ParseOptions,
+}
+
+impl ParseOptionsBuilder {
+    pub fn include_path(mut self, include_path: impl Into<PathBuf>) -> Self {
+        self.options.include_paths.push(include_path.into());
+        self
+    }
+
+    pub fn define(mut self, name: impl Into<String>, value: Option<String>) -> Self {
+        self.options.defines.push((name.into(), value));
+        self
+    }
+
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.options.language = Some(language.into());
+        self
+    }
+
+    pub fn language_standard(mut self, language_standard: LanguageStandard) -> Self {
+        self.options.language_standard = language_standard;
+        self
+    }
+
+    pub fn target_triple(mut self, target_triple: impl Into<String>) -> Self {
+        self.options.target_triple = Some(target_triple.into());
+        self
+    }
+
+    pub fn skip_function_bodies(mut self, skip_function_bodies: bool) -> Self {
+        self.options.skip_function_bodies = skip_function_bodies;
+        self
+    }
+
+    /// Shorthand for `filter_mode(FilterMode::AllFiles)`, or `FilterMode::MainFile` if
+    /// `follow_includes` is false.
+    pub fn follow_includes(mut self, follow_includes: bool) -> Self {
+        self.options.filter_mode = if follow_includes {
+            FilterMode::AllFiles
+        } else {
+            FilterMode::MainFile
+        };
+        self
+    }
+
+    pub fn filter_mode(mut self, filter_mode: FilterMode) -> Self {
+        self.options.filter_mode = filter_mode;
+        self
+    }
+
+    pub fn parse_all_comments(mut self, parse_all_comments: bool) -> Self {
+        self.options.parse_all_comments = parse_all_comments;
+        self
+    }
+
+    pub fn build(self) -> ParseOptions {
+        self.options
+    }
+}
+
+/// Parses the C or C++ file at `path` into a `SourceFile`. Safe to call from several threads,
+/// although libclang work itself is serialized.
+pub fn parse_file(path: &Path, options: ParseOptions) -> Result<SourceFile, ParseError> {
+    parse_file_with(path, options, |_| {})
+}
+
+/// Like `parse_file`, but hands the root entity of the translation unit to `inspect` before it
+/// is walked.
+pub fn parse_file_with(
+    path: &Path,
+    options: ParseOptions,
+    inspect: impl FnOnce(&Entity),
+) -> Result<SourceFile, ParseError> {
+    let mut options = options;
+    if options.language_standard == LanguageStandard::Default {
+        options.language_standard = LanguageStandard::detect(path, options.language.as_deref());
+    }
+    let _guard = CLANG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let cl = Clang::new().map_err(ParseError::ClangUnavailable)?;
+    let idx = Index::new(&cl, true, false);
+    let tu = idx
+        .parser(path)
+        .arguments(&options.arguments())
+        .detailed_preprocessing_record(true)
+        .skip_function_bodies(options.skip_function_bodies)
+        .parse()
+        .map_err(|error| ParseError::ParseFailure {
+            path: path.to_owned(),
+            error,
+        })?;
+    let entity = tu.get_entity();
+    inspect(&entity);
+    let mut source_file = SourceFile::new(
+        entity
+            .get_name()
+            .unwrap_or_else(|| path.to_string_lossy().into_owned()),
+    );
+    source_file.target_triple = options.target_triple.clone();
+    source_file.visit_translation_unit(&entity, options.filter_mode)?;
+    Ok(source_file)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Type {
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::TypeKindDef"))]
+    pub type_kind: TypeKind,
+    pub type_name: String,
+    pub pointee_type: Option<Box<Type>>,
+    pub function_proto: Option<FunctionProtoInfo>,
+    /// What the block a `BlockPointer` such as `void (^)(int)` points to returns.
+    pub block_return_type: Option<Box<Type>>,
+    pub block_parameter_types: Option<Vec<Type>>,
+    pub array_element_type: Option<Box<Type>>,
+    pub array_size: Option<usize>,
+    /// The type with all typedefs resolved, for typedefs, elaborated types and any type that
+    /// is spelled differently once they are resolved.
+    pub canonical_type: Option<Box<Type>>,
+    /// The type an `Elaborated` type names, e.g. the record for `struct Foo`.
+    pub elaborated_inner: Option<Box<Type>>,
+    /// A `typeof(...)` or `__auto_type` whose type libclang could not work out, so `type_name`
+    /// is all there is.
+    pub is_deduced: bool,
+    pub is_atomic: bool,
+    pub is_const: bool,
+    pub is_volatile: bool,
+    pub is_restrict: bool,
+}
+
+impl Type {
+    pub fn new(kind: TypeKind, name: String) -> Self {
+        Self {
+            type_kind: kind,
+            type_name: name,
+            pointee_type: None,
+            function_proto: None,
+            block_return_type: None,
+            block_parameter_types: None,
+            array_element_type: None,
+            array_size: None,
+            canonical_type: None,
+            elaborated_inner: None,
+            is_deduced: false,
+            is_atomic: false,
+            is_const: false,
+            is_volatile: false,
+            is_restrict: false,
+        }
+    }
+
+    pub fn from_clang(t: &clang::Type) -> Self {
+        // `typeof(...)` and `__auto_type` stand for the type of an expression, which only the
+        // canonical type spells out.
+        if Self::is_deduced_type(t) {
+            let canonical_type = t.get_canonical_type();
+            if !Self::is_deduced_type(&canonical_type) {
+                return Self::from_clang(&canonical_type);
+            }
+            let mut result = Self::new(t.get_kind(), t.get_display_name());
+            result.is_deduced = true;
+            return result;
+        }
+        let mut result = Self::new(t.get_kind(), t.get_display_name());
+        result.is_const = t.is_const_qualified();
+        result.is_volatile = t.is_volatile_qualified();
+        result.is_restrict = t.is_restrict_qualified();
+        result.pointee_type = t
+            .get_pointee_type()
+            .map(|pt| Box::new(Self::from_clang(&pt)));
+        result.function_proto = FunctionProtoInfo::from_clang(t);
+        if result.type_kind == TypeKind::BlockPointer {
+            if let Some(block_type) = t.get_pointee_type() {
+                result.block_return_type = block_type
+                    .get_result_type()
+                    .map(|return_type| Box::new(Self::from_clang(&return_type)));
+                result.block_parameter_types = block_type
+                    .get_argument_types()
+                    .map(|parameter_types| parameter_types.iter().map(Self::from_clang).collect());
+            }
+        }
+        if result.is_array() {
+            result.array_element_type = t
+                .get_element_type()
+                .map(|et| Box::new(Self::from_clang(&et)));
+            result.array_size = t.get_size();
+        }
+        let canonical_type = t.get_canonical_type();
+        // Other kinds only differ from their canonical type when they are built from typedefs,
+        // e.g. `FILE_T *` for `struct file *`, which is then needed to match them by that name.
+        if matches!(result.type_kind, TypeKind::Typedef | TypeKind::Elaborated)
+            || canonical_type.get_display_name() != result.type_name
+        {
+            result.canonical_type = Some(Box::new(Self::from_clang(&canonical_type)));
+        }
+        if result.type_kind == TypeKind::Elaborated {
+            result.elaborated_inner = t
+                .get_elaborated_type()
+                .map(|inner_type| Box::new(Self::from_clang(&inner_type)));
+        }
+        // libclang before 11 has no dedicated kind for `_Atomic`, it reports such types as
+        // `Unexposed` and only the display name gives them away.
+        result.is_atomic = result.tagless_name().starts_with("_Atomic");
+        result
+    }
+
+    /// libclang reports `__auto_type` as `Auto`, but `typeof` only as `Unexposed`.
+    fn is_deduced_type(t: &clang::Type) -> bool {
+        match t.get_kind() {
+            TypeKind::Auto => true,
+            TypeKind::Unexposed => {
+                let name = t.get_display_name();
+                ["typeof", "__typeof", "__auto_type"]
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+            }
+            _ => false,
+        }
+    }
+
+    /// The spelling of the type wrapped by `_Atomic(...)`, if this is an atomic type.
+    pub fn atomic_value_type_name(&self) -> Option<&str> {
+        if !self.is_atomic {
+            return None;
+        }
+        let name = self.tagless_name().trim_start_matches("_Atomic").trim();
+        Some(
+            name.strip_prefix('(')
+                .and_then(|name| name.strip_suffix(')'))
+                .unwrap_or(name),
+        )
+    }
+
+    #[inline]
+    pub fn elaborated_inner(&self) -> Option<&Type> {
+        self.elaborated_inner.as_deref()
+    }
+
+    pub fn resolve_canonical(&self) -> &Type {
+        let mut resolved_type = self;
+        while let Some(canonical_type) = &resolved_type.canonical_type {
+            resolved_type = canonical_type;
+        }
+        resolved_type
+    }
+
+    /// Whether the type is spelled `type_name` as written or after resolving typedefs.
+    pub fn matches_name(&self, type_name: &str) -> bool {
+        self.type_name == type_name || self.resolve_canonical().type_name == type_name
+    }
+
+    /// The type name without qualifiers or the `struct`/`union`/`enum`/`class` tag.
+    pub fn tagless_name(&self) -> &str {
+        let mut name = self.type_name.as_str();
+        for prefix in [
+            "const ",
+            "volatile ",
+            "struct ",
+            "union ",
+            "enum ",
+            "class ",
+        ]
+        .iter()
+        {
+            if name.starts_with(prefix) {
+                name = &name[prefix.len()..];
+            }
+        }
+        name
+    }
+
+    /// How many pointers must be followed to reach a non-pointer type, e.g. 2 for `void **`.
+    /// Arrays are not indirections, so `int (*)[4]` has a depth of 1.
+    pub fn pointer_depth(&self) -> usize {
+        let mut pointer_depth = 0;
+        let mut current_type = self;
+        while let Some(pointee_type) = &current_type.pointee_type {
+            pointer_depth += 1;
+            current_type = pointee_type;
+        }
+        pointer_depth
+    }
+
+    /// The innermost non-pointer type, e.g. `int[4]` for `int (*)[4]`.
+    pub fn base_type(&self) -> &Type {
+        let mut current_type = self;
+        while let Some(pointee_type) = &current_type.pointee_type {
+            current_type = pointee_type;
+        }
+        current_type
+    }
+
+    /// Strips pointers and arrays off this type and returns the user-defined type left underneath,
+    /// or `None` if it bottoms out in a builtin type.
+    pub fn referenced_type(&self) -> Option<&Type> {
+        if let Some(pointee_type) = &self.pointee_type {
+            return pointee_type.referenced_type();
+        }
+        if let Some(element_type) = &self.array_element_type {
+            return element_type.referenced_type();
+        }
+        match self.type_kind {
+            TypeKind::Typedef | TypeKind::Elaborated | TypeKind::Record | TypeKind::Enum => {
+                Some(self)
+            }
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn is_anonymous_record(&self) -> bool {
+        self.type_name.contains("(anonymous") || self.type_name.contains("(unnamed")
+    }
+
+    #[inline]
+    pub fn is_array(&self) -> bool {
+        matches!(
+            self.type_kind,
+            TypeKind::ConstantArray | TypeKind::IncompleteArray | TypeKind::VariableArray
+        )
+    }
+
+    #[inline]
+    pub fn is_void(&self) -> bool {
+        self.type_kind == TypeKind::Void
+    }
+
+    /// Whether this is a builtin integer, character or floating point type. Typedefs of those
+    /// do not count.
+    #[inline]
+    pub fn is_primitive(&self) -> bool {
+        matches!(
+            self.type_kind,
+            TypeKind::Bool
+                | TypeKind::CharS
+                | TypeKind::CharU
+                | TypeKind::SChar
+                | TypeKind::UChar
+                | TypeKind::WChar
+                | TypeKind::Char16
+                | TypeKind::Char32
+                | TypeKind::Short
+                | TypeKind::UShort
+                | TypeKind::Int
+                | TypeKind::UInt
+                | TypeKind::Long
+                | TypeKind::ULong
+                | TypeKind::LongLong
+                | TypeKind::ULongLong
+                | TypeKind::Int128
+                | TypeKind::UInt128
+                | TypeKind::Half
+                | TypeKind::Float
+                | TypeKind::Double
+                | TypeKind::LongDouble
+                | TypeKind::Float128
+        )
+    }
+
+    #[inline]
+    pub fn is_pointer(&self) -> bool {
+        self.type_kind == TypeKind::Pointer
+    }
+
+    #[inline]
+    pub fn is_record(&self) -> bool {
+        self.type_kind == TypeKind::Record
+    }
+
+    /// Whether Rust code needs `unsafe` to use a value of this type: raw pointers and function
+    /// pointers, also as array elements or behind typedefs. The fields of a record are not part
+    /// of its type, `SourceFile::type_requires_unsafe` looks into those as well.
+    pub fn requires_unsafe(&self) -> bool {
+        match self.type_kind {
+            TypeKind::Pointer
+            | TypeKind::BlockPointer
+            | TypeKind::FunctionPrototype
+            | TypeKind::FunctionNoPrototype => true,
+            _ => [
+                self.array_element_type.as_deref(),
+                self.elaborated_inner(),
+                self.canonical_type.as_deref(),
+            ]
+            .iter()
+            .flatten()
+            .any(|inner_type| inner_type.requires_unsafe()),
+        }
+    }
+
+    /// The Rust type a builtin C type maps to directly, or `None` if the type is not a builtin
+    /// one or has no exact Rust counterpart, like `long double`.
+    pub fn rust_type_name(&self) -> Option<&'static str> {
+        match self.type_kind {
+            TypeKind::Bool => Some("bool"),
+            TypeKind::CharS | TypeKind::CharU => Some("std::os::raw::c_char"),
+            TypeKind::SChar => Some("std::os::raw::c_schar"),
+            TypeKind::UChar => Some("std::os::raw::c_uchar"),
+            TypeKind::WChar => Some("i32"),
+            TypeKind::Char16 => Some("u16"),
+            TypeKind::Char32 => Some("u32"),
+            TypeKind::Short => Some("i16"),
+            TypeKind::UShort => Some("u16"),
+            TypeKind::Int => Some("i32"),
+            TypeKind::UInt => Some("u32"),
+            TypeKind::Long => Some("std::os::raw::c_long"),
+            TypeKind::ULong => Some("std::os::raw::c_ulong"),
+            TypeKind::LongLong => Some("i64"),
+            TypeKind::ULongLong => Some("u64"),
+            TypeKind::Int128 => Some("i128"),
+            TypeKind::UInt128 => Some("u128"),
+            TypeKind::Float => Some("f32"),
+            TypeKind::Double => Some("f64"),
+            TypeKind::Void => Some("std::os::raw::c_void"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FunctionProtoInfo {
+    pub return_type: Box<Type>,
+    pub parameter_types: Vec<Type>,
+}
+
+impl FunctionProtoInfo {
+    pub fn from_clang(t: &clang::Type) -> Option<Self> {
+        match t.get_kind() {
+            TypeKind::FunctionPrototype | TypeKind::FunctionNoPrototype => Some(Self {
+                return_type: Box::new(Type::from_clang(&t.get_result_type()?)),
+                parameter_types: t
+                    .get_argument_types()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(Type::from_clang)
+                    .collect(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VisibilityKind {
+    Default,
+    Hidden,
+    Protected,
+    Internal,
+}
+
+impl VisibilityKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(VisibilityKind::Default),
+            "hidden" => Some(VisibilityKind::Hidden),
+            "protected" => Some(VisibilityKind::Protected),
+            "internal" => Some(VisibilityKind::Internal),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Attribute {
+    Deprecated {
+        message: Option<String>,
+    },
+    Visibility {
+        level: VisibilityKind,
+    },
+    NoReturn,
+    /// `warn_unused_result` or `[[nodiscard]]`.
+    WarnUnusedResult,
+    Packed,
+    AlignAs {
+        bytes: u32,
+    },
+    Unknown {
+        spelling: String,
+    },
+}
+
+impl Attribute {
+    /// Collects the attributes that libclang attaches as children of `entity`.
+    fn collect(entity: &Entity) -> Vec<Attribute> {
+        entity
+            .get_children()
+            .iter()
+            .filter(|child_entity| child_entity.is_attribute())
+            .map(Attribute::from_clang)
+            .collect()
+    }
+
+    /// Most GCC attributes come through as `UnexposedAttr`, so they are told apart by the
+    /// tokens they were spelled with, e.g. `deprecated("use bar")` or `aligned(16)`.
+    fn from_clang(entity: &Entity) -> Self {
+        if entity.get_kind() == EntityKind::PackedAttr {
+            return Attribute::Packed;
+        }
+        let tokens = entity
+            .get_range()
+            .map(|range| {
+                range
+                    .tokenize()
+                    .iter()
+                    .map(|token| token.get_spelling())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let spelling = tokens
+            .first()
+            .map(|token| token.trim_matches('_').to_owned())
+            .or_else(|| entity.get_display_name())
+            .unwrap_or_default();
+        let string_argument = tokens
+            .iter()
+            .find(|token| token.starts_with('"'))
+            .map(|token| token.trim_matches('"').to_owned());
+        match spelling.as_str() {
+            "deprecated" => Attribute::Deprecated {
+                message: string_argument,
+            },
+            "visibility" => match string_argument.as_deref().and_then(VisibilityKind::parse) {
+                Some(level) => Attribute::Visibility { level },
+                None => Attribute::Unknown { spelling },
+            },
+            "noreturn" | "Noreturn" => Attribute::NoReturn,
+            "warn_unused_result" | "nodiscard" => Attribute::WarnUnusedResult,
+            "packed" => Attribute::Packed,
+            "aligned" | "alignas" | "Alignas" => {
+                match tokens.iter().find_map(|token| token.parse().ok()) {
+                    Some(bytes) => Attribute::AlignAs { bytes },
+                    None => Attribute::Unknown { spelling },
+                }
+            }
+            _ => Attribute::Unknown { spelling },
+        }
+    }
+}
+
+/// A Microsoft `__declspec` on a function or variable, as found in Windows SDK headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeclSpec {
+    DllImport,
+    DllExport,
+    NoInline,
+}
+
+impl DeclSpec {
+    fn from_clang(entity: &Entity) -> Option<Self> {
+        for child_entity in entity.get_children() {
+            match child_entity.get_kind() {
+                EntityKind::DllImport => return Some(DeclSpec::DllImport),
+                EntityKind::DllExport => return Some(DeclSpec::DllExport),
+                _ => {}
+            }
+        }
+        // `noinline` has no cursor kind of its own, so it is looked for in the tokens.
+        let tokens = entity
+            .get_range()
+            .map(|range| {
+                range
+                    .tokenize()
+                    .iter()
+                    .map(|token| token.get_spelling())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        tokens.windows(4).find_map(|window| match window {
+            [declspec, open, argument, close]
+                if declspec == "__declspec" && open == "(" && close == ")" =>
+            {
+                match argument.as_str() {
+                    "dllimport" => Some(DeclSpec::DllImport),
+                    "dllexport" => Some(DeclSpec::DllExport),
+                    "noinline" => Some(DeclSpec::NoInline),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceFile {
+    pub path: String,
+    pub type_declares: Vec<TypeDeclare>,
+    pub forward_declarations: Vec<ForwardDeclaration>,
+    pub function_declares: Vec<FunctionDeclare>,
+    pub function_templates: Vec<FunctionTemplate>,
+    pub class_templates: Vec<ClassTemplate>,
+    pub variable_declares: Vec<VariableDeclare>,
+    pub constants: Vec<ConstantDeclare>,
+    pub constexpr_declares: Vec<ConstexprDeclare>,
+    pub macro_definitions: Vec<MacroDefinition>,
+    pub macro_functions: Vec<MacroFunctionDeclare>,
+    pub macro_expansions: Vec<MacroExpansion>,
+    pub include_directives: Vec<IncludeDirective>,
+    pub namespace_declares: Vec<NamespaceDeclare>,
+    pub include_guard: Option<IncludeGuard>,
+    /// The target the file was parsed for, `None` for the current platform.
+    pub target_triple: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    registry: RegistryCache,
+}
+
+impl SourceFile {
+    pub fn new(path: String) -> Self {
+        SourceFile {
+            path,
+            type_declares: Vec::new(),
+            forward_declarations: Vec::new(),
+            function_declares: Vec::new(),
+            function_templates: Vec::new(),
+            class_templates: Vec::new(),
+            variable_declares: Vec::new(),
+            constants: Vec::new(),
+            constexpr_declares: Vec::new(),
+            macro_definitions: Vec::new(),
+            macro_functions: Vec::new(),
+            macro_expansions: Vec::new(),
+            include_directives: Vec::new(),
+            namespace_declares: Vec::new(),
+            include_guard: None,
+            target_triple: None,
+            registry: RegistryCache::default(),
+        }
+    }
+
+    pub fn retain_extern_functions(&mut self) {
+        self.function_declares
+            .retain(|function_declare| function_declare.linkage() == Some(Linkage::External));
+    }
+
+    /// Combines the declarations of another translation unit into this one. Declarations that
+    /// share a USR are kept once, preferring a definition over a forward declaration; two
+    /// definitions with the same USR but different bodies are reported as a conflict.
+    pub fn merge(mut self, other: SourceFile) -> Result<SourceFile, MergeError> {
+        self.clear_registry();
+        for type_declare in other.type_declares {
+            let existing_index = type_declare.usr().and_then(|usr| {
+                let same_usr = |declare: &TypeDeclare| declare.usr() == Some(usr);
+                self.type_declares
+                    .iter()
+                    .position(|declare| same_usr(declare) && declare.is_definition())
+                    .or_else(|| self.type_declares.iter().position(same_usr))
+            });
+            let existing_index = match existing_index {
+                Some(existing_index) => existing_index,
+                None => {
+                    self.type_declares.push(type_declare);
+                    continue;
+                }
+            };
+            let existing_declare = &mut self.type_declares[existing_index];
+            if !type_declare.is_definition() {
+                continue;
+            } else if !existing_declare.is_definition() {
+                let typedef_name = existing_declare.typedef_name().map(|name| name.to_owned());
+                *existing_declare = type_declare;
+                if let Some(typedef_name) = typedef_name {
+                    if existing_declare.typedef_name().is_none() {
+                        existing_declare.set_typedef_name(typedef_name);
+                    }
+                }
+            } else if !existing_declare.has_same_body(&type_declare) {
+                return Err(MergeError::conflicting_definition(
+                    existing_declare,
+                    &type_declare,
+                ));
+            }
+        }
+        for forward_declaration in other.forward_declarations {
+            if !self.forward_declarations.iter().any(|declaration| {
+                declaration.name == forward_declaration.name
+                    && declaration.kind == forward_declaration.kind
+            }) {
+                self.forward_declarations.push(forward_declaration);
+            }
+        }
+        for function_declare in other.function_declares {
+            if !Self::contains_usr(&self.function_declares, &function_declare) {
+                self.function_declares.push(function_declare);
+            }
+        }
+        for variable_declare in other.variable_declares {
+            if !Self::contains_usr(&self.variable_declares, &variable_declare) {
+                self.variable_declares.push(variable_declare);
+            }
+        }
+        for constant in other.constants {
+            if !Self::contains_usr(&self.constants, &constant) {
+                self.constants.push(constant);
+            }
+        }
+        for constexpr_declare in other.constexpr_declares {
+            if !Self::contains_usr(&self.constexpr_declares, &constexpr_declare) {
+                self.constexpr_declares.push(constexpr_declare);
+            }
+        }
+        for function_template in other.function_templates {
+            if !Self::contains_usr(&self.function_templates, &function_template) {
+                self.function_templates.push(function_template);
+            }
+        }
+        for class_template in other.class_templates {
+            if !Self::contains_usr(&self.class_templates, &class_template) {
+                self.class_templates.push(class_template);
+            }
+        }
+        self.macro_definitions.extend(other.macro_definitions);
+        self.macro_functions.extend(other.macro_functions);
+        self.macro_expansions.extend(other.macro_expansions);
+        self.include_directives.extend(other.include_directives);
+        self.namespace_declares.extend(other.namespace_declares);
+        self.mark_recursive_types();
+        Ok(self)
+    }
+
+    fn contains_usr<T: EntityVisitor>(declares: &[T], declare: &T) -> bool {
+        match declare.usr() {
+            Some(usr) => declares
+                .iter()
+                .any(|existing_declare| existing_declare.usr() == Some(usr)),
+            None => false,
+        }
+    }
+
+    pub fn filter_functions<P: Fn(&FunctionDeclare) -> bool>(
+        &self,
+        predicate: P,
+    ) -> Vec<&FunctionDeclare> {
+        self.function_declares
+            .iter()
+            .filter(|function_declare| predicate(function_declare))
+            .collect()
+    }
+
+    pub fn filter_types<P: Fn(&dyn TypeDeclaration) -> bool>(
+        &self,
+        predicate: P,
+    ) -> Vec<&dyn TypeDeclaration> {
+        self.type_declares
+            .iter()
+            .map(|type_declare| type_declare as &dyn TypeDeclaration)
+            .filter(|type_declare| predicate(*type_declare))
+            .collect()
+    }
+
+    /// Keeps only the functions, types and variables whose name starts with `prefix`.
+    pub fn retain_by_name_prefix(&mut self, prefix: &str) {
+        self.retain_functions_by_name_prefix(prefix);
+        self.retain_types_by_name_prefix(prefix);
+        self.variable_declares
+            .retain(|variable_declare| variable_declare.name.starts_with(prefix));
+    }
+
+    pub fn retain_functions_by_name_prefix(&mut self, prefix: &str) {
+        self.function_declares
+            .retain(|function_declare| function_declare.function_name.starts_with(prefix));
+    }
+
+    /// Keeps only the types whose tag name or typedef name starts with `prefix`.
+    pub fn retain_types_by_name_prefix(&mut self, prefix: &str) {
+        self.type_declares.retain(|type_declare| {
+            type_declare
+                .name()
+                .into_iter()
+                .chain(type_declare.typedef_name())
+                .any(|name| name.starts_with(prefix))
+        });
+    }
+
+    /// Maps each including file to the files it includes, in include order.
+    pub fn include_graph(&self) -> HashMap<String, Vec<String>> {
+        let mut include_graph: HashMap<String, Vec<String>> = HashMap::new();
+        for include_directive in self.include_directives.iter() {
+            if let Some(location) = &include_directive.location {
+                include_graph
+                    .entry(location.path.to_owned())
+                    .or_default()
+                    .push(include_directive.included_path.to_owned());
+            }
+        }
+        include_graph
+    }
+
+    /// Drops function definitions, keeping only prototypes.
+    pub fn retain_function_declarations(&mut self) {
+        self.function_declares
+            .retain(|function_declare| !function_declare.is_definition);
+    }
+
+    /// Drops inline functions, which usually have no symbol to bind to.
+    pub fn skip_inline_functions(&mut self) {
+        self.function_declares
+            .retain(|function_declare| !function_declare.is_inline);
+    }
+
+    /// Functions that are only declared, without a body.
+    pub fn declarations(&self) -> impl Iterator<Item = &FunctionDeclare> {
+        self.function_declares
+            .iter()
+            .filter(|function_declare| !function_declare.is_definition)
+    }
+
+    /// Functions that are defined with a body.
+    pub fn definitions(&self) -> impl Iterator<Item = &FunctionDeclare> {
+        self.function_declares
+            .iter()
+            .filter(|function_declare| function_declare.is_definition)
+    }
+
+    pub fn find_function_by_name<'a>(&'a self, name: &str) -> Option<&'a FunctionDeclare> {
+        self.function_declares
+            .iter()
+            .find(|function_declare| function_declare.function_name == name)
+    }
+
+    /// Finds a type by its tag name or typedef name, preferring its definition over a forward
+    /// declaration.
+    pub fn find_type_by_name<'a>(&'a self, name: &str) -> Option<&'a dyn TypeDeclaration> {
+        self.registry().lookup_by_name(self, name)
+    }
+
+    /// Like `Type::requires_unsafe`, but also true for structs, unions and classes declared in
+    /// this file that hold a pointer in one of their fields, at any depth.
+    pub fn type_requires_unsafe(&self, t: &Type) -> bool {
+        self.type_requires_unsafe_within(t, &mut HashSet::new())
+    }
+
+    fn type_requires_unsafe_within(&self, t: &Type, visited_names: &mut HashSet<String>) -> bool {
+        if t.requires_unsafe() {
+            return true;
+        }
+        // Without pointers, whatever the type refers to is held by value.
+        let name = match t.referenced_type() {
+            Some(referenced_type) => referenced_type.resolve_canonical().tagless_name(),
+            None => return false,
+        };
+        if !visited_names.insert(name.to_owned()) {
+            return false;
+        }
+        let members = match self
+            .find_type_declare(name)
+            .map(|index| &self.type_declares[index])
+        {
+            Some(TypeDeclare::Struct(struct_declare)) => &struct_declare.fields,
+            Some(TypeDeclare::Union(union_declare)) => &union_declare.fields,
+            Some(TypeDeclare::Class(class_declare)) => &class_declare.fields,
+            _ => return false,
+        };
+        self.members_require_unsafe(members, visited_names)
+    }
+
+    fn members_require_unsafe(
+        &self,
+        members: &[MemberDeclare],
+        visited_names: &mut HashSet<String>,
+    ) -> bool {
+        members.iter().any(|member| match member {
+            MemberDeclare::Field(field_declare) => field_declare
+                .field_type
+                .as_ref()
+                .map(|field_type| self.type_requires_unsafe_within(field_type, visited_names))
+                .unwrap_or(false),
+            // Records declared inside another one are not in `type_declares`.
+            MemberDeclare::Struct(struct_declare) => {
+                self.members_require_unsafe(&struct_declare.fields, visited_names)
+            }
+            MemberDeclare::Union(union_declare) => {
+                self.members_require_unsafe(&union_declare.fields, visited_names)
+            }
+            MemberDeclare::Enum(_) => false,
+        })
+    }
+
+    pub fn functions_by_prefix<'a>(
+        &'a self,
+        prefix: &str,
+    ) -> impl Iterator<Item = &'a FunctionDeclare> {
+        let prefix = prefix.to_owned();
+        self.function_declares
+            .iter()
+            .filter(move |function_declare| function_declare.function_name.starts_with(&prefix))
+    }
+
+    /// Functions whose return type is spelled `type_name`, either as written or as its canonical
+    /// type, so that `struct file *` also finds functions returning a `FILE *` typedef of it.
+    pub fn functions_returning_type<'a>(&'a self, type_name: &str) -> Vec<&'a FunctionDeclare> {
+        self.function_declares
+            .iter()
+            .filter(|function_declare| {
+                function_declare
+                    .return_type
+                    .as_ref()
+                    .map(|return_type| return_type.matches_name(type_name))
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+
+    /// Functions with at least one parameter of type `type_name`, matched like in
+    /// `functions_returning_type`.
+    pub fn functions_taking_type<'a>(&'a self, type_name: &str) -> Vec<&'a FunctionDeclare> {
+        self.function_declares
+            .iter()
+            .filter(|function_declare| {
+                function_declare
+                    .parameters
+                    .iter()
+                    .any(|parameter| match parameter {
+                        FunctionParameter::Named(parameter_declare) => parameter_declare
+                            .parameter_type
+                            .as_ref()
+                            .map(|parameter_type| parameter_type.matches_name(type_name))
+                            .unwrap_or(false),
+                        FunctionParameter::Variadic(_) => false,
+                    })
+            })
+            .collect()
+    }
+
+    /// Types whose tag name or typedef name starts with `prefix`.
+    pub fn types_by_prefix<'a>(
+        &'a self,
+        prefix: &str,
+    ) -> impl Iterator<Item = &'a dyn TypeDeclaration> {
+        let prefix = prefix.to_owned();
+        self.type_declares
+            .iter()
+            .filter(move |type_declare| {
+                type_declare
+                    .name()
+                    .into_iter()
+                    .chain(type_declare.typedef_name())
+                    .any(|name| name.starts_with(&prefix))
+            })
+            .map(|type_declare| type_declare as &dyn TypeDeclaration)
+    }
+
+    /// Moves type declarations without a body (`struct Foo;`) out of `type_declares` and into
+    /// `forward_declarations`.
+    pub fn skip_forward_declarations(&mut self) {
+        let (definitions, forward_declarations): (Vec<_>, Vec<_>) = self
+            .type_declares
+            .drain(..)
+            .partition(|type_declare| type_declare.is_definition());
+        self.type_declares = definitions;
+        self.forward_declarations.extend(
+            forward_declarations
+                .into_iter()
+                .filter_map(ForwardDeclaration::from_type_declare),
+        );
+    }
+
+    pub fn build_symbol_index(&self) -> SymbolIndex<'_> {
+        let mut symbol_index = SymbolIndex::default();
+        for type_declare in self.type_declares.iter() {
+            symbol_index.insert_type_declare(type_declare);
+        }
+        for function_declare in self.function_declares.iter() {
+            symbol_index.insert(
+                function_declare.usr(),
+                EntityRef::Function(function_declare),
+            );
+        }
+        symbol_index
+    }
+
+    /// Orders the type declarations so that every type comes after the types it holds by value.
+    /// Typedef names used behind pointers are placed first where possible, struct tags need no
+    /// more than a forward declaration there. Otherwise the declaration order is kept.
+    pub fn topological_sort_types(&self) -> Result<Vec<&TypeDeclare>, CycleError> {
+        fn visit<'a>(
+            source_file: &'a SourceFile,
+            index: usize,
+            visited: &mut [bool],
+            sorted: &mut Vec<&'a TypeDeclare>,
+        ) {
+            visited[index] = true;
+            for (name, _) in source_file.type_declares[index].dependency_names() {
+                match source_file.find_type_declare(name) {
+                    Some(dependency_index) if !visited[dependency_index] => {
+                        visit(source_file, dependency_index, visited, sorted)
+                    }
+                    // A cycle through a pointer is fine, the forward declaration covers it.
+                    _ => {}
+                }
+            }
+            sorted.push(&source_file.type_declares[index]);
+        }
+
+        // Cycles are looked for among value references alone first, as a pointer edge taken
+        // while ordering could hide one.
+        if let Some(type_names) = TypeGraph::new(self).value_cycle() {
+            return Err(CycleError { type_names });
+        }
+        let mut visited = vec![false; self.type_declares.len()];
+        let mut sorted = Vec::with_capacity(self.type_declares.len());
+        for index in 0..self.type_declares.len() {
+            if !visited[index] {
+                visit(self, index, &mut visited, &mut sorted);
+            }
+        }
+        Ok(sorted)
+    }
+
+    /// The position of the type declared under `name`, preferring its definition.
+    fn find_type_declare(&self, name: &str) -> Option<usize> {
+        self.registry().by_name.get(name).copied()
+    }
+
+    /// Flags structs, unions and classes that can reach themselves through their fields, following
+    /// pointers, arrays and typedefs at most `MAX_RECURSION_DEPTH` declarations deep. Records
+    /// nested in fields and declared in namespaces are flagged too, all of them looked up by
+    /// their unqualified name.
+    fn mark_recursive_types(&mut self) {
+        const MAX_RECURSION_DEPTH: usize = 16;
+        let mut nodes = Vec::new();
+        for type_declare in self.type_declares.iter() {
+            TypeNode::collect(type_declare, &mut nodes);
+        }
+        for namespace_declare in self.namespace_declares.iter() {
+            TypeNode::collect_namespace(namespace_declare, &mut nodes);
+        }
+        let find_node = |name: &str| nodes.iter().find(|node| node.names.contains(&name));
+        let recursive_names: HashSet<String> = nodes
+            .iter()
+            .filter(|node| node.is_record)
+            .filter(|node| {
+                let mut visited_names = HashSet::new();
+                let mut pending = vec![(*node, 0)];
+                while let Some((current_node, depth)) = pending.pop() {
+                    for &referenced_name in current_node.referenced_names.iter() {
+                        if node.names.contains(&referenced_name) {
+                            return true;
+                        }
+                        if depth + 1 < MAX_RECURSION_DEPTH && visited_names.insert(referenced_name)
+                        {
+                            pending
+                                .extend(find_node(referenced_name).map(|node| (node, depth + 1)));
+                        }
+                    }
+                }
+                false
+            })
+            .flat_map(|node| node.names.iter().map(|&name| name.to_owned()))
+            .collect();
+
+        fn mark_members(members: &mut [MemberDeclare], recursive_names: &HashSet<String>) {
+            for member in members.iter_mut() {
+                match member {
+                    MemberDeclare::Struct(declare) => {
+                        declare.is_recursive =
+                            is_named(&declare.struct_name, &declare.typedef_name, recursive_names);
+                        mark_members(&mut declare.fields, recursive_names);
+                    }
+                    MemberDeclare::Union(declare) => {
+                        declare.is_recursive =
+                            is_named(&declare.union_name, &declare.typedef_name, recursive_names);
+                        mark_members(&mut declare.fields, recursive_names);
+                    }
+                    MemberDeclare::Field(_) | MemberDeclare::Enum(_) => {}
+                }
+            }
+        }
+        fn mark(type_declares: &mut [TypeDeclare], recursive_names: &HashSet<String>) {
+            for type_declare in type_declares.iter_mut() {
+                match type_declare {
+                    TypeDeclare::Struct(declare) => {
+                        declare.is_recursive =
+                            is_named(&declare.struct_name, &declare.typedef_name, recursive_names);
+                        mark_members(&mut declare.fields, recursive_names);
+                    }
+                    TypeDeclare::Union(declare) => {
+                        declare.is_recursive =
+                            is_named(&declare.union_name, &declare.typedef_name, recursive_names);
+                        mark_members(&mut declare.fields, recursive_names);
+                    }
+                    TypeDeclare::Class(declare) => {
+                        declare.is_recursive =
+                            is_named(&declare.class_name, &declare.typedef_name, recursive_names);
+                        mark_members(&mut declare.fields, recursive_names);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        fn mark_namespace(
+            namespace_declare: &mut NamespaceDeclare,
+            recursive_names: &HashSet<String>,
+        ) {
+            mark(&mut namespace_declare.type_declares, recursive_names);
+            for nested_namespace in namespace_declare.nested_namespaces.iter_mut() {
+                mark_namespace(nested_namespace, recursive_names);
+            }
+        }
+        fn is_named(
+            name: &Option<String>,
+            typedef_name: &Option<String>,
+            names: &HashSet<String>,
+        ) -> bool {
+            name.iter()
+                .chain(typedef_name)
+                .any(|name| names.contains(name))
+        }
+
+        mark(&mut self.type_declares, &recursive_names);
+        for namespace_declare in self.namespace_declares.iter_mut() {
+            mark_namespace(namespace_declare, &recursive_names);
+        }
+    }
+
+    fn parse_type_declare(
+        current_entity: &Entity,
+        parent_entity: &Entity,
+        declares: &mut Vec<TypeDeclare>,
+    ) -> Result<(), AstError> {
+        match current_entity.get_kind() {
+            EntityKind::EnumDecl => {
+                if let Some(name) = current_entity.get_name() {
+                    declares.push(
+                        TypeDeclare::Enum(EnumDeclare::new(Some(name), None))
+                            .visited(current_entity, parent_entity)?,
+                    );
+                }
+            }
+            EntityKind::StructDecl => {
+                if let Some(name) = current_entity.get_name() {
+                    declares.push(
+                        TypeDeclare::Struct(StructDeclare::new(Some(name), None))
+                            .visited(current_entity, parent_entity)?,
+                    );
+                }
+            }
+            EntityKind::UnionDecl => {
+                if let Some(name) = current_entity.get_name() {
+                    declares.push(
+                        TypeDeclare::Union(UnionDeclare::new(Some(name), None))
+                            .visited(current_entity, parent_entity)?,
+                    );
+                }
+            }
+            EntityKind::ClassDecl => {
+                if let Some(name) = current_entity.get_name() {
+                    declares.push(
+                        TypeDeclare::Class(ClassDeclare::new(Some(name), None))
+                            .visited(current_entity, parent_entity)?,
+                    );
+                }
+            }
+            EntityKind::TypedefDecl => {
+                let underlying_type = current_entity
+                    .get_typedef_underlying_type()
+                    .ok_or_else(|| AstError::missing_type(current_entity))?;
+                let declaration_entity =
+                    underlying_type
+                        .get_declaration()
+                        .filter(|declaration_entity| {
+                            declaration_entity.get_kind() != EntityKind::TypedefDecl
+                        });
+                if let Some(declaration_entity) = declaration_entity {
+                    if let Some(declare) = declares.iter_mut().find(|declare| {
+                        if let (Some(name), Some(typedef_name)) =
+                            (declare.name(), current_entity.get_name())
+                        {
+                            return name == typedef_name;
+                        }
+                        false
+                    }) {
+                        if let Some(typedef_name) = current_entity.get_name() {
+                            declare.set_typedef_name(typedef_name);
+                        }
+                    } else {
+                        let declare = match declaration_entity.get_kind() {
+                            EntityKind::EnumDecl => {
+                                TypeDeclare::Enum(EnumDeclare::new(None, current_entity.get_name()))
+                                    .visited(&declaration_entity, parent_entity)?
+                            }
+                            EntityKind::StructDecl => TypeDeclare::Struct(StructDeclare::new(
+                                None,
+                                current_entity.get_name(),
+                            ))
+                            .visited(&declaration_entity, parent_entity)?,
+                            EntityKind::UnionDecl => TypeDeclare::Union(UnionDeclare::new(
+                                None,
+                                current_entity.get_name(),
+                            ))
+                            .visited(&declaration_entity, parent_entity)?,
+                            EntityKind::ClassDecl => TypeDeclare::Class(ClassDeclare::new(
+                                None,
+                                current_entity.get_name(),
+                            ))
+                            .visited(&declaration_entity, parent_entity)?,
+                            _ => return Err(AstError::unexpected_entity_kind(&declaration_entity)),
+                        };
+                        declares.push(declare);
+                    }
+                } else if let Some(function_type) = underlying_type
+                    .get_pointee_type()
+                    .filter(|_| underlying_type.get_kind() == TypeKind::Pointer)
+                    .filter(|pointee_type| {
+                        matches!(
+                            pointee_type.get_kind(),
+                            TypeKind::FunctionPrototype | TypeKind::FunctionNoPrototype
+                        )
+                    })
+                {
+                    let typedef_name = current_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(current_entity))?;
+                    declares.push(
+                        TypeDeclare::FunctionPointer(FunctionPointerTypeDeclare::from_clang(
+                            typedef_name,
+                            &function_type,
+                        ))
+                        .visited(current_entity, parent_entity)?,
+                    );
+                } else {
+                    let typedef_name = current_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(current_entity))?;
+                    declares.push(
+                        TypeDeclare::Typedef(TypedefDeclare::new(
+                            typedef_name,
+                            Type::from_clang(&underlying_type),
+                        ))
+                        .visited(current_entity, parent_entity)?,
+                    );
+                }
+            }
+            _ => return Err(AstError::unexpected_entity_kind(current_entity)),
+        }
+        Ok(())
+    }
+}
+
+impl EntityVisitor for SourceFile {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.path.as_ref())
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_name: String) {
+        self.path = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::TranslationUnit
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.visit_translation_unit(current_entity, FilterMode::MainFile)
+    }
+}
+
+impl SourceFile {
+    /// Walks the top-level declarations of a translation unit that `filter_mode` accepts.
+    fn visit_translation_unit(
+        &mut self,
+        current_entity: &Entity,
+        filter_mode: FilterMode,
+    ) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.include_guard = current_entity
+            .get_name()
+            .and_then(|path| current_entity.get_translation_unit().get_file(path))
+            .and_then(|file| file.get_contents())
+            .and_then(|contents| IncludeGuard::detect(&contents));
+        self.visit_declarations(current_entity, filter_mode)?;
+        self.mark_recursive_types();
+        Ok(())
+    }
+
+    /// Walks the declarations directly in a translation unit or a linkage specification. Kinds
+    /// of declarations that are not modelled, such as using-declarations and static assertions,
+    /// are skipped, like they are in namespaces.
+    fn visit_declarations(
+        &mut self,
+        parent_entity: &Entity,
+        filter_mode: FilterMode,
+    ) -> Result<(), AstError> {
+        let children = parent_entity.get_children();
+        for next_entity in children.iter().filter(|entity| filter_mode.accepts(entity)) {
+            match next_entity.get_kind() {
+                // `extern "C" { ... }`, as C headers wrap their declarations in for C++.
+                EntityKind::LinkageSpec => self.visit_declarations(next_entity, filter_mode)?,
+                EntityKind::EnumDecl
+                | EntityKind::StructDecl
+                | EntityKind::UnionDecl
+                | EntityKind::ClassDecl
+                | EntityKind::TypedefDecl => {
+                    Self::parse_type_declare(next_entity, parent_entity, &mut self.type_declares)?;
+                }
+                EntityKind::FunctionDecl => {
+                    let function_name = next_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(next_entity))?;
+                    self.function_declares.push(
+                        FunctionDeclare::new(function_name).visited(next_entity, parent_entity)?,
+                    );
+                }
+                EntityKind::VarDecl => {
+                    let variable_name = next_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(next_entity))?;
+                    if ConstexprDeclare::is_constexpr(next_entity) {
+                        let value_type = next_entity
+                            .get_type()
+                            .ok_or_else(|| AstError::missing_type(next_entity))?;
+                        self.constexpr_declares.push(
+                            ConstexprDeclare::new(variable_name, Type::from_clang(&value_type))
+                                .visited(next_entity, parent_entity)?,
+                        );
+                    } else if ConstantDeclare::is_constant(next_entity) {
+                        self.constants.push(
+                            ConstantDeclare::new(variable_name)
+                                .visited(next_entity, parent_entity)?,
+                        );
+                    } else {
+                        self.variable_declares.push(
+                            VariableDeclare::new(variable_name)
+                                .visited(next_entity, parent_entity)?,
+                        );
+                    }
+                }
+                EntityKind::MacroDefinition => {
+                    let macro_name = next_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(next_entity))?;
+                    if next_entity.is_function_like_macro() {
+                        self.macro_functions.push(
+                            MacroFunctionDeclare::new(macro_name)
+                                .visited(next_entity, parent_entity)?,
+                        );
+                    } else {
+                        self.macro_definitions.push(
+                            MacroDefinition::new(macro_name).visited(next_entity, parent_entity)?,
+                        );
+                    }
+                }
+                EntityKind::MacroExpansion => {
+                    let macro_name = next_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(next_entity))?;
+                    self.macro_expansions
+                        .push(MacroExpansion::new(macro_name).visited(next_entity, parent_entity)?);
+                }
+                EntityKind::Namespace => {
+                    self.namespace_declares.push(
+                        NamespaceDeclare::new(next_entity.get_name())
+                            .visited(next_entity, parent_entity)?,
+                    );
+                }
+                EntityKind::FunctionTemplate => {
+                    let function_name = next_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(next_entity))?;
+                    self.function_templates.push(
+                        FunctionTemplate::new(function_name).visited(next_entity, parent_entity)?,
+                    );
+                }
+                EntityKind::ClassTemplate => {
+                    self.class_templates.push(
+                        ClassTemplate::new(next_entity.get_name())
+                            .visited(next_entity, parent_entity)?,
+                    );
+                }
+                EntityKind::InclusionDirective => {
+                    let included_path = next_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(next_entity))?;
+                    self.include_directives.push(
+                        IncludeDirective::new(included_path).visited(next_entity, parent_entity)?,
+                    );
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ForwardDeclarationKind {
+    Enum,
+    Struct,
+    Union,
+    Class,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ForwardDeclaration {
+    pub name: String,
+    pub kind: ForwardDeclarationKind,
+    pub location: Option<SourceLocation>,
+}
+
+impl ForwardDeclaration {
+    fn from_type_declare(type_declare: TypeDeclare) -> Option<Self> {
+        let name = type_declare
+            .name()
+            .or_else(|| type_declare.typedef_name())?
+            .to_owned();
+        let (kind, location) = match type_declare {
+            TypeDeclare::Enum(declare) => (ForwardDeclarationKind::Enum, declare.location),
+            TypeDeclare::Struct(declare) => (ForwardDeclarationKind::Struct, declare.location),
+            TypeDeclare::Union(declare) => (ForwardDeclarationKind::Union, declare.location),
+            TypeDeclare::Class(declare) => (ForwardDeclarationKind::Class, declare.location),
+            TypeDeclare::Typedef(_) | TypeDeclare::FunctionPointer(_) => return None,
+        };
+        Some(Self {
+            name,
+            kind,
+            location,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum EntityRef<'a> {
+    Function(&'a FunctionDeclare),
+    Method(&'a MethodDeclare),
+    Enum(&'a EnumDeclare),
+    Struct(&'a StructDeclare),
+    Union(&'a UnionDeclare),
+    Class(&'a ClassDeclare),
+    Typedef(&'a TypedefDeclare),
+    FunctionPointer(&'a FunctionPointerTypeDeclare),
+    Field(&'a FieldDeclare),
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolIndex<'a> {
+    pub symbols: HashMap<String, EntityRef<'a>>,
+}
+
+impl<'a> SymbolIndex<'a> {
+    pub fn get(&self, usr: &str) -> Option<EntityRef<'a>> {
+        self.symbols.get(usr).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    fn insert(&mut self, usr: Option<&str>, entity_ref: EntityRef<'a>) {
+        if let Some(usr) = usr {
+            self.symbols.insert(usr.to_owned(), entity_ref);
+        }
+    }
+
+    fn insert_type_declare(&mut self, type_declare: &'a TypeDeclare) {
+        match type_declare {
+            TypeDeclare::Enum(enum_declare) => {
+                self.insert(enum_declare.usr(), EntityRef::Enum(enum_declare));
+            }
+            TypeDeclare::Struct(struct_declare) => {
+                self.insert_struct_declare(struct_declare);
+            }
+            TypeDeclare::Union(union_declare) => {
+                self.insert_union_declare(union_declare);
+            }
+            TypeDeclare::Class(class_declare) => {
+                self.insert(class_declare.usr(), EntityRef::Class(class_declare));
+                self.insert_members(&class_declare.fields);
+                for method_declare in class_declare.methods.iter() {
+                    self.insert(method_declare.usr(), EntityRef::Method(method_declare));
+                }
+            }
+            TypeDeclare::Typedef(typedef_declare) => {
+                self.insert(typedef_declare.usr(), EntityRef::Typedef(typedef_declare));
+            }
+            TypeDeclare::FunctionPointer(function_pointer_declare) => {
+                self.insert(
+                    function_pointer_declare.usr(),
+                    EntityRef::FunctionPointer(function_pointer_declare),
+                );
+            }
+        }
+    }
+
+    fn insert_struct_declare(&mut self, struct_declare: &'a StructDeclare) {
+        self.insert(struct_declare.usr(), EntityRef::Struct(struct_declare));
+        self.insert_members(&struct_declare.fields);
+    }
+
+    fn insert_union_declare(&mut self, union_declare: &'a UnionDeclare) {
+        self.insert(union_declare.usr(), EntityRef::Union(union_declare));
+        self.insert_members(&union_declare.fields);
+    }
+
+    fn insert_members(&mut self, members: &'a [MemberDeclare]) {
+        for member in members.iter() {
+            match member {
+                MemberDeclare::Field(field_declare) => {
+                    self.insert(field_declare.usr(), EntityRef::Field(field_declare));
+                }
+                MemberDeclare::Struct(struct_declare) => self.insert_struct_declare(struct_declare),
+                MemberDeclare::Union(union_declare) => self.insert_union_declare(union_declare),
+                MemberDeclare::Enum(enum_declare) => {
+                    self.insert(enum_declare.usr(), EntityRef::Enum(enum_declare));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnumConstantValue {
+    pub signed: i64,
+    pub unsigned: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnumConstantDeclare {
+    pub name: String,
+    pub location: Option<SourceLocation>,
+    pub source_range: Option<SourceRange>,
+    pub constant_value: Option<EnumConstantValue>,
+    /// The brief text of the constant's documentation comment, with Doxygen markup removed.
+    pub comment: Option<String>,
+}
+
+impl EnumConstantDeclare {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            location: None,
+            source_range: None,
+            constant_value: None,
+            comment: None,
+        }
+    }
+}
+
+impl EntityVisitor for EnumConstantDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_ref())
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::EnumConstantDecl
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        if let Some((signed_value, unsigned_value)) = current_entity.get_enum_constant_value() {
+            self.constant_value = Some(EnumConstantValue {
+                unsigned: unsigned_value,
+                signed: signed_value,
+            });
+        }
+        self.comment = current_entity.get_comment_brief();
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        self.source_range = current_entity
+            .get_range()
+            .map(|source_range| SourceRange::from_clang(&source_range));
+        Ok(())
+    }
+}
+
+/// A typedef'd type is reported under both names so that it matches either spelling.
+fn push_referenced_type_name<'a>(t: &'a Type, names: &mut Vec<&'a str>) {
+    if let Some(referenced_type) = t.referenced_type() {
+        names.push(referenced_type.tagless_name());
+        names.push(referenced_type.resolve_canonical().tagless_name());
+    }
+}
+
+/// The types the fields refer to, including those of records nested in them.
+fn collect_referenced_type_names<'a>(members: &'a [MemberDeclare], names: &mut Vec<&'a str>) {
+    for member in members.iter() {
+        match member {
+            MemberDeclare::Field(field_declare) => {
+                if let Some(field_type) = &field_declare.field_type {
+                    push_referenced_type_name(field_type, names);
+                }
+            }
+            MemberDeclare::Struct(struct_declare) => {
+                collect_referenced_type_names(&struct_declare.fields, names)
+            }
+            MemberDeclare::Union(union_declare) => {
+                collect_referenced_type_names(&union_declare.fields, names)
+            }
+            MemberDeclare::Enum(_) => {}
+        }
+    }
+}
+
+/// A declaration that can take part in a cycle of type references, with the names it is known
+/// by and the type names its fields or underlying type refer to.
+struct TypeNode<'a> {
+    names: Vec<&'a str>,
+    referenced_names: Vec<&'a str>,
+    is_record: bool,
+}
+
+impl<'a> TypeNode<'a> {
+    fn collect(type_declare: &'a TypeDeclare, nodes: &mut Vec<TypeNode<'a>>) {
+        nodes.push(TypeNode {
+            names: type_declare
+                .name()
+                .into_iter()
+                .chain(type_declare.typedef_name())
+                .collect(),
+            referenced_names: type_declare.referenced_type_names(),
+            is_record: matches!(
+                type_declare,
+                TypeDeclare::Struct(_) | TypeDeclare::Union(_) | TypeDeclare::Class(_)
+            ),
+        });
+        match type_declare {
+            TypeDeclare::Struct(declare) => Self::collect_members(&declare.fields, nodes),
+            TypeDeclare::Union(declare) => Self::collect_members(&declare.fields, nodes),
+            TypeDeclare::Class(declare) => Self::collect_members(&declare.fields, nodes),
+            _ => {}
+        }
+    }
+
+    /// Records nested in fields, each one wrapped up as a declaration of its own.
+    fn collect_members(members: &'a [MemberDeclare], nodes: &mut Vec<TypeNode<'a>>) {
+        for member in members.iter() {
+            let (names, fields) = match member {
+                MemberDeclare::Struct(declare) => (
+                    declare.struct_name.iter().chain(&declare.typedef_name),
+                    &declare.fields,
+                ),
+                MemberDeclare::Union(declare) => (
+                    declare.union_name.iter().chain(&declare.typedef_name),
+                    &declare.fields,
+                ),
+                MemberDeclare::Field(_) | MemberDeclare::Enum(_) => continue,
+            };
+            let names: Vec<&str> = names.map(|name| name.as_str()).collect();
+            if !names.is_empty() {
+                let mut referenced_names = Vec::new();
+                collect_referenced_type_names(fields, &mut referenced_names);
+                nodes.push(TypeNode {
+                    names,
+                    referenced_names,
+                    is_record: true,
+                });
+            }
+            Self::collect_members(fields, nodes);
+        }
+    }
+
+    fn collect_namespace(namespace_declare: &'a NamespaceDeclare, nodes: &mut Vec<TypeNode<'a>>) {
+        for type_declare in namespace_declare.type_declares.iter() {
+            Self::collect(type_declare, nodes);
+        }
+        for nested_namespace in namespace_declare.nested_namespaces.iter() {
+            Self::collect_namespace(nested_namespace, nodes);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnumDeclare {
+    pub enum_name: Option<String>,
+    pub typedef_name: Option<String>,
+    pub constants: Vec<EnumConstantDeclare>,
+    pub enum_type: Option<Type>,
+    pub is_scoped: bool,
+    pub location: Option<SourceLocation>,
+    pub is_definition: bool,
+    pub doc_comment: Option<String>,
+    pub usr: Option<String>,
+    pub source_range: Option<SourceRange>,
+}
+
+impl EnumDeclare {
+    pub fn new(enum_name: Option<String>, typedef_name: Option<String>) -> Self {
+        Self {
+            enum_name,
+            typedef_name,
+            constants: Vec::new(),
+            enum_type: None,
+            is_scoped: false,
+            location: None,
+            is_definition: false,
+            doc_comment: None,
+            usr: None,
+            source_range: None,
+        }
+    }
+
+    /// The name of the underlying integer type with typedefs resolved, e.g. `unsigned int` for
+    /// an enum declared as `enum e : uint32_t`.
+    pub fn integer_type_name(&self) -> Option<&str> {
+        self.enum_type
+            .as_ref()
+            .map(|enum_type| enum_type.resolve_canonical().type_name.as_str())
+    }
+
+    /// The Rust integer type for `#[repr(...)]` matching the underlying type, `None` if it is
+    /// not a plain integer type.
+    pub fn rust_repr(&self) -> Option<&'static str> {
+        self.enum_type.as_ref().and_then(|enum_type| {
+            codegen::integer_type_name(enum_type.resolve_canonical().type_kind)
+        })
+    }
+
+    /// Whether some constants are aliases of others, e.g. `E_OK = 0, E_SUCCESS = 0`.
+    pub fn has_duplicate_values(&self) -> bool {
+        let mut values = HashSet::new();
+        self.constants
+            .iter()
+            .filter_map(|constant| constant.constant_value.as_ref())
+            .any(|value| !values.insert(value.signed))
+    }
+
+    /// Guesses whether the enum is a set of bit flags, e.g. `READ = 1, WRITE = 2, EXEC = 4`.
+    /// Either its name says so, or it has at least three power of two values, every other value
+    /// is zero or a combination of those, and the values are not simply counting up.
+    pub fn is_flags(&self) -> bool {
+        let has_flags_name = self
+            .enum_name
+            .iter()
+            .chain(self.typedef_name.iter())
+            .map(|name| name.to_lowercase())
+            .any(|name| {
+                ["flag", "mask", "options"]
+                    .iter()
+                    .any(|word| name.contains(word))
+            });
+        if has_flags_name {
+            return true;
+        }
+        let mut values = self
+            .constants
+            .iter()
+            .filter_map(|constant| constant.constant_value.as_ref())
+            .map(|value| value.unsigned)
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        values.dedup();
+        let flag_values = values
+            .iter()
+            .filter(|value| value.is_power_of_two())
+            .collect::<Vec<_>>();
+        let all_flags = flag_values.iter().fold(0, |bits, &&value| bits | value);
+        let is_counting = values.windows(2).all(|pair| pair[1] == pair[0] + 1);
+        flag_values.len() >= 3 && !is_counting && values.iter().all(|value| value & !all_flags == 0)
+    }
+
+    /// The constants sharing a value with another one, grouped by value in declaration order.
+    pub fn duplicate_value_groups(&self) -> Vec<Vec<&EnumConstantDeclare>> {
+        let mut groups: Vec<Vec<&EnumConstantDeclare>> = Vec::new();
+        for constant in self.constants.iter() {
+            let value = match &constant.constant_value {
+                Some(value) => value.signed,
+                None => continue,
+            };
+            match groups.iter_mut().find(|group| {
+                group[0]
+                    .constant_value
+                    .as_ref()
+                    .map(|group_value| group_value.signed)
+                    == Some(value)
+            }) {
+                Some(group) => group.push(constant),
+                None => groups.push(vec![constant]),
+            }
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+}
+
+impl EntityVisitor for EnumDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.enum_name.as_ref().map(|s| s.as_ref())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.enum_name = Some(new_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::EnumDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.is_scoped = current_entity.is_scoped();
+        self.enum_type = current_entity
+            .get_enum_underlying_type()
+            .map(|enum_type| Type::from_clang(&enum_type));
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        self.is_definition = current_entity.is_definition();
+        self.doc_comment = current_entity.get_comment();
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.source_range = current_entity
+            .get_range()
+            .map(|source_range| SourceRange::from_clang(&source_range));
+        let children = current_entity.get_children();
+        for child_entity in children.iter().filter(|entity| !entity.is_attribute()) {
+            let name = child_entity
+                .get_name()
+                .ok_or_else(|| AstError::missing_name(child_entity))?;
+            self.constants
+                .push(EnumConstantDeclare::new(name).visited(child_entity, current_entity)?);
+        }
+        Ok(())
+    }
+}
+
+impl TypeDeclaration for EnumDeclare {
+    #[inline]
+    fn typedef_name(&self) -> Option<&str> {
+        self.typedef_name.as_ref().map(|n| n.as_ref())
+    }
+
+    #[inline]
+    fn set_typedef_name(&mut self, new_typedef_name: String) {
+        self.typedef_name = Some(new_typedef_name);
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        self.is_definition
+    }
+}
+
+/// A field of a struct, union or class. An anonymous struct or union member, whose own fields are
+/// accessed as if they belonged to the enclosing record, appears as the nested record followed by
+/// a field with `is_anonymous` set; an unnamed bit-field has no name but is not anonymous.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FieldDeclare {
+    pub name: Option<String>,
+    pub is_anonymous: bool,
+    pub field_type: Option<Type>,
+    pub is_bit_field: bool,
+    pub bit_width: Option<u32>,
+    /// A trailing array without a size, `int data[]`, whose elements live past the end of the
+    /// struct.
+    pub is_flexible_array_member: bool,
+    pub location: Option<SourceLocation>,
+    pub attributes: Vec<Attribute>,
+    pub byte_offset: Option<u64>,
+    /// The exact offset, which only differs from `byte_offset` for bit-fields.
+    pub bit_offset: Option<u64>,
+    pub size_bytes: Option<u64>,
+    pub doc_comment: Option<String>,
+    pub usr: Option<String>,
+    pub source_range: Option<SourceRange>,
+}
+
+impl FieldDeclare {
+    pub fn new(name: Option<String>) -> Self {
+        Self {
+            name,
+            is_anonymous: false,
+            field_type: None,
+            is_bit_field: false,
+            bit_width: None,
+            is_flexible_array_member: false,
+            location: None,
+            attributes: Vec::new(),
+            byte_offset: None,
+            bit_offset: None,
+            size_bytes: None,
+            doc_comment: None,
+            usr: None,
+            source_range: None,
+        }
+    }
+
+    /// Whether this is the implicit field of an anonymous struct or union member.
+    #[inline]
+    pub fn is_anonymous(&self) -> bool {
+        self.is_anonymous
+    }
+
+    #[inline]
+    pub fn is_flexible_array_member(&self) -> bool {
+        self.is_flexible_array_member
+    }
+}
+
+impl EntityVisitor for FieldDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|n| n.as_ref())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.name = Some(new_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::FieldDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.field_type = current_entity
+            .get_type()
+            .map(|field_type| Type::from_clang(&field_type));
+        self.is_bit_field = current_entity.is_bit_field();
+        self.bit_width = current_entity
+            .get_bit_field_width()
+            .map(|bit_width| bit_width as u32);
+        // Only the last member of a struct may have an incomplete array type.
+        self.is_flexible_array_member = self
+            .field_type
+            .as_ref()
+            .map(|field_type| field_type.type_kind == TypeKind::IncompleteArray)
+            .unwrap_or(false);
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        self.attributes = Attribute::collect(current_entity);
+        self.bit_offset = current_entity
+            .get_offset_of_field()
+            .ok()
+            .map(|bit_offset| bit_offset as u64);
+        self.byte_offset = self.bit_offset.map(|bit_offset| bit_offset / 8);
+        self.size_bytes = current_entity
+            .get_type()
+            .and_then(|field_type| field_type.get_sizeof().ok())
+            .map(|size| size as u64);
+        self.doc_comment = current_entity.get_comment();
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.source_range = current_entity
+            .get_range()
+            .map(|source_range| SourceRange::from_clang(&source_range));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StructDeclare {
+    pub struct_name: Option<String>,
+    pub typedef_name: Option<String>,
+    pub fields: Vec<MemberDeclare>,
+    pub location: Option<SourceLocation>,
+    pub attributes: Vec<Attribute>,
+    pub size_bytes: Option<u64>,
+    pub align_bytes: Option<u64>,
+    pub is_definition: bool,
+    pub is_recursive: bool,
+    pub is_opaque: bool,
+    /// `__attribute__((packed))`, no padding between the fields and an alignment of 1.
+    pub is_packed: bool,
+    pub has_flexible_array_member: bool,
+    pub doc_comment: Option<String>,
+    pub usr: Option<String>,
+    pub source_range: Option<SourceRange>,
+}
+
+impl StructDeclare {
+    pub fn new(struct_name: Option<String>, typedef_name: Option<String>) -> Self {
+        Self {
+            struct_name,
+            typedef_name,
+            fields: Vec::new(),
+            location: None,
+            attributes: Vec::new(),
+            size_bytes: None,
+            align_bytes: None,
+            is_definition: false,
+            is_recursive: false,
+            is_opaque: false,
+            is_packed: false,
+            has_flexible_array_member: false,
+            doc_comment: None,
+            usr: None,
+            source_range: None,
+        }
+    }
+
+    /// Whether one of the fields refers back to this struct, directly or through other types.
+    #[inline]
+    pub fn is_recursive(&self) -> bool {
+        self.is_recursive
+    }
+
+    /// Whether the struct has no body here and is only ever used through pointers, like
+    /// `struct handle;` in a C API hiding its implementation.
+    #[inline]
+    pub fn is_opaque(&self) -> bool {
+        self.is_opaque
+    }
+
+    /// Whether the struct ends in a C99 flexible array member like `int data[]`. Such a struct
+    /// has to be allocated with room for the elements, so it is never put on the stack.
+    #[inline]
+    pub fn has_flexible_array_member(&self) -> bool {
+        self.has_flexible_array_member
+    }
+
+    pub fn flexible_array_member_field(&self) -> Option<&FieldDeclare> {
+        self.fields.iter().find_map(|member| match member {
+            MemberDeclare::Field(field_declare) if field_declare.is_flexible_array_member => {
+                Some(field_declare)
+            }
+            _ => None,
+        })
+    }
+
+    /// The fields as C code sees them: the fields of anonymous struct and union members take
+    /// the place of the member, at any depth. Named nested records are not descended into.
+    /// Offsets of the flattened fields stay relative to the anonymous record holding them.
+    pub fn all_fields_recursive(&self) -> Vec<&FieldDeclare> {
+        let mut fields = Vec::new();
+        MemberDeclare::collect_fields_recursive(&self.fields, &mut fields);
+        fields
+    }
+}
+
+impl EntityVisitor for StructDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.struct_name.as_ref().map(|n| n.as_ref())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.struct_name = Some(new_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::StructDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        self.attributes = Attribute::collect(current_entity);
+        self.is_packed = self.attributes.contains(&Attribute::Packed);
+        if let Some(record_type) = current_entity.get_type() {
+            self.size_bytes = record_type.get_sizeof().ok().map(|size| size as u64);
+            self.align_bytes = record_type.get_alignof().ok().map(|align| align as u64);
+        }
+        self.is_definition = current_entity.is_definition();
+        self.doc_comment = current_entity.get_comment();
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.source_range = current_entity
+            .get_range()
+            .map(|source_range| SourceRange::from_clang(&source_range));
+        for child_entity in current_entity.get_children().iter() {
+            MemberDeclare::visit_member(&mut self.fields, child_entity, current_entity)?;
+        }
+        self.is_opaque =
+            self.fields.is_empty() && (!self.is_definition || self.size_bytes.is_none());
+        self.has_flexible_array_member = self.flexible_array_member_field().is_some();
+        Ok(())
+    }
+}
+
+impl TypeDeclaration for StructDeclare {
+    #[inline]
+    fn typedef_name(&self) -> Option<&str> {
+        self.typedef_name.as_ref().map(|n| n.as_ref())
+    }
+
+    #[inline]
+    fn set_typedef_name(&mut self, new_typedef_name: String) {
+        self.typedef_name = Some(new_typedef_name);
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        self.is_definition
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UnionDeclare {
+    pub union_name: Option<String>,
+    pub typedef_name: Option<String>,
+    pub fields: Vec<MemberDeclare>,
+    pub location: Option<SourceLocation>,
+    pub attributes: Vec<Attribute>,
+    pub size_bytes: Option<u64>,
+    pub align_bytes: Option<u64>,
+    pub is_definition: bool,
+    pub is_recursive: bool,
+    pub is_opaque: bool,
+    pub is_packed: bool,
+    pub usr: Option<String>,
+    pub source_range: Option<SourceRange>,
+}
+
+impl UnionDeclare {
+    pub fn new(union_name: Option<String>, typedef_name: Option<String>) -> Self {
+        Self {
+            union_name,
+            typedef_name,
+            fields: Vec::new(),
+            location: None,
+            attributes: Vec::new(),
+            size_bytes: None,
+            align_bytes: None,
+            is_definition: false,
+            is_recursive: false,
+            is_opaque: false,
+            is_packed: false,
+            usr: None,
+            source_range: None,
+        }
+    }
+
+    /// Whether one of the fields refers back to this union, directly or through other types.
+    #[inline]
+    pub fn is_recursive(&self) -> bool {
+        self.is_recursive
+    }
+
+    /// Whether the union has no body here, see `StructDeclare::is_opaque`.
+    #[inline]
+    pub fn is_opaque(&self) -> bool {
+        self.is_opaque
+    }
+}
+
+impl EntityVisitor for UnionDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.union_name.as_ref().map(|n| n.as_ref())
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_name: String) {
+        self.union_name = Some(new_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::UnionDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        self.attributes = Attribute::collect(current_entity);
+        self.is_packed = self.attributes.contains(&Attribute::Packed);
+        if let Some(record_type) = current_entity.get_type() {
+            self.size_bytes = record_type.get_sizeof().ok().map(|size| size as u64);
+            self.align_bytes = record_type.get_alignof().ok().map(|align| align as u64);
+        }
+        self.is_definition = current_entity.is_definition();
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.source_range = current_entity
+            .get_range()
+            .map(|source_range| SourceRange::from_clang(&source_range));
+        for child_entity in current_entity.get_children().iter() {
+            MemberDeclare::visit_member(&mut self.fields, child_entity, current_entity)?;
+        }
+        self.is_opaque =
+            self.fields.is_empty() && (!self.is_definition || self.size_bytes.is_none());
+        Ok(())
+    }
+}
+
+impl TypeDeclaration for UnionDeclare {
+    #[inline]
+    fn typedef_name(&self) -> Option<&str> {
+        self.typedef_name.as_ref().map(|n| n.as_ref())
+    }
+
+    #[inline]
+    fn set_typedef_name(&mut self, new_typedef_name: String) {
+        self.typedef_name = Some(new_typedef_name);
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        self.is_definition
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BaseClassInfo {
+    pub name: String,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::option_accessibility"))]
+    pub access_specifier: Option<Accessibility>,
+}
+
+impl BaseClassInfo {
+    pub fn from_clang(entity: &Entity) -> Result<Self, AstError> {
+        let name = entity
+            .get_type()
+            .map(|base_type| base_type.get_display_name())
+            .or_else(|| entity.get_name())
+            .ok_or_else(|| AstError::missing_name(entity))?;
+        Ok(Self {
+            name,
+            access_specifier: entity.get_accessibility(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClassDeclare {
+    pub class_name: Option<String>,
+    pub typedef_name: Option<String>,
+    pub base_classes: Vec<BaseClassInfo>,
+    pub constructors: Vec<ConstructorDeclare>,
+    pub destructor: Option<Box<DestructorDeclare>>,
+    pub conversion_functions: Vec<ConversionFunctionDeclare>,
+    pub methods: Vec<MethodDeclare>,
+    pub fields: Vec<MemberDeclare>,
+    pub location: Option<SourceLocation>,
+    pub is_definition: bool,
+    pub is_recursive: bool,
+    pub usr: Option<String>,
+    pub source_range: Option<SourceRange>,
+}
+
+impl ClassDeclare {
+    pub fn new(class_name: Option<String>, typedef_name: Option<String>) -> Self {
+        Self {
+            class_name,
+            typedef_name,
+            base_classes: Vec::new(),
+            constructors: Vec::new(),
+            destructor: None,
+            conversion_functions: Vec::new(),
+            methods: Vec::new(),
+            fields: Vec::new(),
+            location: None,
+            is_definition: false,
+            is_recursive: false,
+            usr: None,
+            source_range: None,
+        }
+    }
+
+    /// Whether one of the fields refers back to this class, directly or through other types.
+    #[inline]
+    pub fn is_recursive(&self) -> bool {
+        self.is_recursive
+    }
+
+    /// The types the class converts to without a cast, through its conversion functions that
+    /// are not `explicit`.
+    pub fn implicit_conversion_targets(&self) -> Vec<&Type> {
+        self.conversion_functions
+            .iter()
+            .filter(|conversion_function| !conversion_function.is_explicit)
+            .map(|conversion_function| &conversion_function.target_type)
+            .collect()
+    }
+
+    /// The types that convert to the class without a cast, through its converting constructors.
+    pub fn implicit_conversion_sources(&self) -> Vec<&Type> {
+        self.constructors
+            .iter()
+            .filter(|constructor| constructor.kind == ConstructorKind::Converting)
+            .filter_map(
+                |constructor| match constructor.function.parameters.first() {
+                    Some(FunctionParameter::Named(parameter_declare)) => {
+                        parameter_declare.parameter_type.as_ref()
+                    }
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+}
+
+impl EntityVisitor for ClassDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.class_name.as_ref().map(|n| n.as_ref())
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_name: String) {
+        self.class_name = Some(new_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::ClassDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.visit_body(current_entity)
+    }
+}
+
+impl ClassDeclare {
+    /// Collects everything but the entity kind, which differs between a class and a class
+    /// template. Members other than bases, constructors, the destructor, conversion functions and
+    /// methods are left to `MemberDeclare::visit_member`.
+    fn visit_body(&mut self, current_entity: &Entity) -> Result<(), AstError> {
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        self.is_definition = current_entity.is_definition();
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.source_range = current_entity
+            .get_range()
+            .map(|source_range| SourceRange::from_clang(&source_range));
+        let children = current_entity.get_children();
+        for child_entity in children.iter() {
+            match child_entity.get_kind() {
+                EntityKind::BaseSpecifier => {
+                    self.base_classes
+                        .push(BaseClassInfo::from_clang(child_entity)?);
+                }
+                EntityKind::Constructor => {
+                    let constructor_name = child_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(child_entity))?;
+                    self.constructors.push(
+                        ConstructorDeclare::new(constructor_name)
+                            .visited(child_entity, current_entity)?,
+                    );
+                }
+                EntityKind::Destructor => {
+                    let destructor_name = child_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(child_entity))?;
+                    self.destructor = Some(Box::new(
+                        DestructorDeclare::new(destructor_name)
+                            .visited(child_entity, current_entity)?,
+                    ));
+                }
+                EntityKind::ConversionFunction => {
+                    self.conversion_functions
+                        .push(ConversionFunctionDeclare::from_clang(child_entity)?);
+                }
+                EntityKind::Method => {
+                    let method_name = child_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(child_entity))?;
+                    self.methods.push(
+                        MethodDeclare::new(method_name).visited(child_entity, current_entity)?,
+                    );
+                }
+                _ => MemberDeclare::visit_member(&mut self.fields, child_entity, current_entity)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TypeDeclaration for ClassDeclare {
+    #[inline]
+    fn typedef_name(&self) -> Option<&str> {
+        self.typedef_name.as_ref().map(|n| n.as_ref())
+    }
+
+    #[inline]
+    fn set_typedef_name(&mut self, new_typedef_name: String) {
+        self.typedef_name = Some(new_typedef_name);
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        self.is_definition
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MethodDeclare {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub function: FunctionDeclare,
+    pub is_virtual: bool,
+    pub is_pure_virtual: bool,
+    pub is_static: bool,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::option_accessibility"))]
+    pub access_specifier: Option<Accessibility>,
+}
+
+impl MethodDeclare {
+    pub fn new(method_name: String) -> Self {
+        Self {
+            function: FunctionDeclare::new(method_name),
+            is_virtual: false,
+            is_pure_virtual: false,
+            is_static: false,
+            access_specifier: None,
+        }
+    }
+}
+
+impl EntityVisitor for MethodDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.function.name()
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_method_name: String) {
+        self.function.set_name(new_method_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::Method
+    }
+
+    #[inline]
+    fn linkage(&self) -> Option<Linkage> {
+        self.function.linkage()
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.function.usr()
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.function.visit_signature(current_entity)?;
+        self.is_virtual = current_entity.is_virtual_method();
+        self.is_pure_virtual = current_entity.is_pure_virtual_method();
+        self.is_static = current_entity.is_static_method();
+        self.access_specifier = current_entity.get_accessibility();
+        Ok(())
+    }
+}
+
+/// What a constructor constructs its object from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ConstructorKind {
+    /// Takes no arguments, `T()`.
+    Default,
+    /// `T(const T &)`.
+    Copy,
+    /// `T(T &&)`.
+    Move,
+    /// Any other constructor callable with one argument and not `explicit`, which makes the
+    /// argument type implicitly convertible to the class.
+    Converting,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConstructorDeclare {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub function: FunctionDeclare,
+    pub kind: ConstructorKind,
+    pub is_explicit: bool,
+    /// Declared `= default`.
+    pub is_defaulted: bool,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::option_accessibility"))]
+    pub access_specifier: Option<Accessibility>,
+}
+
+impl ConstructorDeclare {
+    pub fn new(constructor_name: String) -> Self {
+        Self {
+            function: FunctionDeclare::new(constructor_name),
+            kind: ConstructorKind::Other,
+            is_explicit: false,
+            is_defaulted: false,
+            access_specifier: None,
+        }
+    }
+}
+
+impl EntityVisitor for ConstructorDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.function.name()
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_constructor_name: String) {
+        self.function.set_name(new_constructor_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::Constructor
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.function.usr()
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.function.visit_signature(current_entity)?;
+        self.kind = if current_entity.is_copy_constructor() {
+            ConstructorKind::Copy
+        } else if current_entity.is_move_constructor() {
+            ConstructorKind::Move
+        } else if current_entity.is_default_constructor() {
+            ConstructorKind::Default
+        } else if current_entity.is_converting_constructor() {
+            ConstructorKind::Converting
+        } else {
+            ConstructorKind::Other
+        };
+        self.is_explicit = is_explicit(current_entity);
+        self.is_defaulted = current_entity.is_defaulted();
+        self.access_specifier = current_entity.get_accessibility();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DestructorDeclare {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub function: FunctionDeclare,
+    pub is_virtual: bool,
+    /// Declared `= default`.
+    pub is_defaulted: bool,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::option_accessibility"))]
+    pub access_specifier: Option<Accessibility>,
+}
+
+impl DestructorDeclare {
+    pub fn new(destructor_name: String) -> Self {
+        Self {
+            function: FunctionDeclare::new(destructor_name),
+            is_virtual: false,
+            is_defaulted: false,
+            access_specifier: None,
+        }
+    }
+}
+
+impl EntityVisitor for DestructorDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.function.name()
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_destructor_name: String) {
+        self.function.set_name(new_destructor_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::Destructor
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.function.usr()
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.function.visit_signature(current_entity)?;
+        self.is_virtual = current_entity.is_virtual_method();
+        self.is_defaulted = current_entity.is_defaulted();
+        self.access_specifier = current_entity.get_accessibility();
+        Ok(())
+    }
+}
+
+/// A conversion function such as `operator int()`, which makes the class convertible to its
+/// target type, implicitly unless it is `explicit`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConversionFunctionDeclare {
+    pub target_type: Type,
+    pub is_explicit: bool,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::option_accessibility"))]
+    pub access_specifier: Option<Accessibility>,
+    pub location: Option<SourceLocation>,
+}
+
+impl ConversionFunctionDeclare {
+    pub fn new(target_type: Type) -> Self {
+        Self {
+            target_type,
+            is_explicit: false,
+            access_specifier: None,
+            location: None,
+        }
+    }
+
+    fn from_clang(entity: &Entity) -> Result<Self, AstError> {
+        let target_type = entity
+            .get_result_type()
+            .ok_or_else(|| AstError::missing_type(entity))?;
+        Ok(Self {
+            target_type: Type::from_clang(&target_type),
+            is_explicit: is_explicit(entity),
+            access_specifier: entity.get_accessibility(),
+            location: entity
+                .get_location()
+                .map(|source_location| SourceLocation::from_clang(&source_location)),
+        })
+    }
+}
+
+/// Whether a constructor or conversion function is declared `explicit`, which libclang has no
+/// query for before version 17.
+fn is_explicit(entity: &Entity) -> bool {
+    entity
+        .get_range()
+        .map(|range| {
+            range
+                .tokenize()
+                .iter()
+                .map(|token| token.get_spelling())
+                .take_while(|spelling| spelling != "(")
+                .any(|spelling| spelling == "explicit")
+        })
+        .unwrap_or(false)
+}
+
+/// A parameter of a function or class template.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TemplateParameter {
+    /// `typename T` or `class T`.
+    Type { name: Option<String> },
+    /// A value such as `int N`.
+    NonType {
+        name: Option<String>,
+        parameter_type: Option<Type>,
+    },
+    /// `template <typename> class C`.
+    Template { name: Option<String> },
+}
+
+impl TemplateParameter {
+    /// The template parameters among the children of a template entity.
+    fn collect(template_entity: &Entity) -> Vec<TemplateParameter> {
+        template_entity
+            .get_children()
+            .iter()
+            .filter_map(|child_entity| match child_entity.get_kind() {
+                EntityKind::TemplateTypeParameter => Some(TemplateParameter::Type {
+                    name: child_entity.get_name(),
+                }),
+                EntityKind::NonTypeTemplateParameter => Some(TemplateParameter::NonType {
+                    name: child_entity.get_name(),
+                    parameter_type: child_entity
+                        .get_type()
+                        .map(|parameter_type| Type::from_clang(&parameter_type)),
+                }),
+                EntityKind::TemplateTemplateParameter => Some(TemplateParameter::Template {
+                    name: child_entity.get_name(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// A function template with its parameter list. Instantiations are not collected.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FunctionTemplate {
+    pub template_parameters: Vec<TemplateParameter>,
+    pub function: FunctionDeclare,
+}
+
+impl FunctionTemplate {
+    pub fn new(function_name: String) -> Self {
+        Self {
+            template_parameters: Vec::new(),
+            function: FunctionDeclare::new(function_name),
+        }
+    }
+}
+
+impl EntityVisitor for FunctionTemplate {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.function.name()
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_function_name: String) {
+        self.function.set_name(new_function_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::FunctionTemplate
+    }
+
+    #[inline]
+    fn linkage(&self) -> Option<Linkage> {
+        self.function.linkage()
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.function.usr()
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.template_parameters = TemplateParameter::collect(current_entity);
+        self.function.visit_signature(current_entity)
+    }
+}
+
+/// A class template with its parameter list. Instantiations are not collected.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClassTemplate {
+    pub template_parameters: Vec<TemplateParameter>,
+    pub class: ClassDeclare,
+}
+
+impl ClassTemplate {
+    pub fn new(class_name: Option<String>) -> Self {
+        Self {
+            template_parameters: Vec::new(),
+            class: ClassDeclare::new(class_name, None),
+        }
+    }
+}
+
+impl EntityVisitor for ClassTemplate {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.class.name()
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_name: String) {
+        self.class.set_name(new_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::ClassTemplate
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.class.usr()
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.template_parameters = TemplateParameter::collect(current_entity);
+        self.class.visit_body(current_entity)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TypeDeclare {
+    Enum(EnumDeclare),
+    Struct(StructDeclare),
+    Union(UnionDeclare),
+    Class(ClassDeclare),
+    Typedef(TypedefDeclare),
+    FunctionPointer(FunctionPointerTypeDeclare),
+}
+
+impl TypeDeclare {
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            TypeDeclare::Enum(declare) => declare.location.as_ref(),
+            TypeDeclare::Struct(declare) => declare.location.as_ref(),
+            TypeDeclare::Union(declare) => declare.location.as_ref(),
+            TypeDeclare::Class(declare) => declare.location.as_ref(),
+            TypeDeclare::Typedef(declare) => declare.location.as_ref(),
+            TypeDeclare::FunctionPointer(declare) => declare.location.as_ref(),
+        }
+    }
+
+    /// Like `referenced_type_names`, but each name says whether the type is held by value, that
+    /// is, not behind a pointer.
+    fn dependency_names(&self) -> Vec<(&str, bool)> {
+        fn push<'a>(t: &'a Type, by_value: bool, names: &mut Vec<(&'a str, bool)>) {
+            if let Some(pointee_type) = &t.pointee_type {
+                push(pointee_type, false, names);
+            } else if let Some(element_type) = &t.array_element_type {
+                push(element_type, by_value, names);
+            } else if let TypeKind::Typedef
+            | TypeKind::Elaborated
+            | TypeKind::Record
+            | TypeKind::Enum = t.type_kind
+            {
+                // Behind a pointer a tag can be forward declared, a typedef name cannot.
+                if by_value || t.type_kind == TypeKind::Typedef {
+                    names.push((t.tagless_name(), by_value));
+                }
+                if let Some(canonical_type) = &t.canonical_type {
+                    push(canonical_type, by_value, names);
+                }
+            }
+        }
+        fn collect<'a>(members: &'a [MemberDeclare], names: &mut Vec<(&'a str, bool)>) {
+            for member in members.iter() {
+                match member {
+                    MemberDeclare::Field(field_declare) => {
+                        if let Some(field_type) = &field_declare.field_type {
+                            push(field_type, true, names);
+                        }
+                    }
+                    MemberDeclare::Struct(struct_declare) => collect(&struct_declare.fields, names),
+                    MemberDeclare::Union(union_declare) => collect(&union_declare.fields, names),
+                    MemberDeclare::Enum(_) => {}
+                }
+            }
+        }
+        let mut names = Vec::new();
+        match self {
+            TypeDeclare::Enum(_) => {}
+            TypeDeclare::Struct(declare) => collect(&declare.fields, &mut names),
+            TypeDeclare::Union(declare) => collect(&declare.fields, &mut names),
+            TypeDeclare::Class(declare) => collect(&declare.fields, &mut names),
+            TypeDeclare::Typedef(declare) => push(&declare.underlying_type, true, &mut names),
+            TypeDeclare::FunctionPointer(declare) => {
+                for t in declare.signature_types() {
+                    push(t, false, &mut names);
+                }
+            }
+        }
+        names
+    }
+
+    /// Names of the user-defined types this declare refers to through its fields or, for a
+    /// typedef, its underlying type.
+    fn referenced_type_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        match self {
+            TypeDeclare::Enum(_) => {}
+            TypeDeclare::Struct(declare) => {
+                collect_referenced_type_names(&declare.fields, &mut names)
+            }
+            TypeDeclare::Union(declare) => {
+                collect_referenced_type_names(&declare.fields, &mut names)
+            }
+            TypeDeclare::Class(declare) => {
+                collect_referenced_type_names(&declare.fields, &mut names)
+            }
+            TypeDeclare::Typedef(declare) => {
+                push_referenced_type_name(&declare.underlying_type, &mut names)
+            }
+            TypeDeclare::FunctionPointer(declare) => {
+                for t in declare.signature_types() {
+                    push_referenced_type_name(t, &mut names);
+                }
+            }
+        }
+        names
+    }
+
+    /// Whether two definitions of the same type agree on their constants or members.
+    pub fn has_same_body(&self, other: &TypeDeclare) -> bool {
+        match (self, other) {
+            (TypeDeclare::Enum(declare), TypeDeclare::Enum(other_declare)) => {
+                declare.constants.len() == other_declare.constants.len()
+                    && declare
+                        .constants
+                        .iter()
+                        .zip(other_declare.constants.iter())
+                        .all(|(constant, other_constant)| {
+                            constant.name == other_constant.name
+                                && constant.constant_value.as_ref().map(|value| value.signed)
+                                    == other_constant
+                                        .constant_value
+                                        .as_ref()
+                                        .map(|value| value.signed)
+                        })
+            }
+            (TypeDeclare::Struct(declare), TypeDeclare::Struct(other_declare)) => {
+                MemberDeclare::have_same_members(&declare.fields, &other_declare.fields)
+            }
+            (TypeDeclare::Union(declare), TypeDeclare::Union(other_declare)) => {
+                MemberDeclare::have_same_members(&declare.fields, &other_declare.fields)
+            }
+            (TypeDeclare::Class(declare), TypeDeclare::Class(other_declare)) => {
+                MemberDeclare::have_same_members(&declare.fields, &other_declare.fields)
+            }
+            (TypeDeclare::Typedef(declare), TypeDeclare::Typedef(other_declare)) => {
+                declare.underlying_type.type_name == other_declare.underlying_type.type_name
+            }
+            (
+                TypeDeclare::FunctionPointer(declare),
+                TypeDeclare::FunctionPointer(other_declare),
+            ) => {
+                declare.is_variadic == other_declare.is_variadic
+                    && declare
+                        .signature_types()
+                        .map(|t| &t.type_name)
+                        .eq(other_declare.signature_types().map(|t| &t.type_name))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EntityVisitor for TypeDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        match self {
+            TypeDeclare::Enum(declare) => declare.name(),
+            TypeDeclare::Struct(declare) => declare.name(),
+            TypeDeclare::Union(declare) => declare.name(),
+            TypeDeclare::Class(declare) => declare.name(),
+            TypeDeclare::Typedef(declare) => declare.name(),
+            TypeDeclare::FunctionPointer(declare) => declare.name(),
+        }
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_name: String) {
+        match self {
+            TypeDeclare::Enum(declare) => declare.set_name(new_name),
+            TypeDeclare::Struct(declare) => declare.set_name(new_name),
+            TypeDeclare::Union(declare) => declare.set_name(new_name),
+            TypeDeclare::Class(declare) => declare.set_name(new_name),
+            TypeDeclare::Typedef(declare) => declare.set_name(new_name),
+            TypeDeclare::FunctionPointer(declare) => declare.set_name(new_name),
+        }
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        match self {
+            TypeDeclare::Enum(declare) => declare.entity_kind(),
+            TypeDeclare::Struct(declare) => declare.entity_kind(),
+            TypeDeclare::Union(declare) => declare.entity_kind(),
+            TypeDeclare::Class(declare) => declare.entity_kind(),
+            TypeDeclare::Typedef(declare) => declare.entity_kind(),
+            TypeDeclare::FunctionPointer(declare) => declare.entity_kind(),
+        }
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        match self {
+            TypeDeclare::Enum(declare) => declare.usr(),
+            TypeDeclare::Struct(declare) => declare.usr(),
+            TypeDeclare::Union(declare) => declare.usr(),
+            TypeDeclare::Class(declare) => declare.usr(),
+            TypeDeclare::Typedef(declare) => declare.usr(),
+            TypeDeclare::FunctionPointer(declare) => declare.usr(),
+        }
+    }
+
+    fn visit_entity(
+        &mut self,
+        current_entity: &Entity,
+        parent_entity: &Entity,
+    ) -> Result<(), AstError> {
+        match self {
+            TypeDeclare::Enum(declare) => declare.visit_entity(current_entity, parent_entity),
+            TypeDeclare::Struct(declare) => declare.visit_entity(current_entity, parent_entity),
+            TypeDeclare::Union(declare) => declare.visit_entity(current_entity, parent_entity),
+            TypeDeclare::Class(declare) => declare.visit_entity(current_entity, parent_entity),
+            TypeDeclare::Typedef(declare) => declare.visit_entity(current_entity, parent_entity),
+            TypeDeclare::FunctionPointer(declare) => {
+                declare.visit_entity(current_entity, parent_entity)
+            }
+        }
+    }
+}
+
+impl TypeDeclaration for TypeDeclare {
+    #[inline]
+    fn typedef_name(&self) -> Option<&str> {
+        match self {
+            TypeDeclare::Enum(declare) => declare.typedef_name(),
+            TypeDeclare::Struct(declare) => declare.typedef_name(),
+            TypeDeclare::Union(declare) => declare.typedef_name(),
+            TypeDeclare::Class(declare) => declare.typedef_name(),
+            TypeDeclare::Typedef(declare) => declare.typedef_name(),
+            TypeDeclare::FunctionPointer(declare) => declare.typedef_name(),
+        }
+    }
+
+    #[inline]
+    fn set_typedef_name(&mut self, new_typedef_name: String) {
+        match self {
+            TypeDeclare::Enum(declare) => declare.set_typedef_name(new_typedef_name),
+            TypeDeclare::Struct(declare) => declare.set_typedef_name(new_typedef_name),
+            TypeDeclare::Union(declare) => declare.set_typedef_name(new_typedef_name),
+            TypeDeclare::Class(declare) => declare.set_typedef_name(new_typedef_name),
+            TypeDeclare::Typedef(declare) => declare.set_typedef_name(new_typedef_name),
+            TypeDeclare::FunctionPointer(declare) => declare.set_typedef_name(new_typedef_name),
+        }
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        match self {
+            TypeDeclare::Enum(declare) => declare.is_definition(),
+            TypeDeclare::Struct(declare) => declare.is_definition(),
+            TypeDeclare::Union(declare) => declare.is_definition(),
+            TypeDeclare::Class(declare) => declare.is_definition(),
+            TypeDeclare::Typedef(declare) => declare.is_definition(),
+            TypeDeclare::FunctionPointer(declare) => declare.is_definition(),
+        }
+    }
+}
+
+/// A typedef of a builtin, pointer or another typedef, such as `typedef void *Handle`. Typedefs
+/// naming a record or enum are folded into that declare's `typedef_name` instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypedefDeclare {
+    pub name: String,
+    pub underlying_type: Type,
+    pub location: Option<SourceLocation>,
+    pub usr: Option<String>,
+}
+
+impl TypedefDeclare {
+    pub fn new(name: String, underlying_type: Type) -> Self {
+        Self {
+            name,
+            underlying_type,
+            location: None,
+            usr: None,
+        }
+    }
+}
+
+impl EntityVisitor for TypedefDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::TypedefDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
+
+impl TypeDeclaration for TypedefDeclare {
+    #[inline]
+    fn typedef_name(&self) -> Option<&str> {
+        None
+    }
+
+    #[inline]
+    fn set_typedef_name(&mut self, new_typedef_name: String) {
+        self.name = new_typedef_name;
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        true
+    }
+}
+
+/// A typedef of a function pointer, e.g. `typedef void (*callback_t)(int, void *);`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FunctionPointerTypeDeclare {
+    pub name: String,
+    pub return_type: Type,
+    pub parameter_types: Vec<Type>,
+    pub is_variadic: bool,
+    pub location: Option<SourceLocation>,
+    pub usr: Option<String>,
+}
+
+impl FunctionPointerTypeDeclare {
+    pub fn new(name: String, return_type: Type) -> Self {
+        Self {
+            name,
+            return_type,
+            parameter_types: Vec::new(),
+            is_variadic: false,
+            location: None,
+            usr: None,
+        }
+    }
+
+    fn from_clang(name: String, function_type: &clang::Type) -> Self {
+        let return_type = function_type
+            .get_result_type()
+            .map(|return_type| Type::from_clang(&return_type))
+            .unwrap_or_else(|| Type::new(TypeKind::Void, "void".to_owned()));
+        let mut declare = Self::new(name, return_type);
+        declare.parameter_types = function_type
+            .get_argument_types()
+            .unwrap_or_default()
+            .iter()
+            .map(Type::from_clang)
+            .collect();
+        declare.is_variadic = function_type.is_variadic();
+        declare
+    }
+
+    /// The return type followed by the parameter types.
+    pub fn signature_types(&self) -> impl Iterator<Item = &Type> {
+        std::iter::once(&self.return_type).chain(self.parameter_types.iter())
+    }
+
+    /// The C declarator of the typedef without the `typedef` keyword, e.g.
+    /// `void (*callback_t)(int, void *)`.
+    pub fn declaration_string(&self) -> String {
+        let mut parameters = self
+            .parameter_types
+            .iter()
+            .map(|parameter_type| TypePrinter::new(parameter_type).print())
+            .collect::<Vec<_>>();
+        if self.is_variadic {
+            parameters.push("...".to_owned());
+        }
+        let parameters = if parameters.is_empty() {
+            "void".to_owned()
+        } else {
+            parameters.join(", ")
+        };
+        TypePrinter::new(&self.return_type)
+            .print_declaration(&format!("(*{})({})", self.name, parameters))
+    }
+}
+
+impl EntityVisitor for FunctionPointerTypeDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::TypedefDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
+
+impl TypeDeclaration for FunctionPointerTypeDeclare {
+    #[inline]
+    fn typedef_name(&self) -> Option<&str> {
+        None
+    }
+
+    #[inline]
+    fn set_typedef_name(&mut self, new_typedef_name: String) {
+        self.name = new_typedef_name;
+    }
+
+    #[inline]
+    fn is_definition(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MemberDeclare {
+    Field(FieldDeclare),
+    Struct(StructDeclare),
+    Union(UnionDeclare),
+    Enum(EnumDeclare),
+}
+
+impl MemberDeclare {
+    /// Clang does not report the implicit field behind an anonymous struct or union member, so
+    /// one is made up from the record itself.
+    fn anonymous_field(record_entity: &Entity) -> Option<MemberDeclare> {
+        if !record_entity.is_anonymous() {
+            return None;
+        }
+        let mut field_declare = FieldDeclare::new(None);
+        field_declare.is_anonymous = true;
+        field_declare.field_type = record_entity
+            .get_type()
+            .map(|field_type| Type::from_clang(&field_type));
+        field_declare.location = record_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Some(MemberDeclare::Field(field_declare))
+    }
+
+    /// Records a field, nested struct, union or enum of the record `parent_entity` in `members`.
+    /// Members that are not modelled, such as methods, static data members, nested classes,
+    /// type aliases, friends, using declarations and static assertions, are skipped, so that a
+    /// C++ struct with methods or a C11 struct with a `_Static_assert` still parses.
+    fn visit_member(
+        members: &mut Vec<MemberDeclare>,
+        child_entity: &Entity,
+        parent_entity: &Entity,
+    ) -> Result<(), AstError> {
+        match child_entity.get_kind() {
+            EntityKind::FieldDecl => {
+                members.push(
+                    MemberDeclare::Field(FieldDeclare::new(child_entity.get_name()))
+                        .visited(child_entity, parent_entity)?,
+                );
+            }
+            EntityKind::StructDecl => {
+                members.push(
+                    MemberDeclare::Struct(StructDeclare::new(
+                        Self::record_name(child_entity),
+                        None,
+                    ))
+                    .visited(child_entity, parent_entity)?,
+                );
+                members.extend(Self::anonymous_field(child_entity));
+            }
+            EntityKind::UnionDecl => {
+                members.push(
+                    MemberDeclare::Union(UnionDeclare::new(Self::record_name(child_entity), None))
+                        .visited(child_entity, parent_entity)?,
+                );
+                members.extend(Self::anonymous_field(child_entity));
+            }
+            EntityKind::EnumDecl => {
+                members.push(
+                    MemberDeclare::Enum(EnumDeclare::new(child_entity.get_name(), None))
+                        .visited(child_entity, parent_entity)?,
+                );
+            }
+            EntityKind::BaseSpecifier
+            | EntityKind::Method
+            | EntityKind::Constructor
+            | EntityKind::Destructor
+            | EntityKind::ConversionFunction
+            | EntityKind::AccessSpecifier
+            | EntityKind::TemplateTypeParameter
+            | EntityKind::NonTypeTemplateParameter
+            | EntityKind::TemplateTemplateParameter
+            | EntityKind::ClassDecl
+            | EntityKind::ClassTemplate
+            | EntityKind::ClassTemplatePartialSpecialization
+            | EntityKind::VarDecl
+            | EntityKind::FunctionTemplate
+            | EntityKind::TypedefDecl
+            | EntityKind::TypeAliasDecl
+            | EntityKind::TypeAliasTemplateDecl
+            | EntityKind::FriendDecl
+            | EntityKind::UsingDeclaration
+            | EntityKind::StaticAssert => {}
+            _ if child_entity.is_attribute() => {}
+            _ => return Err(AstError::unexpected_entity_kind(child_entity)),
+        }
+        Ok(())
+    }
+
+    fn collect_fields_recursive<'a>(
+        members: &'a [MemberDeclare],
+        fields: &mut Vec<&'a FieldDeclare>,
+    ) {
+        for (index, member) in members.iter().enumerate() {
+            match member {
+                // The implicit field comes right after the anonymous record it stands for.
+                MemberDeclare::Field(field_declare) if field_declare.is_anonymous => {
+                    match index.checked_sub(1).map(|index| &members[index]) {
+                        Some(MemberDeclare::Struct(struct_declare)) => {
+                            Self::collect_fields_recursive(&struct_declare.fields, fields)
+                        }
+                        Some(MemberDeclare::Union(union_declare)) => {
+                            Self::collect_fields_recursive(&union_declare.fields, fields)
+                        }
+                        _ => fields.push(field_declare),
+                    }
+                }
+                MemberDeclare::Field(field_declare) => fields.push(field_declare),
+                MemberDeclare::Struct(_) | MemberDeclare::Union(_) | MemberDeclare::Enum(_) => {}
+            }
+        }
+    }
+
+    fn record_name(entity: &Entity) -> Option<String> {
+        if entity.is_anonymous() {
+            None
+        } else {
+            entity.get_name()
+        }
+    }
+
+    fn have_same_members(members: &[MemberDeclare], other_members: &[MemberDeclare]) -> bool {
+        members.len() == other_members.len()
+            && members
+                .iter()
+                .zip(other_members.iter())
+                .all(|(member, other_member)| match (member, other_member) {
+                    (MemberDeclare::Field(field), MemberDeclare::Field(other_field)) => {
+                        field.name == other_field.name
+                            && field.field_type.as_ref().map(|t| &t.type_name)
+                                == other_field.field_type.as_ref().map(|t| &t.type_name)
+                    }
+                    (MemberDeclare::Struct(declare), MemberDeclare::Struct(other_declare)) => {
+                        declare.struct_name == other_declare.struct_name
+                            && Self::have_same_members(&declare.fields, &other_declare.fields)
+                    }
+                    (MemberDeclare::Union(declare), MemberDeclare::Union(other_declare)) => {
+                        declare.union_name == other_declare.union_name
+                            && Self::have_same_members(&declare.fields, &other_declare.fields)
+                    }
+                    (MemberDeclare::Enum(declare), MemberDeclare::Enum(other_declare)) => {
+                        declare.enum_name == other_declare.enum_name
+                            && declare.constants.len() == other_declare.constants.len()
+                            && declare
+                                .constants
+                                .iter()
+                                .zip(other_declare.constants.iter())
+                                .all(|(constant, other_constant)| {
+                                    constant.name == other_constant.name
+                                })
+                    }
+                    _ => false,
+                })
+    }
+}
+
+impl EntityVisitor for MemberDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        match self {
+            MemberDeclare::Field(declare) => declare.name(),
+            MemberDeclare::Struct(declare) => declare.name(),
+            MemberDeclare::Union(declare) => declare.name(),
+            MemberDeclare::Enum(declare) => declare.name(),
+        }
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_name: String) {
+        match self {
+            MemberDeclare::Field(declare) => declare.set_name(new_name),
+            MemberDeclare::Struct(declare) => declare.set_name(new_name),
+            MemberDeclare::Union(declare) => declare.set_name(new_name),
+            MemberDeclare::Enum(declare) => declare.set_name(new_name),
+        }
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        match self {
+            MemberDeclare::Field(declare) => declare.entity_kind(),
+            MemberDeclare::Struct(declare) => declare.entity_kind(),
+            MemberDeclare::Union(declare) => declare.entity_kind(),
+            MemberDeclare::Enum(declare) => declare.entity_kind(),
+        }
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        match self {
+            MemberDeclare::Field(declare) => declare.usr(),
+            MemberDeclare::Struct(declare) => declare.usr(),
+            MemberDeclare::Union(declare) => declare.usr(),
+            MemberDeclare::Enum(declare) => declare.usr(),
+        }
+    }
+
+    fn visit_entity(
+        &mut self,
+        current_entity: &Entity,
+        parent_entity: &Entity,
+    ) -> Result<(), AstError> {
+        match self {
+            MemberDeclare::Field(declare) => declare.visit_entity(current_entity, parent_entity),
+            MemberDeclare::Struct(declare) => declare.visit_entity(current_entity, parent_entity),
+            MemberDeclare::Union(declare) => declare.visit_entity(current_entity, parent_entity),
+            MemberDeclare::Enum(declare) => declare.visit_entity(current_entity, parent_entity),
+        }
+    }
+}
+
+/// A C++ exception specification.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ExceptionSpec {
+    /// `noexcept` or `throw()`.
+    NoExcept,
+    /// `throw(A, B)` with the spelling of each listed type.
+    ThrowList(Vec<String>),
+    /// `throw(...)`, which allows any exception.
+    DynamicExcept,
+    /// `noexcept(expr)`, which only tells at compile time whether the function may throw.
+    ComputedNoexcept,
+}
+
+impl ExceptionSpec {
+    fn from_clang(entity: &Entity) -> Option<Self> {
+        match entity.get_exception_specification()? {
+            ExceptionSpecification::BasicNoexcept | ExceptionSpecification::DynamicNone => {
+                Some(ExceptionSpec::NoExcept)
+            }
+            ExceptionSpecification::Dynamic => {
+                Some(ExceptionSpec::ThrowList(Self::thrown_types(entity)))
+            }
+            ExceptionSpecification::DynamicAny => Some(ExceptionSpec::DynamicExcept),
+            ExceptionSpecification::ComputedNoexcept => Some(ExceptionSpec::ComputedNoexcept),
+            // Specifications of implicit members and templates that clang has not worked out.
+            ExceptionSpecification::Unevaluated
+            | ExceptionSpecification::Uninstantiated
+            | ExceptionSpecification::Unparsed => None,
+        }
+    }
+
+    /// libclang does not list the types of a `throw(A, B)` specification, so they are read off
+    /// the tokens of the declaration, ahead of the body if there is one.
+    fn thrown_types(entity: &Entity) -> Vec<String> {
+        let tokens = entity
+            .get_range()
+            .map(|range| {
+                range
+                    .tokenize()
+                    .iter()
+                    .map(|token| token.get_spelling())
+                    .take_while(|spelling| spelling != "{")
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let start = match tokens
+            .windows(2)
+            .position(|window| window[0] == "throw" && window[1] == "(")
+        {
+            Some(position) => position + 2,
+            None => return Vec::new(),
+        };
+        let mut thrown_types = Vec::new();
+        let mut current_type = String::new();
+        let mut depth = 0;
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        for token in tokens[start..].iter() {
+            match token.as_str() {
+                "(" | "<" => depth += 1,
+                ")" if depth == 0 => break,
+                ")" | ">" => depth -= 1,
+                "," if depth == 0 => {
+                    thrown_types.push(std::mem::take(&mut current_type));
+                    continue;
+                }
+                _ => {}
+            }
+            if current_type.ends_with(is_word_char) && token.starts_with(is_word_char) {
+                current_type.push(' ');
+            }
+            current_type.push_str(token);
+        }
+        if !current_type.is_empty() {
+            thrown_types.push(current_type);
+        }
+        thrown_types
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FunctionDeclare {
+    pub function_name: String,
+    pub return_type: Option<Type>,
+    pub parameters: Vec<FunctionParameter>,
+    pub is_variadic: bool,
+    pub has_void_parameters: bool,
+    pub is_definition: bool,
+    /// Inline functions usually have no external symbol to link against.
+    pub is_inline: bool,
+    pub is_static_inline: bool,
+    /// Set for `_Noreturn`, `[[noreturn]]` and `__attribute__((noreturn))` functions.
+    pub is_noreturn: bool,
+    /// Whether the caller has to free the returned pointer as far as the declaration itself
+    /// says, through `warn_unused_result`. `Some(false)` when the function does not return a
+    /// pointer to data, `None` when there is no telling. Names are left to
+    /// `MemoryModel::classify`, which knows the conventions of the library, see also
+    /// `return_type_is_caller_owns`.
+    pub caller_owns_return: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::option_linkage"))]
+    pub linkage: Option<Linkage>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "serde_remote::option_calling_convention")
+    )]
+    pub calling_convention: Option<CallingConvention>,
+    /// Only C++ functions have one, `None` means the function may throw anything.
+    pub exception_specification: Option<ExceptionSpec>,
+    pub declspec: Option<DeclSpec>,
+    pub location: Option<SourceLocation>,
+    pub attributes: Vec<Attribute>,
+    pub doc_comment: Option<String>,
+    #[cfg(feature = "parse_doxygen")]
+    pub doxygen_comment: Option<DoxygenComment>,
+    pub usr: Option<String>,
+    pub source_range: Option<SourceRange>,
+}
+
+impl FunctionDeclare {
+    pub fn new(function_name: String) -> Self {
+        Self {
+            function_name,
+            return_type: None,
+            parameters: Vec::new(),
+            is_variadic: false,
+            has_void_parameters: false,
+            is_definition: false,
+            is_inline: false,
+            is_static_inline: false,
+            is_noreturn: false,
+            caller_owns_return: None,
+            linkage: None,
+            calling_convention: None,
+            exception_specification: None,
+            declspec: None,
+            location: None,
+            attributes: Vec::new(),
+            doc_comment: None,
+            #[cfg(feature = "parse_doxygen")]
+            doxygen_comment: None,
+            usr: None,
+            source_range: None,
+        }
+    }
+}
+
+impl EntityVisitor for FunctionDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.function_name.as_str())
+    }
+
+    #[inline]
+    fn set_name(&mut self, new_function_name: String) {
+        self.function_name = new_function_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::FunctionDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    #[inline]
+    fn linkage(&self) -> Option<Linkage> {
+        self.linkage
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.visit_signature(current_entity)
+    }
+}
+
+impl FunctionDeclare {
+    /// Whether the function never returns to its caller.
+    #[inline]
+    pub fn is_noreturn(&self) -> bool {
+        self.is_noreturn
+    }
+
+    /// The named parameter called `name`, for matching `@param` tags or annotations that refer
+    /// to parameters by name.
+    pub fn argument_by_name<'a>(&'a self, name: &str) -> Option<&'a ParameterDeclare> {
+        self.parameters
+            .iter()
+            .find_map(|parameter| match parameter {
+                FunctionParameter::Named(parameter_declare)
+                    if parameter_declare.name.as_deref() == Some(name) =>
+                {
+                    Some(parameter_declare)
+                }
+                _ => None,
+            })
+    }
+
+    /// The name generated bindings give one of the parameters of the function, see
+    /// `ParameterDeclare::binding_name`.
+    pub fn parameter_binding_name(&self, parameter_declare: &ParameterDeclare) -> String {
+        let index = self
+            .parameters
+            .iter()
+            .position(|parameter| match parameter {
+                FunctionParameter::Named(named_parameter) => {
+                    std::ptr::eq(named_parameter, parameter_declare)
+                }
+                FunctionParameter::Variadic(_) => false,
+            })
+            .unwrap_or_default();
+        parameter_declare.binding_name(index)
+    }
+
+    /// The number of named parameters, the `...` of a variadic function is not counted.
+    pub fn argument_count(&self) -> usize {
+        self.parameters
+            .iter()
+            .filter(|parameter| matches!(parameter, FunctionParameter::Named(_)))
+            .count()
+    }
+
+    /// Whether the function is declared as `f(void)`. In C an empty `f()` instead declares a
+    /// function whose parameters are unknown, both have no arguments. C++ treats `f()` like
+    /// `f(void)`, so it is reported the same way there.
+    #[inline]
+    pub fn has_void_parameters(&self) -> bool {
+        self.has_void_parameters
+    }
+
+    /// A C-like `return_type name(parameter_type parameter_name, ...)` string rebuilt from the
+    /// parsed types, so it does not change with libclang's display names.
+    pub fn signature_string(&self) -> String {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|parameter| match parameter {
+                FunctionParameter::Named(parameter_declare) => {
+                    match (&parameter_declare.parameter_type, &parameter_declare.name) {
+                        (Some(parameter_type), Some(name)) => {
+                            TypePrinter::new(parameter_type).print_declaration(name)
+                        }
+                        (Some(parameter_type), None) => TypePrinter::new(parameter_type).print(),
+                        (None, name) => name.to_owned().unwrap_or_default(),
+                    }
+                }
+                FunctionParameter::Variadic(_) => "...".to_owned(),
+            })
+            .collect::<Vec<_>>();
+        let parameters = if parameters.is_empty() {
+            "void".to_owned()
+        } else {
+            parameters.join(", ")
+        };
+        let declarator = format!("{}({})", self.function_name, parameters);
+        match &self.return_type {
+            Some(return_type) => TypePrinter::new(return_type).print_declaration(&declarator),
+            None => declarator,
+        }
+    }
+
+    fn visit_signature(&mut self, current_entity: &Entity) -> Result<(), AstError> {
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        self.attributes = Attribute::collect(current_entity);
+        self.doc_comment = current_entity.get_comment();
+        #[cfg(feature = "parse_doxygen")]
+        {
+            self.doxygen_comment = self.doc_comment.as_deref().map(DoxygenComment::parse);
+        }
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.source_range = current_entity
+            .get_range()
+            .map(|source_range| SourceRange::from_clang(&source_range));
+        self.return_type = current_entity
+            .get_result_type()
+            .map(|return_type| Type::from_clang(&return_type));
+        // libclang only lists the arguments of plain functions, a function template has them as
+        // children.
+        let arguments = current_entity.get_arguments().unwrap_or_else(|| {
+            current_entity
+                .get_children()
+                .into_iter()
+                .filter(|child_entity| child_entity.get_kind() == EntityKind::ParmDecl)
+                .collect()
+        });
+        for argument in arguments.iter() {
+            self.parameters.push(FunctionParameter::Named(
+                ParameterDeclare::new(argument.get_name()).visited(argument, current_entity)?,
+            ));
+        }
+        self.is_variadic = current_entity.is_variadic();
+        // Without arguments the two only differ in the function type, `f()` has no prototype.
+        self.has_void_parameters = arguments.is_empty()
+            && !self.is_variadic
+            && current_entity
+                .get_type()
+                .map(|function_type| function_type.get_kind() == TypeKind::FunctionPrototype)
+                .unwrap_or(false);
+        self.linkage = current_entity.get_linkage();
+        self.is_definition = current_entity.is_definition();
+        self.is_inline = current_entity.is_inline_function();
+        self.is_static_inline =
+            self.is_inline && current_entity.get_storage_class() == Some(StorageClass::Static);
+        self.calling_convention = current_entity
+            .get_type()
+            .and_then(|function_type| function_type.get_calling_convention());
+        self.exception_specification = ExceptionSpec::from_clang(current_entity);
+        self.declspec = DeclSpec::from_clang(current_entity);
+        // The C11 `_Noreturn` specifier is not exposed as an attribute, so it is looked for among
+        // the tokens in front of the function name.
+        self.is_noreturn = self.attributes.contains(&Attribute::NoReturn)
+            || current_entity
+                .get_range()
+                .map(|range| {
+                    range
+                        .tokenize()
+                        .iter()
+                        .map(|token| token.get_spelling())
+                        .take_while(|spelling| *spelling != self.function_name)
+                        .any(|spelling| spelling == "_Noreturn" || spelling == "noreturn")
+                })
+                .unwrap_or(false);
+        self.caller_owns_return = self.guess_caller_owns_return(&OwnershipHints::none());
+        if self.is_variadic {
+            self.parameters
+                .push(FunctionParameter::Variadic(VariadicParameterDeclare));
+        }
+        Ok(())
+    }
+}
+
+// Variadic parameters are rare, boxing every named one to keep them small is not worth it.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FunctionParameter {
+    Named(ParameterDeclare),
+    Variadic(VariadicParameterDeclare),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VariadicParameterDeclare;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParameterDeclare {
+    /// `None` for a parameter of a prototype that is not given a name, as in `void f(int);`.
+    pub name: Option<String>,
+    pub parameter_type: Option<Type>,
+    /// Declared as `int values[]`, `int values[4]` or with a typedef of an array type rather
+    /// than as `int *values`.
+    pub is_array_parameter: bool,
+    pub direction: Option<ParameterDirection>,
+    pub location: Option<SourceLocation>,
+    pub doc_comment: Option<String>,
+}
+
+impl ParameterDeclare {
+    pub fn new(name: Option<String>) -> Self {
+        Self {
+            name,
+            parameter_type: None,
+            is_array_parameter: false,
+            direction: None,
+            location: None,
+            doc_comment: None,
+        }
+    }
+
+    /// The name of the parameter in generated code, `arg{index}` if the declaration does not
+    /// give it one. `index` is the position of the parameter in the function.
+    pub fn binding_name(&self, index: usize) -> String {
+        match &self.name {
+            Some(name) => name.to_owned(),
+            None => format!("arg{}", index),
+        }
+    }
+
+    /// Whether the parameter is declared with array syntax. C adjusts such a parameter to a
+    /// pointer to the element type, but the caller is expected to pass an array.
+    #[inline]
+    pub fn is_array_parameter(&self) -> bool {
+        self.is_array_parameter
+    }
+
+    /// libclang reports some parameters declared as arrays with the adjusted pointer type, in
+    /// that case the `[...]` closing the declaration gives them away.
+    fn is_declared_as_array(parameter_entity: &Entity) -> bool {
+        let is_array_type = parameter_entity
+            .get_type()
+            .map(|parameter_type| {
+                matches!(
+                    parameter_type.get_canonical_type().get_kind(),
+                    TypeKind::ConstantArray | TypeKind::IncompleteArray
+                )
+            })
+            .unwrap_or(false);
+        if is_array_type {
+            return true;
+        }
+        let tokens = parameter_entity
+            .get_range()
+            .map(|range| {
+                range
+                    .tokenize()
+                    .iter()
+                    .map(|token| token.get_spelling())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if tokens.last().map(String::as_str) != Some("]") {
+            return false;
+        }
+        // `int (*p)[4]` ends in `]` too, but its brackets follow the parenthesized pointer
+        // declarator rather than the name.
+        match parameter_entity.get_name() {
+            Some(name) => tokens
+                .iter()
+                .rposition(|token| *token == name)
+                .and_then(|index| tokens.get(index + 1))
+                .map(|token| token == "[")
+                .unwrap_or(false),
+            None => tokens
+                .iter()
+                .position(|token| token == "[")
+                .and_then(|index| index.checked_sub(1))
+                .map(|index| tokens[index] != ")")
+                .unwrap_or(false),
+        }
+    }
+
+    /// SAL annotations are macros that expand to nothing outside of code analysis builds, so they
+    /// are looked for in the tokens between the previous parameter and this one. Annotations
+    /// spelled as `__attribute__((annotate("_In_")))` come through as attributes instead.
+    fn annotation_tokens(parameter_entity: &Entity, function_entity: &Entity) -> Vec<String> {
+        let mut annotation_tokens = parameter_entity
+            .get_children()
+            .iter()
+            .filter(|child_entity| child_entity.get_kind() == EntityKind::AnnotateAttr)
+            .filter_map(|child_entity| child_entity.get_display_name())
+            .collect::<Vec<_>>();
+        let (parameter_range, function_range) =
+            match (parameter_entity.get_range(), function_entity.get_range()) {
+                (Some(parameter_range), Some(function_range)) => (parameter_range, function_range),
+                _ => return annotation_tokens,
+            };
+        let parameter_start = parameter_range.get_start().get_file_location().offset;
+        let parameter_end = parameter_range.get_end().get_file_location().offset;
+        let tokens = function_range
+            .tokenize()
+            .into_iter()
+            .map(|token| {
+                (
+                    token.get_location().get_file_location().offset,
+                    token.get_spelling(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let end = tokens
+            .iter()
+            .position(|(offset, _)| *offset > parameter_end)
+            .unwrap_or(tokens.len());
+        let mut start = tokens
+            .iter()
+            .position(|(offset, _)| *offset >= parameter_start)
+            .unwrap_or(end);
+        // Walk back to the `(` or `,` in front of the parameter, skipping over the parentheses
+        // of annotations like `_In_reads_(count)`.
+        let mut depth = 0;
+        while start > 0 {
+            match tokens[start - 1].1.as_str() {
+                ")" => depth += 1,
+                "(" if depth > 0 => depth -= 1,
+                "(" | "," if depth == 0 => break,
+                _ => {}
+            }
+            start -= 1;
+        }
+        annotation_tokens.extend(
+            tokens[start..end]
+                .iter()
+                .map(|(_, spelling)| spelling.to_owned()),
+        );
+        annotation_tokens
+    }
+}
+
+/// The data flow of a parameter according to its SAL annotation in Windows SDK headers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ParameterDirection {
+    /// `_In_`, `_In_opt_`, `_In_z_`, ...
+    In,
+    /// `_Out_`, `_Out_opt_`, `_Out_writes_(size)`, ...
+    Out,
+    /// `_Inout_`, `_Inout_opt_`, `_Inout_updates_(size)`, ...
+    InOut,
+    /// `_In_reads_(size)` and `_In_reads_bytes_(size)`, an input buffer whose length is given
+    /// by `size_param`.
+    InReads { size_param: String },
+}
+
+impl ParameterDirection {
+    /// Finds the first SAL annotation among the tokens of a parameter declaration.
+    pub fn parse(tokens: &[String]) -> Option<Self> {
+        tokens.iter().enumerate().find_map(|(index, token)| {
+            let annotation = token.strip_prefix('_')?.strip_suffix('_')?;
+            if annotation.starts_with("In_reads") {
+                let mut depth = 0;
+                let size_param = tokens[index + 1..]
+                    .iter()
+                    .take_while(|token| {
+                        match token.as_str() {
+                            "(" => depth += 1,
+                            ")" => depth -= 1,
+                            _ => {}
+                        }
+                        depth > 0
+                    })
+                    .skip(1)
+                    .map(|token| token.as_str())
+                    .collect::<String>();
+                return Some(ParameterDirection::InReads { size_param });
+            }
+            match annotation.split('_').next() {
+                Some("In") => Some(ParameterDirection::In),
+                Some("Out") => Some(ParameterDirection::Out),
+                Some("Inout") => Some(ParameterDirection::InOut),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl EntityVisitor for ParameterDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.name = Some(new_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::ParmDecl
+    }
+
+    fn visit_entity(
+        &mut self,
+        current_entity: &Entity,
+        parent_entity: &Entity,
+    ) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.parameter_type = current_entity
+            .get_type()
+            .map(|parameter_type| Type::from_clang(&parameter_type));
+        self.is_array_parameter = Self::is_declared_as_array(current_entity);
+        self.direction =
+            ParameterDirection::parse(&Self::annotation_tokens(current_entity, parent_entity));
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        self.doc_comment = current_entity.get_comment();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VariableDeclare {
+    pub name: String,
+    pub variable_type: Option<Type>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::option_linkage"))]
+    pub linkage: Option<Linkage>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::option_storage_class"))]
+    pub storage_class: Option<StorageClass>,
+    pub is_const: bool,
+    pub is_thread_local: bool,
+    pub has_initializer: bool,
+    pub declspec: Option<DeclSpec>,
+    pub location: Option<SourceLocation>,
+    pub usr: Option<String>,
+}
+
+impl VariableDeclare {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            variable_type: None,
+            linkage: None,
+            storage_class: None,
+            is_const: false,
+            is_thread_local: false,
+            has_initializer: false,
+            declspec: None,
+            location: None,
+            usr: None,
+        }
+    }
+}
+
+impl EntityVisitor for VariableDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::VarDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    #[inline]
+    fn linkage(&self) -> Option<Linkage> {
+        self.linkage
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        if let Some(variable_type) = current_entity.get_type() {
+            self.is_const = variable_type.is_const_qualified();
+            self.variable_type = Some(Type::from_clang(&variable_type));
+        }
+        self.linkage = current_entity.get_linkage();
+        self.storage_class = current_entity.get_storage_class();
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.is_thread_local = current_entity.get_tls_kind().is_some();
+        self.has_initializer = current_entity
+            .get_children()
+            .iter()
+            .any(|child_entity| child_entity.is_expression());
+        self.declspec = DeclSpec::from_clang(current_entity);
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
+
+/// A `static const` integer at file scope, e.g. `static const int MAX_SIZE = 1024;`. Unlike a
+/// macro it has a type, and unlike other variables its value is known.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConstantDeclare {
+    pub name: String,
+    pub constant_type: Option<Type>,
+    pub value: EnumConstantValue,
+    pub location: Option<SourceLocation>,
+    pub usr: Option<String>,
+}
+
+impl ConstantDeclare {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            constant_type: None,
+            value: EnumConstantValue {
+                signed: 0,
+                unsigned: 0,
+            },
+            location: None,
+            usr: None,
+        }
+    }
+
+    /// Whether `entity` is a `static const` integer variable whose value clang can evaluate.
+    pub fn is_constant(entity: &Entity) -> bool {
+        entity.get_kind() == EntityKind::VarDecl
+            && entity.get_storage_class() == Some(StorageClass::Static)
+            && entity
+                .get_type()
+                .map(|constant_type| {
+                    constant_type.is_const_qualified()
+                        && constant_type.get_canonical_type().is_integer()
+                })
+                .unwrap_or(false)
+            && Self::evaluate(entity).is_some()
+    }
+
+    fn evaluate(entity: &Entity) -> Option<EnumConstantValue> {
+        match entity.evaluate()? {
+            EvaluationResult::SignedInteger(value) => Some(EnumConstantValue {
+                signed: value,
+                unsigned: value as u64,
+            }),
+            EvaluationResult::UnsignedInteger(value) => Some(EnumConstantValue {
+                signed: value as i64,
+                unsigned: value,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl EntityVisitor for ConstantDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::VarDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.constant_type = current_entity
+            .get_type()
+            .map(|constant_type| Type::from_clang(&constant_type));
+        if let Some(value) = Self::evaluate(current_entity) {
+            self.value = value;
+        }
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
+
+/// The value of a `constexpr` variable as evaluated by clang.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ConstexprValue {
+    /// Unsigned values are stored as their bit pattern, the type tells how to read them.
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+/// A C++11 `constexpr` variable at file scope, e.g. `constexpr int VERSION = 42;`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConstexprDeclare {
+    pub name: String,
+    pub value_type: Type,
+    /// `None` for values clang cannot evaluate to a scalar, like `constexpr` objects of class
+    /// type.
+    pub value: Option<ConstexprValue>,
+    pub location: Option<SourceLocation>,
+    pub usr: Option<String>,
+}
+
+impl ConstexprDeclare {
+    pub fn new(name: String, value_type: Type) -> Self {
+        Self {
+            name,
+            value_type,
+            value: None,
+            location: None,
+            usr: None,
+        }
+    }
+
+    /// libclang has no query for `constexpr`, so it is looked for among the tokens of the
+    /// declaration ahead of the initializer.
+    pub fn is_constexpr(entity: &Entity) -> bool {
+        entity.get_kind() == EntityKind::VarDecl
+            && entity
+                .get_range()
+                .map(|range| {
+                    range
+                        .tokenize()
+                        .iter()
+                        .map(|token| token.get_spelling())
+                        .take_while(|spelling| spelling != "=" && spelling != "{")
+                        .any(|spelling| spelling == "constexpr")
+                })
+                .unwrap_or(false)
+    }
+}
+
+impl EntityVisitor for ConstexprDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::VarDecl
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        let is_bool = self.value_type.resolve_canonical().type_kind == TypeKind::Bool;
+        self.value = match current_entity.evaluate() {
+            Some(EvaluationResult::SignedInteger(value)) if is_bool => {
+                Some(ConstexprValue::Bool(value != 0))
+            }
+            Some(EvaluationResult::UnsignedInteger(value)) if is_bool => {
+                Some(ConstexprValue::Bool(value != 0))
+            }
+            Some(EvaluationResult::SignedInteger(value)) => Some(ConstexprValue::Integer(value)),
+            Some(EvaluationResult::UnsignedInteger(value)) => {
+                Some(ConstexprValue::Integer(value as i64))
+            }
+            Some(EvaluationResult::Float(value)) => Some(ConstexprValue::Float(value)),
+            _ => None,
+        };
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NamespaceDeclare {
+    /// `None` for an anonymous namespace.
+    pub name: Option<String>,
+    /// Qualified name of the namespace this one is nested in, if any.
+    pub enclosing_namespace: Option<String>,
+    pub nested_namespaces: Vec<NamespaceDeclare>,
+    pub type_declares: Vec<TypeDeclare>,
+    pub function_declares: Vec<FunctionDeclare>,
+    pub function_templates: Vec<FunctionTemplate>,
+    pub class_templates: Vec<ClassTemplate>,
+    pub variable_declares: Vec<VariableDeclare>,
+    pub constants: Vec<ConstantDeclare>,
+    pub constexpr_declares: Vec<ConstexprDeclare>,
+    pub location: Option<SourceLocation>,
+    pub usr: Option<String>,
+}
+
+impl NamespaceDeclare {
+    pub fn new(name: Option<String>) -> Self {
+        Self {
+            name,
+            enclosing_namespace: None,
+            nested_namespaces: Vec::new(),
+            type_declares: Vec::new(),
+            function_declares: Vec::new(),
+            function_templates: Vec::new(),
+            class_templates: Vec::new(),
+            variable_declares: Vec::new(),
+            constants: Vec::new(),
+            constexpr_declares: Vec::new(),
+            location: None,
+            usr: None,
+        }
+    }
+
+    /// The `A::B`-style name, with anonymous namespaces shown as `(anonymous namespace)`.
+    pub fn qualified_name(&self) -> String {
+        let name = self.name.as_deref().unwrap_or("(anonymous namespace)");
+        match &self.enclosing_namespace {
+            Some(enclosing_namespace) => format!("{}::{}", enclosing_namespace, name),
+            None => name.to_owned(),
+        }
+    }
+}
+
+impl EntityVisitor for NamespaceDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.name = Some(new_name);
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::Namespace
+    }
+
+    #[inline]
+    fn usr(&self) -> Option<&str> {
+        self.usr.as_ref().map(|usr| usr.as_ref())
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        let mut enclosing_names = Vec::new();
+        let mut semantic_parent = current_entity.get_semantic_parent();
+        while let Some(parent_entity) = semantic_parent {
+            if parent_entity.get_kind() != EntityKind::Namespace {
+                break;
+            }
+            enclosing_names.push(
+                parent_entity
+                    .get_name()
+                    .unwrap_or_else(|| "(anonymous namespace)".to_owned()),
+            );
+            semantic_parent = parent_entity.get_semantic_parent();
+        }
+        if !enclosing_names.is_empty() {
+            enclosing_names.reverse();
+            self.enclosing_namespace = Some(enclosing_names.join("::"));
+        }
+        self.visit_declarations(current_entity)?;
+        self.usr = current_entity.get_usr().map(|usr| usr.0);
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
+
+impl NamespaceDeclare {
+    /// Walks the declarations directly in a namespace or a linkage specification in it, skipping
+    /// the kinds `SourceFile` skips at the top level.
+    fn visit_declarations(&mut self, parent_entity: &Entity) -> Result<(), AstError> {
+        for child_entity in parent_entity.get_children().iter() {
+            match child_entity.get_kind() {
+                EntityKind::LinkageSpec => self.visit_declarations(child_entity)?,
+                EntityKind::Namespace => {
+                    self.nested_namespaces.push(
+                        NamespaceDeclare::new(child_entity.get_name())
+                            .visited(child_entity, parent_entity)?,
+                    );
+                }
+                EntityKind::EnumDecl
+                | EntityKind::StructDecl
+                | EntityKind::UnionDecl
+                | EntityKind::ClassDecl
+                | EntityKind::TypedefDecl => {
+                    SourceFile::parse_type_declare(
+                        child_entity,
+                        parent_entity,
+                        &mut self.type_declares,
+                    )?;
+                }
+                EntityKind::FunctionDecl => {
+                    let function_name = child_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(child_entity))?;
+                    self.function_declares.push(
+                        FunctionDeclare::new(function_name).visited(child_entity, parent_entity)?,
+                    );
+                }
+                EntityKind::VarDecl => {
+                    let variable_name = child_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(child_entity))?;
+                    if ConstexprDeclare::is_constexpr(child_entity) {
+                        let value_type = child_entity
+                            .get_type()
+                            .ok_or_else(|| AstError::missing_type(child_entity))?;
+                        self.constexpr_declares.push(
+                            ConstexprDeclare::new(variable_name, Type::from_clang(&value_type))
+                                .visited(child_entity, parent_entity)?,
+                        );
+                    } else if ConstantDeclare::is_constant(child_entity) {
+                        self.constants.push(
+                            ConstantDeclare::new(variable_name)
+                                .visited(child_entity, parent_entity)?,
+                        );
+                    } else {
+                        self.variable_declares.push(
+                            VariableDeclare::new(variable_name)
+                                .visited(child_entity, parent_entity)?,
+                        );
+                    }
+                }
+                EntityKind::FunctionTemplate => {
+                    let function_name = child_entity
+                        .get_name()
+                        .ok_or_else(|| AstError::missing_name(child_entity))?;
+                    self.function_templates.push(
+                        FunctionTemplate::new(function_name)
+                            .visited(child_entity, parent_entity)?,
+                    );
+                }
+                EntityKind::ClassTemplate => {
+                    self.class_templates.push(
+                        ClassTemplate::new(child_entity.get_name())
+                            .visited(child_entity, parent_entity)?,
+                    );
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MacroDefinition {
+    pub name: String,
+    pub body_tokens: Vec<String>,
+    pub location: Option<SourceLocation>,
+}
+
+impl MacroDefinition {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            body_tokens: Vec::new(),
+            location: None,
+        }
+    }
+}
+
+impl EntityVisitor for MacroDefinition {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::MacroDefinition
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        if let Some(range) = current_entity.get_range() {
+            self.body_tokens = range
+                .tokenize()
+                .into_iter()
+                .map(|token| token.get_spelling())
+                .skip(1)
+                .collect();
+        }
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
+
+/// A function-like macro such as `#define MAX(a, b) ((a) > (b) ? (a) : (b))`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MacroFunctionDeclare {
+    pub name: String,
+    /// Includes `...` for a variadic macro.
+    pub parameter_names: Vec<String>,
+    pub body_tokens: Vec<String>,
+    pub location: Option<SourceLocation>,
+}
+
+impl MacroFunctionDeclare {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            parameter_names: Vec::new(),
+            body_tokens: Vec::new(),
+            location: None,
+        }
+    }
+}
+
+impl EntityVisitor for MacroFunctionDeclare {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::MacroDefinition
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        if let Some(range) = current_entity.get_range() {
+            // The name is followed by `(`, the parameter list and `)`.
+            let mut tokens = range
+                .tokenize()
+                .into_iter()
+                .map(|token| token.get_spelling())
+                .skip(2);
+            for token in tokens.by_ref() {
+                match token.as_str() {
+                    ")" => break,
+                    "," => {}
+                    _ => self.parameter_names.push(token),
+                }
+            }
+            self.body_tokens = tokens.collect();
+        }
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MacroExpansion {
+    pub name: String,
+    pub location: Option<SourceLocation>,
+}
+
+impl MacroExpansion {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            location: None,
+        }
+    }
+}
+
+impl EntityVisitor for MacroExpansion {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::MacroExpansion
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
+
+/// How a header protects itself against being included twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum IncludeGuard {
+    PragmaOnce,
+    /// `#ifndef FOO_H` followed by `#define FOO_H` before anything else in the file.
+    MacroGuard {
+        macro_name: String,
+    },
+}
+
+impl IncludeGuard {
+    /// Looks for the guard in the text of a header. libclang keeps neither pragmas nor
+    /// conditional directives in the AST, so the directives are picked out of the source lines.
+    pub fn detect(source: &str) -> Option<IncludeGuard> {
+        let lines = code_lines(source);
+        if lines.iter().any(|words| words == &["#", "pragma", "once"]) {
+            return Some(IncludeGuard::PragmaOnce);
+        }
+        match (lines.first(), lines.get(1)) {
+            (Some(ifndef), Some(define))
+                if ifndef.len() == 3
+                    && ifndef[..2] == ["#", "ifndef"]
+                    && define.len() == 3
+                    && define[..2] == ["#", "define"]
+                    && ifndef[2] == define[2] =>
+            {
+                Some(IncludeGuard::MacroGuard {
+                    macro_name: ifndef[2].to_owned(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Splits C source into the words of each line with comments removed, skipping lines left
+/// empty. A leading `#` becomes a word of its own, so `#  define` and `# define` look alike.
+fn code_lines(source: &str) -> Vec<Vec<String>> {
+    let mut in_block_comment = false;
+    let mut lines = Vec::new();
+    for line in source.lines() {
+        let mut code = String::new();
+        let mut rest = line;
+        loop {
+            if in_block_comment {
+                match rest.find("*/") {
+                    Some(index) => {
+                        rest = &rest[index + 2..];
+                        in_block_comment = false;
+                    }
+                    None => break,
+                }
+                continue;
+            }
+            match (rest.find("/*"), rest.find("//")) {
+                (Some(block_index), Some(line_index)) if line_index < block_index => {
+                    code.push_str(&rest[..line_index]);
+                    break;
+                }
+                (Some(block_index), _) => {
+                    code.push_str(&rest[..block_index]);
+                    code.push(' ');
+                    rest = &rest[block_index + 2..];
+                    in_block_comment = true;
+                }
+                (None, Some(line_index)) => {
+                    code.push_str(&rest[..line_index]);
+                    break;
+                }
+                (None, None) => {
+                    code.push_str(rest);
+                    break;
+                }
+            }
+        }
+        let code = code.trim();
+        let code = match code.strip_prefix('#') {
+            Some(directive) => format!("# {}", directive),
+            None => code.to_owned(),
+        };
+        let words: Vec<String> = code
+            .split_whitespace()
+            .map(|word| word.to_owned())
+            .collect();
+        if !words.is_empty() {
+            lines.push(words);
+        }
+    }
+    lines
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IncludeDirective {
+    pub included_path: String,
+    pub is_angled: bool,
+    pub location: Option<SourceLocation>,
+}
+
+impl IncludeDirective {
+    pub fn new(included_path: String) -> Self {
+        Self {
+            included_path,
+            is_angled: false,
+            location: None,
+        }
+    }
+}
+
+impl EntityVisitor for IncludeDirective {
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        Some(self.included_path.as_str())
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        self.included_path = new_name;
+    }
+
+    #[inline]
+    fn entity_kind(&self) -> EntityKind {
+        EntityKind::InclusionDirective
+    }
+
+    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) -> Result<(), AstError> {
+        self.check_entity_kind(current_entity)?;
+        if let Some(included_file) = current_entity.get_file() {
+            self.included_path = included_file.get_path().to_string_lossy().into_owned();
+        }
+        self.is_angled = current_entity
+            .get_range()
+            .map(|range| {
+                range
+                    .tokenize()
+                    .iter()
+                    .any(|token| token.get_spelling() == "<")
+            })
+            .unwrap_or(false);
+        self.location = current_entity
+            .get_location()
+            .map(|source_location| SourceLocation::from_clang(&source_location));
+        Ok(())
+    }
+}
@@ -1,638 +1,1009 @@
-use clang::{source, Clang, Entity, EntityKind, Index, TypeKind};
-use std::{env::args_os, fmt::Debug, iter};
-use tap::TapOps;
-
-trait EntityVisitor: Debug {
-    fn name(&self) -> Option<&str>;
-    fn set_name(&mut self, new_name: String);
-    fn entity_kind(&self) -> EntityKind;
-    fn visit_entity(&mut self, current_entity: &Entity, parent_entity: &Entity);
+use clang::Entity;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    env::args_os,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+    sync::mpsc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+#[cfg(feature = "serde")]
+use std::{
+    process::Command,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+use test_clang_rust::{
+    codegen,
+    completeness::CompletenessChecker,
+    export_list::ExportList,
+    graph, markdown,
+    modules::module_name,
+    output_dir::{self, DirectoryWriter, SplitBy},
+    parse_file, parse_file_with,
+    tree::TreePrinter,
+    EntityVisitor, FilterMode, LanguageStandard, MemberDeclare, ParseError, ParseOptions,
+    SourceFile, TypeDeclaration, TypeDeclare,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Debug,
+    Json,
+    Yaml,
+    Dot,
+    Markdown,
+}
+
+impl OutputFormat {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "debug" => Ok(OutputFormat::Debug),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "dot" => Ok(OutputFormat::Dot),
+            "markdown" => Ok(OutputFormat::Markdown),
+            _ => Err(format!("Unknown output format: {}", name)),
+        }
+    }
 }
 
-trait TypeDeclaration: EntityVisitor {
-    fn typedef_name(&self) -> Option<&str>;
-    fn set_typedef_name(&mut self, new_typedef_name: String);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EmitTarget {
+    RustFfi,
+    Zig,
+    CSharp,
+    PythonCtypes,
 }
 
-#[derive(Debug)]
-struct SourceLocation {
-    path: String,
-    line_number: u32,
-    column_number: u32,
-}
-
-impl SourceLocation {
-    fn new(loc: (String, u32, u32)) -> Self {
-        Self {
-            path: loc.0,
-            line_number: loc.1,
-            column_number: loc.2,
+impl EmitTarget {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "rust-ffi" => Ok(EmitTarget::RustFfi),
+            "zig" => Ok(EmitTarget::Zig),
+            "csharp" => Ok(EmitTarget::CSharp),
+            "python-ctypes" => Ok(EmitTarget::PythonCtypes),
+            _ => Err(format!("Unknown emit target: {}", name)),
         }
     }
-
-    fn from_clang(loc: &source::SourceLocation) -> Self {
-        Self::new(loc.get_presumed_location())
-    }
 }
 
+/// A search for functions by the types in their signature, given as `--query return-type=<name>`
+/// or `--query param-type=<name>`.
 #[derive(Debug)]
-struct Type {
-    type_kind: TypeKind,
-    type_name: String,
-    pointee_type: Option<Box<Type>>,
-}
-
-impl Type {
-    fn new(kind: TypeKind, name: String, pointee_type: Option<Box<Type>>) -> Self {
-        Self {
-            type_kind: kind,
-            type_name: name,
-            pointee_type,
+enum Query {
+    ReturnType(String),
+    ParamType(String),
+}
+
+impl Query {
+    fn parse(query: &str) -> Result<Self, String> {
+        match query
+            .find('=')
+            .map(|index| (&query[..index], &query[index + 1..]))
+        {
+            Some(("return-type", type_name)) => Ok(Query::ReturnType(type_name.to_owned())),
+            Some(("param-type", type_name)) => Ok(Query::ParamType(type_name.to_owned())),
+            _ => Err(format!("Unknown query: {}", query)),
         }
     }
-
-    fn from_clang(t: &clang::Type) -> Self {
-        Self::new(
-            t.get_kind(),
-            t.get_display_name(),
-            t.get_pointee_type()
-                .map(|pt| Box::new(Self::from_clang(&pt))),
-        )
-    }
 }
 
 #[derive(Debug)]
-struct SourceFile {
-    path: String,
-    type_declares: Vec<Box<dyn TypeDeclaration>>,
-    function_declares: Vec<FunctionDeclare>,
-}
-
-impl SourceFile {
-    fn new(path: String) -> Self {
-        SourceFile {
-            path,
-            type_declares: Vec::new(),
-            function_declares: Vec::new(),
+struct Options {
+    format: OutputFormat,
+    emit: Option<EmitTarget>,
+    bitflags: bool,
+    dll_name: Option<String>,
+    module_prefix: Option<String>,
+    output_dir: Option<PathBuf>,
+    split_by: SplitBy,
+    parse_options: ParseOptions,
+    only_extern: bool,
+    skip_forward_declarations: bool,
+    show_layout: bool,
+    layout_report: bool,
+    show_includes: bool,
+    show_stats: bool,
+    declarations_only: bool,
+    skip_inline: bool,
+    check_completeness: bool,
+    validate: bool,
+    query: Option<Query>,
+    function_prefix: Option<String>,
+    type_prefix: Option<String>,
+    export_list: Option<ExportList>,
+    jobs: usize,
+    /// Set in the processes `parse_in_workers` starts, to the index of the file they parse and
+    /// where to write it.
+    parse_worker: Option<(usize, PathBuf)>,
+    watch: bool,
+    diff: bool,
+    tree: bool,
+    tree_depth: Option<usize>,
+    tree_indent: usize,
+    tree_all_files: bool,
+    tree_system_headers: bool,
+    file_paths: Vec<OsString>,
+}
+
+impl Options {
+    fn from_args(args: impl Iterator<Item = OsString>) -> Result<Self, String> {
+        let mut options = Options {
+            format: OutputFormat::Debug,
+            emit: None,
+            bitflags: false,
+            dll_name: None,
+            module_prefix: None,
+            output_dir: None,
+            split_by: SplitBy::Module,
+            parse_options: ParseOptions::default(),
+            only_extern: false,
+            skip_forward_declarations: false,
+            show_layout: false,
+            layout_report: false,
+            show_includes: false,
+            show_stats: false,
+            declarations_only: false,
+            skip_inline: false,
+            check_completeness: false,
+            validate: false,
+            query: None,
+            function_prefix: None,
+            type_prefix: None,
+            export_list: None,
+            jobs: thread::available_parallelism()
+                .map(|jobs| jobs.get())
+                .unwrap_or(1),
+            parse_worker: None,
+            watch: false,
+            diff: false,
+            tree: false,
+            tree_depth: None,
+            tree_indent: 4,
+            tree_all_files: false,
+            tree_system_headers: false,
+            file_paths: Vec::new(),
+        };
+        let mut parse_worker = None;
+        let mut worker_output = None;
+        let mut args = args.peekable();
+        if args.peek().map(|arg| arg == "diff").unwrap_or(false) {
+            args.next();
+            options.diff = true;
         }
-    }
-
-    fn parse_type_declare(
-        current_entity: &Entity,
-        parent_entity: &Entity,
-        declares: &mut Vec<Box<dyn TypeDeclaration>>,
-    ) {
-        match current_entity.get_kind() {
-            EntityKind::EnumDecl => {
-                if let Some(name) = current_entity.get_name() {
-                    declares.push(Box::new(EnumDeclare::new(Some(name), None)).tap(
-                        |enum_declare| {
-                            enum_declare.visit_entity(current_entity, parent_entity);
-                        },
-                    ));
+        while let Some(arg) = args.next() {
+            let flag = match arg.to_str() {
+                Some(flag) if flag.starts_with('-') => flag.to_owned(),
+                _ => {
+                    options.file_paths.push(arg);
+                    continue;
                 }
-            }
-            EntityKind::StructDecl => {
-                if let Some(name) = current_entity.get_name() {
-                    declares.push(Box::new(StructDeclare::new(Some(name), None)).tap(
-                        |struct_declare| {
-                            struct_declare.visit_entity(current_entity, parent_entity);
-                        },
-                    ));
-                }
-            }
-            EntityKind::UnionDecl => {
-                if let Some(name) = current_entity.get_name() {
-                    declares.push(Box::new(UnionDeclare::new(Some(name), None)).tap(
-                        |union_declare| {
-                            union_declare.visit_entity(current_entity, parent_entity);
-                        },
-                    ));
-                }
-            }
-            EntityKind::TypedefDecl => {
-                if let Some(declaration_entity) = current_entity
-                    .get_typedef_underlying_type()
-                    .and_then(|t| t.get_declaration())
-                {
-                    if let Some(declare) = declares
-                        .iter_mut()
-                        .filter(|declare| {
-                            if let (Some(name), Some(typedef_name)) =
-                                (declare.name(), current_entity.get_name())
-                            {
-                                return name == typedef_name;
-                            }
-                            false
-                        })
-                        .next()
-                    {
-                        if let Some(typedef_name) = current_entity.get_name() {
-                            declare.set_typedef_name(typedef_name);
-                        }
-                    } else {
-                        let declare: Box<dyn TypeDeclaration> = match declaration_entity.get_kind()
-                        {
-                            EntityKind::EnumDecl => Box::new(EnumDeclare::new(
-                                None,
-                                current_entity.get_name(),
-                            ))
-                            .tap(|enum_declare| {
-                                enum_declare.visit_entity(&declaration_entity, parent_entity);
-                            }),
-                            EntityKind::StructDecl => Box::new(StructDeclare::new(
-                                None,
-                                current_entity.get_name(),
-                            ))
-                            .tap(|struct_declare| {
-                                struct_declare.visit_entity(&declaration_entity, parent_entity);
-                            }),
-                            EntityKind::UnionDecl => Box::new(UnionDeclare::new(
-                                None,
-                                current_entity.get_name(),
-                            ))
-                            .tap(|union_declare| {
-                                union_declare.visit_entity(&declaration_entity, parent_entity);
-                            }),
-                            _ => panic!(
-                                "Unexpected typedef declaration entity: {:?}",
-                                declaration_entity
-                            ),
+            };
+            if let Some(format) = flag_value("--format", &flag, &mut args)? {
+                options.format = OutputFormat::parse(&format)?;
+            } else if let Some(emit) = flag_value("--emit", &flag, &mut args)? {
+                options.emit = Some(EmitTarget::parse(&emit)?);
+            } else if let Some(dll_name) = flag_value("--dll-name", &flag, &mut args)? {
+                options.dll_name = Some(dll_name);
+            } else if let Some(module_prefix) = flag_value("--module-prefix", &flag, &mut args)? {
+                options.module_prefix = Some(module_prefix);
+            } else if let Some(output_dir) = flag_value("--output-dir", &flag, &mut args)? {
+                options.output_dir = Some(output_dir.into());
+            } else if let Some(split_by) = flag_value("--split-by", &flag, &mut args)? {
+                options.split_by = SplitBy::parse(&split_by)
+                    .ok_or_else(|| format!("Unknown value for --split-by: {}", split_by))?;
+            } else if let Some(language) = flag_value("--language", &flag, &mut args)? {
+                // A standard such as `c99` or `c++17` picks the language along with it.
+                match LanguageStandard::parse(&language) {
+                    Ok(language_standard) => {
+                        let language = if language_standard.is_cxx() {
+                            "c++"
+                        } else {
+                            "c"
                         };
-                        declares.push(declare);
+                        options.parse_options.language = Some(language.to_owned());
+                        options.parse_options.language_standard = language_standard;
                     }
+                    Err(_) => options.parse_options.language = Some(language),
                 }
+            } else if let Some(language_standard) = flag_value("--std", &flag, &mut args)? {
+                options.parse_options.language_standard =
+                    LanguageStandard::parse(&language_standard)?;
+            } else if let Some(target_triple) = flag_value("--target", &flag, &mut args)? {
+                options.parse_options.target_triple = Some(target_triple);
+            } else if let Some(include_path) = short_flag_value("-I", &flag, &mut args)? {
+                options
+                    .parse_options
+                    .include_paths
+                    .push(include_path.into());
+            } else if let Some(define) = short_flag_value("-D", &flag, &mut args)? {
+                options.parse_options.defines.push(match define.find('=') {
+                    Some(index) => (
+                        define[..index].to_owned(),
+                        Some(define[index + 1..].to_owned()),
+                    ),
+                    None => (define, None),
+                });
+            } else if let Some(query) = flag_value("--query", &flag, &mut args)? {
+                options.query = Some(Query::parse(&query)?);
+            } else if let Some(function_prefix) = flag_value("--function-prefix", &flag, &mut args)?
+            {
+                options.function_prefix = Some(function_prefix);
+            } else if let Some(type_prefix) = flag_value("--type-prefix", &flag, &mut args)? {
+                options.type_prefix = Some(type_prefix);
+            } else if let Some(export_list) = flag_value("--export-list", &flag, &mut args)? {
+                options.export_list = Some(
+                    ExportList::from_file(Path::new(&export_list))
+                        .map_err(|err| format!("Failed to read {}: {}", export_list, err))?,
+                );
+            } else if let Some(jobs) = flag_value("--jobs", &flag, &mut args)? {
+                options.jobs = jobs
+                    .parse()
+                    .map_err(|_| format!("Invalid value for --jobs: {}", jobs))?;
+            } else if let Some(index) = flag_value("--parse-worker", &flag, &mut args)? {
+                parse_worker = Some(
+                    index
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid value for --parse-worker: {}", index))?,
+                );
+            } else if let Some(output_path) = flag_value("--worker-output", &flag, &mut args)? {
+                worker_output = Some(PathBuf::from(output_path));
+            } else if let Some(tree_depth) = flag_value("--tree-depth", &flag, &mut args)? {
+                options.tree_depth = Some(
+                    tree_depth
+                        .parse()
+                        .map_err(|_| format!("Invalid value for --tree-depth: {}", tree_depth))?,
+                );
+            } else if let Some(tree_indent) = flag_value("--tree-indent", &flag, &mut args)? {
+                options.tree_indent = tree_indent
+                    .parse()
+                    .map_err(|_| format!("Invalid value for --tree-indent: {}", tree_indent))?;
+            } else if flag == "--bitflags" {
+                options.bitflags = true;
+            } else if flag == "--only-extern" {
+                options.only_extern = true;
+            } else if flag == "--skip-forward-decls" {
+                options.skip_forward_declarations = true;
+            } else if flag == "--show-layout" {
+                options.show_layout = true;
+            } else if flag == "--layout" {
+                options.layout_report = true;
+            } else if flag == "--show-includes" {
+                options.show_includes = true;
+            } else if flag == "--stats" {
+                options.show_stats = true;
+            } else if flag == "--declarations-only" {
+                options.declarations_only = true;
+            } else if flag == "--skip-system-headers" {
+                options.parse_options.filter_mode = FilterMode::SkipSystemHeaders;
+            } else if flag == "--parse-all-comments" {
+                options.parse_options.parse_all_comments = true;
+            } else if flag == "--skip-inline" {
+                options.skip_inline = true;
+            } else if flag == "--check-completeness" {
+                options.check_completeness = true;
+            } else if flag == "--validate" {
+                options.validate = true;
+            } else if flag == "--watch" {
+                options.watch = true;
+            } else if flag == "--tree" {
+                options.tree = true;
+            } else if flag == "--tree-all-files" {
+                options.tree_all_files = true;
+            } else if flag == "--tree-system-headers" {
+                options.tree_system_headers = true;
+            } else {
+                return Err(format!("Unknown option: {}", flag));
             }
-            _ => panic!("Unexpected type entity: {:?}", current_entity),
         }
-    }
-}
-
-impl EntityVisitor for SourceFile {
-    #[inline]
-    fn name(&self) -> Option<&str> {
-        Some(self.path.as_ref())
-    }
-
-    #[inline]
-    fn set_name(&mut self, new_name: String) {
-        self.path = new_name;
-    }
-
-    #[inline]
-    fn entity_kind(&self) -> EntityKind {
-        EntityKind::TranslationUnit
-    }
-
-    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) {
-        assert_eq!(current_entity.get_kind(), self.entity_kind());
-        let children = current_entity.get_children();
-        for next_entity in children.iter().filter(|entity| entity.is_in_main_file()) {
-            match next_entity.get_kind() {
-                EntityKind::EnumDecl | EntityKind::StructDecl | EntityKind::TypedefDecl => {
-                    Self::parse_type_declare(next_entity, current_entity, &mut self.type_declares);
-                }
-                EntityKind::FunctionDecl => {
-                    if let Some(function_name) = next_entity.get_name() {
-                        self.function_declares
-                            .push(FunctionDeclare::new(function_name).tap(|function_declare| {
-                                function_declare.visit_entity(next_entity, current_entity);
-                            }));
-                    } else {
-                        panic!("Unnamed function was declared")
-                    }
+        options.parse_worker = match (parse_worker, worker_output) {
+            (Some(index), Some(worker_output)) => Some((index, worker_output)),
+            (None, None) => None,
+            _ => return Err("--parse-worker and --worker-output go together".to_owned()),
+        };
+        if options.diff && options.file_paths.len() != 2 {
+            return Err("Usage: diff <old file> <new file>".to_owned());
+        }
+        Ok(options)
+    }
+
+    fn show_entities(&self) -> bool {
+        self.tree
+            || (!self.show_layout
+                && !self.layout_report
+                && !self.show_includes
+                && !self.show_stats
+                && self.query.is_none()
+                && self.emit.is_none()
+                && self.output_dir.is_none()
+                && self.format == OutputFormat::Debug)
+    }
+
+    fn tree_printer(&self) -> TreePrinter {
+        let mut tree_printer = TreePrinter::new();
+        tree_printer.indent_width = self.tree_indent;
+        tree_printer.max_depth = self.tree_depth;
+        tree_printer.show_system_headers = self.tree_system_headers;
+        if !self.tree_all_files {
+            tree_printer.filter = Some(Box::new(|entity: &Entity| entity.is_in_main_file()));
+        }
+        tree_printer
+    }
+}
+
+fn flag_value(
+    flag: &str,
+    arg: &str,
+    args: &mut impl Iterator<Item = OsString>,
+) -> Result<Option<String>, String> {
+    if arg == flag {
+        args.next()
+            .and_then(|value| value.into_string().ok())
+            .map(Some)
+            .ok_or_else(|| format!("Missing value for {}", flag))
+    } else if arg.starts_with(flag) && arg[flag.len()..].starts_with('=') {
+        Ok(Some(arg[flag.len() + 1..].to_owned()))
+    } else {
+        Ok(None)
+    }
+}
+
+fn short_flag_value(
+    flag: &str,
+    arg: &str,
+    args: &mut impl Iterator<Item = OsString>,
+) -> Result<Option<String>, String> {
+    if arg == flag {
+        args.next()
+            .and_then(|value| value.into_string().ok())
+            .map(Some)
+            .ok_or_else(|| format!("Missing value for {}", flag))
+    } else if let Some(value) = arg.strip_prefix(flag) {
+        Ok(Some(value.to_owned()))
+    } else {
+        Ok(None)
+    }
+}
+
+fn print_source_file(source_file: &SourceFile, format: OutputFormat) {
+    match format {
+        OutputFormat::Debug => println!("****** source_file: {:#?}", source_file),
+        #[cfg(feature = "serde")]
+        OutputFormat::Json => {
+            let mut value = serde_json::to_value(source_file).unwrap();
+            value["api_hash"] = api_hash(source_file).into();
+            println!("{}", serde_json::to_string_pretty(&value).unwrap())
+        }
+        #[cfg(not(feature = "serde"))]
+        OutputFormat::Json => {
+            eprintln!("JSON output requires the `serde` feature");
+            exit(1);
+        }
+        #[cfg(feature = "yaml")]
+        OutputFormat::Yaml => {
+            let mut value = serde_yaml::to_value(source_file).unwrap();
+            value["api_hash"] = api_hash(source_file).into();
+            print!("{}", serde_yaml::to_string(&value).unwrap())
+        }
+        #[cfg(not(feature = "yaml"))]
+        OutputFormat::Yaml => {
+            eprintln!("YAML output requires the `yaml` feature");
+            exit(1);
+        }
+        OutputFormat::Dot => print!("{}", graph::DotGraphEmitter::new(source_file).emit()),
+        OutputFormat::Markdown => print!(
+            "{}",
+            markdown::MarkdownTableEmitter::new(source_file).emit()
+        ),
+    }
+}
+
+/// Written next to the serialized AST so API changes can be spotted by comparing one field. It is
+/// a hex string as JSON consumers tend to lose precision on integers above 2^53.
+#[cfg(feature = "serde")]
+fn api_hash(source_file: &SourceFile) -> String {
+    format!("{:016x}", source_file.compute_hash())
+}
+
+fn print_layout(source_file: &SourceFile) {
+    for type_declare in source_file.type_declares.iter() {
+        let (keyword, size_bytes, align_bytes, fields) = match type_declare {
+            TypeDeclare::Struct(declare) => (
+                "struct",
+                declare.size_bytes,
+                declare.align_bytes,
+                &declare.fields,
+            ),
+            TypeDeclare::Union(declare) => (
+                "union",
+                declare.size_bytes,
+                declare.align_bytes,
+                &declare.fields,
+            ),
+            TypeDeclare::Enum(_)
+            | TypeDeclare::Class(_)
+            | TypeDeclare::Typedef(_)
+            | TypeDeclare::FunctionPointer(_) => continue,
+        };
+        let name = type_declare
+            .name()
+            .or_else(|| type_declare.typedef_name())
+            .unwrap_or("<anonymous>");
+        println!(
+            "{} {}: size {}, align {}",
+            keyword,
+            name,
+            optional_bytes(size_bytes),
+            optional_bytes(align_bytes)
+        );
+        println!("{:>8}  field", "offset");
+        print_member_layout(fields, 0);
+        println!();
+    }
+}
+
+fn print_member_layout(members: &[MemberDeclare], level: usize) {
+    let prefix_spaces = " ".repeat(level * 4);
+    for member in members.iter() {
+        match member {
+            MemberDeclare::Field(field_declare) => {
+                let type_name = field_declare
+                    .field_type
+                    .as_ref()
+                    .map(|field_type| field_type.type_name.as_str())
+                    .unwrap_or("?");
+                print!(
+                    "{:>8}  {}{}: {}",
+                    optional_bytes(field_declare.byte_offset),
+                    prefix_spaces,
+                    field_declare
+                        .name()
+                        .unwrap_or(if field_declare.is_anonymous {
+                            "<anonymous>"
+                        } else {
+                            "<unnamed>"
+                        }),
+                    type_name
+                );
+                match field_declare.bit_width {
+                    Some(bit_width) => println!(" : {}", bit_width),
+                    None => println!(),
                 }
-                _ => panic!("Unexpected entity: {:?}", next_entity),
             }
+            MemberDeclare::Struct(struct_declare) => {
+                println!(
+                    "{:>8}  {}struct {}",
+                    "",
+                    prefix_spaces,
+                    struct_declare.name().unwrap_or("<anonymous>")
+                );
+                print_member_layout(&struct_declare.fields, level + 1);
+            }
+            MemberDeclare::Union(union_declare) => {
+                println!(
+                    "{:>8}  {}union {}",
+                    "",
+                    prefix_spaces,
+                    union_declare.name().unwrap_or("<anonymous>")
+                );
+                print_member_layout(&union_declare.fields, level + 1);
+            }
+            // An enum declared in the body takes no space in the record.
+            MemberDeclare::Enum(_) => {}
         }
     }
 }
 
-#[derive(Debug)]
-struct EnumConstantValue {
-    signed: i64,
-    unsigned: u64,
-}
-
-#[derive(Debug)]
-struct EnumConstantDeclare {
-    name: String,
-    location: Option<SourceLocation>,
-    constant_value: Option<EnumConstantValue>,
-}
-
-impl EnumConstantDeclare {
-    fn new(name: String) -> Self {
-        Self {
-            name,
-            location: None,
-            constant_value: None,
+fn print_includes(source_file: &SourceFile) {
+    let include_graph = source_file.include_graph();
+    let included_paths = include_graph.values().flatten().collect::<HashSet<_>>();
+    let mut root_paths = include_graph
+        .keys()
+        .filter(|path| !included_paths.contains(path))
+        .collect::<Vec<_>>();
+    root_paths.sort();
+    let mut visited = HashSet::new();
+    for root_path in root_paths {
+        print_include_tree(&include_graph, root_path, 0, &mut visited);
+    }
+}
+
+fn print_include_tree<'a>(
+    include_graph: &'a HashMap<String, Vec<String>>,
+    path: &'a str,
+    level: usize,
+    visited: &mut HashSet<&'a str>,
+) {
+    let prefix_spaces = " ".repeat(level * 4);
+    if !visited.insert(path) {
+        println!("{}{} (already shown)", prefix_spaces, path);
+        return;
+    }
+    println!("{}{}", prefix_spaces, path);
+    if let Some(included_paths) = include_graph.get(path) {
+        for included_path in included_paths.iter() {
+            print_include_tree(include_graph, included_path, level + 1, visited);
         }
     }
 }
 
-impl EntityVisitor for EnumConstantDeclare {
-    #[inline]
-    fn name(&self) -> Option<&str> {
-        Some(self.name.as_ref())
-    }
-
-    #[inline]
-    fn set_name(&mut self, new_name: String) {
-        self.name = new_name;
-    }
-
-    #[inline]
-    fn entity_kind(&self) -> EntityKind {
-        EntityKind::EnumConstantDecl
-    }
-
-    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) {
-        assert_eq!(current_entity.get_kind(), self.entity_kind());
-        if let Some((signed_value, unsigned_value)) = current_entity.get_enum_constant_value() {
-            self.constant_value = Some(EnumConstantValue {
-                unsigned: unsigned_value,
-                signed: signed_value,
-            });
-        }
-        self.location = current_entity
-            .get_location()
-            .map(|source_location| SourceLocation::from_clang(&source_location));
-    }
+fn optional_bytes(bytes: Option<u64>) -> String {
+    bytes
+        .map(|bytes| bytes.to_string())
+        .unwrap_or_else(|| "?".to_owned())
 }
 
-#[derive(Debug)]
-struct EnumDeclare {
-    enum_name: Option<String>,
-    typedef_name: Option<String>,
-    constants: Vec<EnumConstantDeclare>,
-    enum_type: Option<Type>,
-    location: Option<SourceLocation>,
-}
-
-impl EnumDeclare {
-    fn new(enum_name: Option<String>, typedef_name: Option<String>) -> Self {
-        Self {
-            enum_name,
-            typedef_name,
-            constants: Vec::new(),
-            enum_type: None,
-            location: None,
+fn emit_source_file(source_file: &SourceFile, target: EmitTarget, options: &Options) {
+    match target {
+        EmitTarget::RustFfi => match &options.module_prefix {
+            Some(module_prefix) => {
+                let mut root = RustModule::default();
+                for (module_path, module_file) in
+                    source_file.group_by_module_with_prefix(module_prefix)
+                {
+                    let module = module_path
+                        .split('/')
+                        .filter(|name| !name.is_empty())
+                        .fold(&mut root, |module, name| {
+                            module.modules.entry(module_name(name)).or_default()
+                        });
+                    module.bindings = rust_bindings(&module_file, options);
+                }
+                print!("{}", root.bindings);
+                print_rust_modules(&root, 0);
+            }
+            None => print!("{}", rust_bindings(source_file, options)),
+        },
+        EmitTarget::Zig => print!("{}", codegen::ZigFfiGenerator::new(source_file).generate()),
+        EmitTarget::CSharp => {
+            let mut generator = codegen::PInvokeGenerator::new(source_file);
+            if let Some(dll_name) = &options.dll_name {
+                generator = generator.library_name(dll_name.to_owned());
+            }
+            print!("{}", generator.generate())
+        }
+        EmitTarget::PythonCtypes => {
+            let mut generator = codegen::CtypesGenerator::new(source_file);
+            if let Some(dll_name) = &options.dll_name {
+                generator = generator.library_name(dll_name.to_owned());
+            }
+            print!("{}", generator.generate())
         }
     }
 }
 
-impl EntityVisitor for EnumDeclare {
-    #[inline]
-    fn name(&self) -> Option<&str> {
-        self.enum_name.as_ref().map(|s| s.as_ref())
-    }
-
-    fn set_name(&mut self, new_name: String) {
-        self.enum_name = Some(new_name);
-    }
-
-    #[inline]
-    fn entity_kind(&self) -> EntityKind {
-        EntityKind::EnumDecl
-    }
-
-    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) {
-        assert_eq!(current_entity.get_kind(), self.entity_kind());
-        self.enum_type = current_entity
-            .get_enum_underlying_type()
-            .map(|enum_type| Type::from_clang(&enum_type));
-        self.location = current_entity
-            .get_location()
-            .map(|source_location| SourceLocation::from_clang(&source_location));
-        let children = current_entity.get_children();
-        for child_entity in children.iter() {
-            let name = child_entity.get_name().unwrap();
-            self.constants
-                .push(EnumConstantDeclare::new(name).tap(|enum_constant_declare| {
-                    enum_constant_declare.visit_entity(child_entity, current_entity);
-                }));
+fn write_output_dir(source_file: &SourceFile, output_dir: &Path, options: &Options) {
+    let format = match (options.emit, options.format) {
+        (Some(EmitTarget::RustFfi), _) => output_dir::OutputFormat::RustFfi,
+        (Some(EmitTarget::PythonCtypes), _) => output_dir::OutputFormat::PythonCtypes,
+        (None, OutputFormat::Json) => output_dir::OutputFormat::Json,
+        (None, OutputFormat::Yaml) => output_dir::OutputFormat::Yaml,
+        (None, OutputFormat::Markdown) => output_dir::OutputFormat::Markdown,
+        _ => {
+            eprintln!("--output-dir supports --emit rust-ffi, --emit python-ctypes and --format json, yaml or markdown");
+            exit(1);
         }
+    };
+    let mut writer = DirectoryWriter::new(source_file, format)
+        .split_by(options.split_by)
+        .bitflags(options.bitflags);
+    if let Some(module_prefix) = &options.module_prefix {
+        writer = writer.module_prefix(module_prefix.to_owned());
     }
-}
-
-impl TypeDeclaration for EnumDeclare {
-    #[inline]
-    fn typedef_name(&self) -> Option<&str> {
-        self.typedef_name.as_ref().map(|n| n.as_ref())
+    if let Some(dll_name) = &options.dll_name {
+        writer = writer.library_name(dll_name.to_owned());
     }
-
-    #[inline]
-    fn set_typedef_name(&mut self, new_typedef_name: String) {
-        self.typedef_name = Some(new_typedef_name);
+    if let Err(err) = writer.write(output_dir) {
+        eprintln!("Failed to write {}: {}", output_dir.display(), err);
+        exit(1);
     }
 }
 
-#[derive(Debug)]
-struct FieldDeclare {
-    name: Option<String>,
-    field_type: Option<Type>,
-    location: Option<SourceLocation>,
-}
-
-impl FieldDeclare {
-    fn new(name: Option<String>) -> Self {
-        Self {
-            name,
-            field_type: None,
-            location: None,
-        }
+fn rust_bindings(source_file: &SourceFile, options: &Options) -> String {
+    let mut generator = codegen::RustFfiGenerator::new(source_file).bitflags(options.bitflags);
+    if let Some(dll_name) = &options.dll_name {
+        generator = generator.dll_name(dll_name.to_owned());
     }
+    generator.generate()
 }
 
-impl EntityVisitor for FieldDeclare {
-    #[inline]
-    fn name(&self) -> Option<&str> {
-        self.name.as_ref().map(|n| n.as_ref())
-    }
-
-    fn set_name(&mut self, new_name: String) {
-        self.name = Some(new_name);
-    }
-
-    #[inline]
-    fn entity_kind(&self) -> EntityKind {
-        EntityKind::FieldDecl
-    }
-
-    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) {
-        assert_eq!(current_entity.get_kind(), self.entity_kind());
-        self.field_type = current_entity
-            .get_type()
-            .map(|field_type| Type::from_clang(&field_type));
-        self.location = current_entity
-            .get_location()
-            .map(|source_location| SourceLocation::from_clang(&source_location));
-    }
+/// The bindings for the headers of one directory, nested like the directories are.
+#[derive(Default)]
+struct RustModule {
+    bindings: String,
+    modules: BTreeMap<String, RustModule>,
 }
 
-#[derive(Debug)]
-struct StructDeclare {
-    struct_name: Option<String>,
-    typedef_name: Option<String>,
-    fields: Vec<Box<dyn EntityVisitor>>,
-    location: Option<SourceLocation>,
-}
-
-impl StructDeclare {
-    fn new(struct_name: Option<String>, typedef_name: Option<String>) -> Self {
-        Self {
-            struct_name,
-            typedef_name,
-            fields: Vec::new(),
-            location: None,
+fn print_rust_modules(parent: &RustModule, level: usize) {
+    let prefix_spaces = "    ".repeat(level);
+    for (name, module) in parent.modules.iter() {
+        println!("{}pub mod {} {{", prefix_spaces, name);
+        // Types from the headers of parent directories are used without a path.
+        println!("{}    #[allow(unused_imports)]", prefix_spaces);
+        println!("{}    use super::*;", prefix_spaces);
+        for line in module.bindings.lines() {
+            if line.is_empty() {
+                println!();
+            } else {
+                println!("{}    {}", prefix_spaces, line);
+            }
         }
+        print_rust_modules(module, level + 1);
+        println!("{}}}", prefix_spaces);
     }
 }
 
-impl EntityVisitor for StructDeclare {
-    #[inline]
-    fn name(&self) -> Option<&str> {
-        self.struct_name.as_ref().map(|n| n.as_ref())
-    }
-
-    fn set_name(&mut self, new_name: String) {
-        self.struct_name = Some(new_name);
-    }
-
-    #[inline]
-    fn entity_kind(&self) -> EntityKind {
-        EntityKind::StructDecl
-    }
-
-    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) {
-        assert_eq!(current_entity.get_kind(), self.entity_kind());
-        self.location = current_entity
-            .get_location()
-            .map(|source_location| SourceLocation::from_clang(&source_location));
-        let children = current_entity.get_children();
-        for child_entity in children.iter() {
-            match child_entity.get_kind() {
-                EntityKind::FieldDecl => {
-                    self.fields
-                        .push(Box::new(FieldDeclare::new(child_entity.get_name())).tap(
-                            |field_declare| {
-                                field_declare.visit_entity(child_entity, current_entity);
-                            },
-                        ));
-                }
-                EntityKind::UnionDecl => {
-                    self.fields.push(
-                        Box::new(UnionDeclare::new(child_entity.get_name(), None)).tap(
-                            |union_declare| {
-                                union_declare.visit_entity(child_entity, current_entity);
-                            },
-                        ),
-                    );
-                }
-                _ => panic!("Unexpected entity: {:?}"),
+fn parse_source_file(file_path: &OsString, options: &Options) -> Result<SourceFile, ParseError> {
+    let show_entities = options.show_entities();
+    parse_file_with(
+        Path::new(file_path),
+        options.parse_options.clone(),
+        |entity| {
+            if show_entities {
+                options.tree_printer().print(*entity);
             }
+        },
+    )
+}
+
+/// Parses the input files in order. libclang allows one instance per process, so several files
+/// are parsed in parallel by worker processes, as many at a time as `--jobs` says.
+fn parse_source_files(options: &Options, args: &[OsString]) -> Vec<Result<SourceFile, String>> {
+    if options.jobs > 1 && options.file_paths.len() > 1 {
+        parse_in_workers(options, args)
+    } else {
+        parse_one_by_one(options)
+    }
+}
+
+fn parse_one_by_one(options: &Options) -> Vec<Result<SourceFile, String>> {
+    options
+        .file_paths
+        .iter()
+        .map(|file_path| parse_source_file(file_path, options).map_err(|err| err.to_string()))
+        .collect()
+}
+
+/// Runs this executable again for each input file, with the same arguments plus
+/// `--parse-worker`, and reads the files back from the JSON the workers write. What the workers
+/// print, like the entity tree, is passed on in the order of the input files.
+#[cfg(feature = "serde")]
+fn parse_in_workers(options: &Options, args: &[OsString]) -> Vec<Result<SourceFile, String>> {
+    let current_exe = match std::env::current_exe() {
+        Ok(current_exe) => current_exe,
+        Err(err) => return vec![Err(format!("Failed to start the parse workers: {}", err))],
+    };
+    let file_count = options.file_paths.len();
+    let next_index = AtomicUsize::new(0);
+    let results = Mutex::new((0..file_count).map(|_| None).collect::<Vec<_>>());
+    thread::scope(|scope| {
+        for _ in 0..options.jobs.min(file_count) {
+            scope.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::SeqCst);
+                if index >= file_count {
+                    break;
+                }
+                let result = run_parse_worker(&current_exe, args, index);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .map(|(stdout, result)| {
+            print!("{}", String::from_utf8_lossy(&stdout));
+            result
+        })
+        .collect()
+}
+
+/// Without `serde` the workers cannot hand the parsed files back, so the files are parsed here
+/// one by one.
+#[cfg(not(feature = "serde"))]
+fn parse_in_workers(options: &Options, _: &[OsString]) -> Vec<Result<SourceFile, String>> {
+    parse_one_by_one(options)
+}
+
+/// What the worker for the file at `index` printed, and the file it parsed or its error message.
+#[cfg(feature = "serde")]
+fn run_parse_worker(
+    current_exe: &Path,
+    args: &[OsString],
+    index: usize,
+) -> (Vec<u8>, Result<SourceFile, String>) {
+    let worker_output = std::env::temp_dir().join(format!(
+        "test-clang-rust-{}-{}.json",
+        std::process::id(),
+        index
+    ));
+    let output = match Command::new(current_exe)
+        .args(args)
+        .arg("--parse-worker")
+        .arg(index.to_string())
+        .arg("--worker-output")
+        .arg(&worker_output)
+        .output()
+    {
+        Ok(output) => output,
+        Err(err) => {
+            return (
+                Vec::new(),
+                Err(format!("Failed to start a parse worker: {}", err)),
+            )
+        }
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return (output.stdout, Err(stderr.trim_end().to_owned()));
+    }
+    let source_file = fs::read(&worker_output)
+        .map_err(|err| err.to_string())
+        .and_then(|json| serde_json::from_slice(&json).map_err(|err| err.to_string()))
+        .map_err(|err| format!("Failed to read {}: {}", worker_output.display(), err));
+    let _ = fs::remove_file(&worker_output);
+    (output.stdout, source_file)
+}
+
+/// The worker side of `parse_in_workers`: parses the input file at `index` and writes it as
+/// JSON to `worker_output`.
+#[cfg(feature = "serde")]
+fn parse_worker(options: &Options, index: usize, worker_output: &Path) {
+    let file_path = options.file_paths.get(index).unwrap_or_else(|| {
+        eprintln!("No input file for --parse-worker {}", index);
+        exit(1);
+    });
+    let source_file = parse_source_file(file_path, options).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(1);
+    });
+    let json = serde_json::to_vec(&source_file).unwrap();
+    if let Err(err) = fs::write(worker_output, json) {
+        eprintln!("Failed to write {}: {}", worker_output.display(), err);
+        exit(1);
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn parse_worker(_: &Options, _: usize, _: &Path) {
+    eprintln!("Parse workers require the `serde` feature");
+    exit(1);
+}
+
+fn print_output(mut source_file: SourceFile, options: &Options) {
+    if options.check_completeness {
+        for unresolved_type_ref in CompletenessChecker::check(&source_file) {
+            eprintln!("warning: {}", unresolved_type_ref);
         }
     }
-}
-
-impl TypeDeclaration for StructDeclare {
-    #[inline]
-    fn typedef_name(&self) -> Option<&str> {
-        self.typedef_name.as_ref().map(|n| n.as_ref())
+    if options.validate {
+        for validation_warning in source_file.validate() {
+            eprintln!("warning: {}", validation_warning);
+        }
     }
-
-    #[inline]
-    fn set_typedef_name(&mut self, new_typedef_name: String) {
-        self.typedef_name = Some(new_typedef_name);
+    // With `--tree` the entity tree printed while parsing is the whole output.
+    if options.tree {
+        return;
     }
-}
-
-#[derive(Debug)]
-struct UnionDeclare {
-    union_name: Option<String>,
-    typedef_name: Option<String>,
-    fields: Vec<Box<dyn EntityVisitor>>,
-    location: Option<SourceLocation>,
-}
-
-impl UnionDeclare {
-    fn new(union_name: Option<String>, typedef_name: Option<String>) -> Self {
-        Self {
-            union_name,
-            typedef_name,
-            fields: Vec::new(),
-            location: None,
-        }
+    if options.only_extern {
+        source_file.retain_extern_functions();
     }
-}
-
-impl EntityVisitor for UnionDeclare {
-    #[inline]
-    fn name(&self) -> Option<&str> {
-        self.union_name.as_ref().map(|n| n.as_ref())
+    if options.skip_forward_declarations {
+        source_file.skip_forward_declarations();
     }
-
-    #[inline]
-    fn set_name(&mut self, new_name: String) {
-        self.union_name = Some(new_name);
+    if options.declarations_only {
+        source_file.retain_function_declarations();
     }
-
-    #[inline]
-    fn entity_kind(&self) -> EntityKind {
-        EntityKind::UnionDecl
+    if options.skip_inline {
+        source_file.skip_inline_functions();
     }
-
-    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) {
-        assert_eq!(current_entity.get_kind(), self.entity_kind());
-        self.location = current_entity
-            .get_location()
-            .map(|source_location| SourceLocation::from_clang(&source_location));
-        let children = current_entity.get_children();
-        for child_entity in children.iter() {
-            self.fields
-                .push(
-                    Box::new(FieldDeclare::new(child_entity.get_name())).tap(|field_declare| {
-                        field_declare.visit_entity(child_entity, current_entity);
-                    }),
-                );
-        }
+    if let Some(function_prefix) = &options.function_prefix {
+        source_file.retain_functions_by_name_prefix(function_prefix);
     }
-}
-
-impl TypeDeclaration for UnionDeclare {
-    #[inline]
-    fn typedef_name(&self) -> Option<&str> {
-        self.typedef_name.as_ref().map(|n| n.as_ref())
+    if let Some(type_prefix) = &options.type_prefix {
+        source_file.retain_types_by_name_prefix(type_prefix);
     }
-
-    #[inline]
-    fn set_typedef_name(&mut self, new_typedef_name: String) {
-        self.typedef_name = Some(new_typedef_name);
+    if let Some(export_list) = &options.export_list {
+        source_file.retain_exported(export_list);
     }
-}
-
-#[derive(Debug)]
-struct FunctionDeclare {
-    function_name: String,
-    return_type: Option<Type>,
-    parameters: Vec<ParameterDeclare>,
-    location: Option<SourceLocation>,
-}
-
-impl FunctionDeclare {
-    fn new(function_name: String) -> Self {
-        Self {
-            function_name,
-            return_type: None,
-            parameters: Vec::new(),
-            location: None,
+    if let Some(query) = &options.query {
+        let function_declares = match query {
+            Query::ReturnType(type_name) => source_file.functions_returning_type(type_name),
+            Query::ParamType(type_name) => source_file.functions_taking_type(type_name),
+        };
+        for function_declare in function_declares {
+            println!("{}", function_declare.signature_string());
+        }
+    } else if options.show_layout {
+        print_layout(&source_file);
+    } else if options.layout_report {
+        print!("{}", source_file.layout_report());
+    } else if options.show_includes {
+        print_includes(&source_file);
+    } else if options.show_stats {
+        println!("{}", source_file.statistics());
+    } else if let Some(output_dir) = &options.output_dir {
+        write_output_dir(&source_file, output_dir, options);
+    } else {
+        match options.emit {
+            Some(target) => emit_source_file(&source_file, target, options),
+            None => print_source_file(&source_file, options.format),
         }
     }
 }
 
-impl EntityVisitor for FunctionDeclare {
-    #[inline]
-    fn name(&self) -> Option<&str> {
-        Some(self.function_name.as_str())
-    }
-
-    #[inline]
-    fn set_name(&mut self, new_function_name: String) {
-        self.function_name = new_function_name;
-    }
+fn canonical_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
 
-    #[inline]
-    fn entity_kind(&self) -> EntityKind {
-        EntityKind::FunctionDecl
+fn add_include_edges(include_graph: &mut HashMap<PathBuf, Vec<PathBuf>>, source_file: &SourceFile) {
+    for (path, included_paths) in source_file.include_graph() {
+        include_graph.insert(
+            canonical_path(Path::new(&path)),
+            included_paths
+                .iter()
+                .map(|included_path| canonical_path(Path::new(included_path)))
+                .collect(),
+        );
     }
+}
 
-    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) {
-        assert_eq!(current_entity.get_kind(), self.entity_kind());
-        self.location = current_entity
-            .get_location()
-            .map(|source_location| SourceLocation::from_clang(&source_location));
-        self.return_type = current_entity
-            .get_result_type()
-            .map(|return_type| Type::from_clang(&return_type));
-        if let Some(arguments) = current_entity.get_arguments() {
-            for argument in arguments.iter() {
-                self.parameters
-                    .push(ParameterDeclare::new(argument.get_name().unwrap()).tap(
-                        |param_declare| {
-                            param_declare.visit_entity(argument, current_entity);
-                        },
-                    ));
+/// The file itself plus every header it reaches through the include graph.
+fn include_dependencies(
+    file_path: &OsString,
+    include_graph: &HashMap<PathBuf, Vec<PathBuf>>,
+) -> HashSet<PathBuf> {
+    let mut dependencies = HashSet::new();
+    let mut pending = vec![canonical_path(Path::new(file_path))];
+    while let Some(path) = pending.pop() {
+        if let Some(included_paths) = include_graph.get(&path) {
+            pending.extend(included_paths.iter().cloned());
+        }
+        dependencies.insert(path);
+    }
+    dependencies
+}
+
+fn format_time_of_day(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+        % 86400;
+    format!(
+        "{:02}:{:02}:{:02} UTC",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn watch(
+    options: &Options,
+    mut include_graph: HashMap<PathBuf, Vec<PathBuf>>,
+) -> notify::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    let mut watched_directories = HashSet::new();
+    loop {
+        // Watch directories rather than files, editors often replace a file on save.
+        for file_path in options.file_paths.iter() {
+            for dependency in include_dependencies(file_path, &include_graph) {
+                if let Some(directory) = dependency.parent() {
+                    if watched_directories.insert(directory.to_owned()) {
+                        watcher.watch(directory, RecursiveMode::NonRecursive)?;
+                    }
+                }
             }
         }
-    }
-}
-
-#[derive(Debug)]
-struct ParameterDeclare {
-    name: String,
-    parameter_type: Option<Type>,
-    location: Option<SourceLocation>,
-}
-
-impl ParameterDeclare {
-    fn new(name: String) -> Self {
-        Self {
-            name,
-            parameter_type: None,
-            location: None,
+        let mut events = match receiver.recv() {
+            Ok(event) => vec![event],
+            Err(_) => return Ok(()),
+        };
+        // A single save usually produces a burst of events.
+        while let Ok(event) = receiver.recv_timeout(Duration::from_millis(100)) {
+            events.push(event);
+        }
+        let mut changed_paths = HashSet::new();
+        for event in events {
+            let event = event?;
+            if let EventKind::Create(_) | EventKind::Modify(_) = event.kind {
+                changed_paths.extend(event.paths.iter().map(|path| canonical_path(path)));
+            }
+        }
+        for file_path in options.file_paths.iter() {
+            if include_dependencies(file_path, &include_graph).is_disjoint(&changed_paths) {
+                continue;
+            }
+            println!(
+                "=== reparsed {} at {} ===",
+                Path::new(file_path).display(),
+                format_time_of_day(SystemTime::now())
+            );
+            match parse_source_file(file_path, options) {
+                Ok(source_file) => {
+                    add_include_edges(&mut include_graph, &source_file);
+                    print_output(source_file, options);
+                }
+                Err(err) => eprintln!("{}", err),
+            }
         }
     }
 }
 
-impl EntityVisitor for ParameterDeclare {
-    #[inline]
-    fn name(&self) -> Option<&str> {
-        Some(self.name.as_str())
-    }
-
-    fn set_name(&mut self, new_name: String) {
-        self.name = new_name;
+fn diff_files(options: &Options) {
+    let parse = |file_path: &OsString| {
+        parse_file(Path::new(file_path), options.parse_options.clone()).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(1);
+        })
+    };
+    let old_source_file = parse(&options.file_paths[0]);
+    let new_source_file = parse(&options.file_paths[1]);
+    let api_diff = old_source_file.api_diff(&new_source_file);
+    print!("{}", api_diff);
+    if api_diff.is_breaking() {
+        exit(1);
     }
+}
 
-    #[inline]
-    fn entity_kind(&self) -> EntityKind {
-        EntityKind::ParmDecl
+fn main() {
+    let args = args_os().skip(1).collect::<Vec<_>>();
+    let options = Options::from_args(args.iter().cloned()).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(1);
+    });
+    if options.diff {
+        diff_files(&options);
+        return;
+    }
+    if let Some((index, worker_output)) = &options.parse_worker {
+        parse_worker(&options, *index, worker_output);
+        return;
+    }
+    let mut errors = Vec::new();
+    let mut include_graph = HashMap::new();
+    let mut merged_source_file: Option<SourceFile> = None;
+    for parse_result in parse_source_files(&options, &args) {
+        let source_file = match parse_result {
+            Ok(source_file) => source_file,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
+        add_include_edges(&mut include_graph, &source_file);
+        merged_source_file = Some(match merged_source_file.take() {
+            Some(merged_source_file) => {
+                merged_source_file.merge(source_file).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    exit(1);
+                })
+            }
+            None => source_file,
+        });
     }
-
-    fn visit_entity(&mut self, current_entity: &Entity, _: &Entity) {
-        assert_eq!(current_entity.get_kind(), self.entity_kind());
-        self.parameter_type = current_entity
-            .get_type()
-            .map(|parameter_type| Type::from_clang(&parameter_type));
-        self.location = current_entity
-            .get_location()
-            .map(|source_location| SourceLocation::from_clang(&source_location));
+    if let Some(source_file) = merged_source_file {
+        print_output(source_file, &options);
     }
-}
-
-fn show_entity(entity: Entity, level: usize) {
-    if entity.is_in_main_file() {
-        let prefix_spaces = iter::repeat(" ").take(level * 4).collect::<String>();
-        println!("{}{:?}", prefix_spaces, entity);
-        for child in entity.get_children() {
-            show_entity(child, level + 1);
-        }
+    for err in errors.iter() {
+        eprintln!("{}", err);
     }
-}
-
-fn main() {
-    let cl = Clang::new().unwrap();
-    let idx = Index::new(&cl, true, false);
-    for file_path in args_os().skip(1) {
-        let tu = idx.parser(file_path).parse().unwrap();
-        let entity = tu.get_entity();
-        show_entity(entity, 0);
-
-        if let Some(name) = entity.get_name() {
-            let mut source_file = SourceFile::new(name);
-            source_file.visit_entity(&entity, &entity);
-            println!("****** source_file: {:#?}", source_file);
+    if options.watch {
+        if let Err(err) = watch(&options, include_graph) {
+            eprintln!("{}", err);
+            exit(1);
         }
+    } else if !errors.is_empty() {
+        exit(1);
     }
 }
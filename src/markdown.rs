@@ -0,0 +1,194 @@
+use crate::{
+    printer::TypePrinter, EntityVisitor, FunctionParameter, MemberDeclare, SourceFile, Type,
+    TypeDeclaration, TypeDeclare,
+};
+use std::{collections::HashMap, fmt::Write};
+
+/// Renders the functions of a `SourceFile` as a GitHub Markdown table for API reference docs.
+/// Types that are declared in the file get a section of their own that the table links to.
+pub struct MarkdownTableEmitter<'a> {
+    source_file: &'a SourceFile,
+    /// Maps type and typedef names to the heading of the section describing the type.
+    section_names: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> MarkdownTableEmitter<'a> {
+    pub fn new(source_file: &'a SourceFile) -> Self {
+        let mut section_names = HashMap::new();
+        for type_declare in source_file
+            .type_declares
+            .iter()
+            .filter(|type_declare| type_declare.is_definition())
+        {
+            if let Some(section_name) = type_declare.name().or_else(|| type_declare.typedef_name())
+            {
+                section_names.insert(section_name, section_name);
+                if let Some(typedef_name) = type_declare.typedef_name() {
+                    section_names.insert(typedef_name, section_name);
+                }
+            }
+        }
+        Self {
+            source_file,
+            section_names,
+        }
+    }
+
+    pub fn emit(&self) -> String {
+        let mut output = String::new();
+        writeln!(output, "| Function | Return type | Parameters |").unwrap();
+        writeln!(output, "| --- | --- | --- |").unwrap();
+        for function_declare in self.source_file.function_declares.iter() {
+            let return_type = function_declare
+                .return_type
+                .as_ref()
+                .map(|return_type| {
+                    self.code_span(return_type, TypePrinter::new(return_type).print())
+                })
+                .unwrap_or_default();
+            let parameters = function_declare
+                .parameters
+                .iter()
+                .map(|parameter| match parameter {
+                    FunctionParameter::Named(parameter_declare) => {
+                        match &parameter_declare.parameter_type {
+                            Some(parameter_type) => {
+                                let type_printer = TypePrinter::new(parameter_type);
+                                self.code_span(
+                                    parameter_type,
+                                    match &parameter_declare.name {
+                                        Some(name) => type_printer.print_declaration(name),
+                                        None => type_printer.print(),
+                                    },
+                                )
+                            }
+                            None => {
+                                format!("`{}`", parameter_declare.name.as_deref().unwrap_or("?"))
+                            }
+                        }
+                    }
+                    FunctionParameter::Variadic(_) => "`...`".to_owned(),
+                })
+                .collect::<Vec<_>>();
+            let parameters = if parameters.is_empty() {
+                "`void`".to_owned()
+            } else {
+                parameters.join(", ")
+            };
+            writeln!(
+                output,
+                "| `{}` | {} | {} |",
+                function_declare.function_name, return_type, parameters
+            )
+            .unwrap();
+        }
+
+        let type_declares = self
+            .source_file
+            .type_declares
+            .iter()
+            .filter(|type_declare| type_declare.is_definition())
+            .collect::<Vec<_>>();
+        if !type_declares.is_empty() {
+            writeln!(output).unwrap();
+            writeln!(output, "## Types").unwrap();
+        }
+        for type_declare in type_declares {
+            self.write_type_declare(&mut output, type_declare);
+        }
+        output
+    }
+
+    fn write_type_declare(&self, output: &mut String, type_declare: &TypeDeclare) {
+        let name = match type_declare.name().or_else(|| type_declare.typedef_name()) {
+            Some(name) => name,
+            None => return,
+        };
+        writeln!(output).unwrap();
+        writeln!(output, "### {}", name).unwrap();
+        writeln!(output).unwrap();
+        let members = match type_declare {
+            TypeDeclare::Enum(enum_declare) => {
+                for constant in enum_declare.constants.iter() {
+                    match &constant.constant_value {
+                        Some(value) => {
+                            writeln!(output, "- `{} = {}`", constant.name, value.signed).unwrap()
+                        }
+                        None => writeln!(output, "- `{}`", constant.name).unwrap(),
+                    }
+                }
+                return;
+            }
+            TypeDeclare::Struct(struct_declare) => &struct_declare.fields,
+            TypeDeclare::Union(union_declare) => &union_declare.fields,
+            TypeDeclare::Class(class_declare) => &class_declare.fields,
+            TypeDeclare::Typedef(typedef_declare) => {
+                writeln!(
+                    output,
+                    "Alias of {}",
+                    self.code_span(
+                        &typedef_declare.underlying_type,
+                        TypePrinter::new(&typedef_declare.underlying_type).print()
+                    )
+                )
+                .unwrap();
+                return;
+            }
+            TypeDeclare::FunctionPointer(function_pointer_declare) => {
+                writeln!(
+                    output,
+                    "Function pointer `{}`",
+                    function_pointer_declare
+                        .declaration_string()
+                        .replace('|', "\\|")
+                )
+                .unwrap();
+                return;
+            }
+        };
+        for member in members.iter() {
+            if let MemberDeclare::Field(field_declare) = member {
+                if let (Some(field_name), Some(field_type)) =
+                    (&field_declare.name, &field_declare.field_type)
+                {
+                    writeln!(
+                        output,
+                        "- {}",
+                        self.code_span(
+                            field_type,
+                            TypePrinter::new(field_type).print_declaration(field_name)
+                        )
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+
+    /// Wraps `text` in backticks, linking it to the section of the type `t` refers to if there
+    /// is one.
+    fn code_span(&self, t: &Type, text: String) -> String {
+        let text = format!("`{}`", text.replace('|', "\\|"));
+        match t
+            .referenced_type()
+            .and_then(|referenced_type| self.section_names.get(referenced_type.tagless_name()))
+        {
+            Some(section_name) => format!("[{}](#{})", text, anchor(section_name)),
+            None => text,
+        }
+    }
+}
+
+/// The anchor GitHub generates for a `### name` heading.
+fn anchor(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-' || *c == ' ')
+        .map(|c| {
+            if c == ' ' {
+                '-'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,234 @@
+use crate::{
+    Attribute, FunctionDeclare, FunctionParameter, ParameterDeclare, ParameterDirection, Type,
+};
+use clang::TypeKind;
+
+/// Naming conventions that tell the functions creating objects apart from the ones destroying
+/// them, e.g. `create_buffer` and `buffer_free`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnershipHints {
+    pub alloc_prefixes: Vec<String>,
+    pub alloc_suffixes: Vec<String>,
+    pub free_prefixes: Vec<String>,
+    pub free_suffixes: Vec<String>,
+}
+
+impl Default for OwnershipHints {
+    fn default() -> Self {
+        let strings = |patterns: &[&str]| patterns.iter().map(|&p| p.to_owned()).collect();
+        Self {
+            alloc_prefixes: strings(&["alloc_", "create_", "new_"]),
+            alloc_suffixes: strings(&["_alloc", "_create", "_new"]),
+            free_prefixes: strings(&["free_", "destroy_", "release_"]),
+            free_suffixes: strings(&["_free", "_destroy", "_release"]),
+        }
+    }
+}
+
+impl OwnershipHints {
+    /// No naming conventions at all, so that only signatures and attributes count.
+    pub fn none() -> Self {
+        Self {
+            alloc_prefixes: Vec::new(),
+            alloc_suffixes: Vec::new(),
+            free_prefixes: Vec::new(),
+            free_suffixes: Vec::new(),
+        }
+    }
+
+    pub fn is_alloc_name(&self, name: &str) -> bool {
+        Self::matches(name, &self.alloc_prefixes, &self.alloc_suffixes)
+    }
+
+    pub fn is_free_name(&self, name: &str) -> bool {
+        Self::matches(name, &self.free_prefixes, &self.free_suffixes)
+    }
+
+    fn matches(name: &str, prefixes: &[String], suffixes: &[String]) -> bool {
+        prefixes
+            .iter()
+            .any(|prefix| name.starts_with(prefix.as_str()))
+            || suffixes
+                .iter()
+                .any(|suffix| name.ends_with(suffix.as_str()))
+    }
+}
+
+/// What a function does with the memory passed to or returned from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionOwnership {
+    /// Returns a new object the caller owns, either directly or through an `T **` parameter.
+    Allocator,
+    /// Takes ownership of the object its first pointer parameter points to and frees it.
+    Deallocator,
+    /// Borrows a pointer parameter mutably and may write through it.
+    Transformer,
+    /// Only reads through its pointer parameters, if it has any.
+    Observer,
+}
+
+/// Guesses the ownership semantics of functions from their names and signatures. C has no way
+/// to spell ownership out, so this is a heuristic: an allocator has to be named like one and
+/// return a pointer, or be declared to return one its caller owns, see
+/// `FunctionDeclare::caller_owns_return`. A deallocator has to be named like one and take a
+/// pointer.
+pub struct MemoryModel;
+
+impl MemoryModel {
+    pub fn classify(func: &FunctionDeclare, names: &OwnershipHints) -> FunctionOwnership {
+        let name = func.function_name.as_str();
+        let returns_pointer = func.return_type.as_ref().and_then(data_pointer).is_some();
+        if func.caller_owns_return == Some(true)
+            || names.is_alloc_name(name)
+                && (returns_pointer || Self::out_pointer_parameter(func).is_some())
+        {
+            FunctionOwnership::Allocator
+        } else if names.is_free_name(name) && Self::owned_parameter(func).is_some() {
+            FunctionOwnership::Deallocator
+        } else if !Self::mutable_parameters(func).is_empty() {
+            FunctionOwnership::Transformer
+        } else {
+            FunctionOwnership::Observer
+        }
+    }
+
+    /// The deallocator among `functions` that frees what the allocator `func` returns, found by
+    /// the type of its owned parameter.
+    pub fn matching_deallocator<'a>(
+        func: &FunctionDeclare,
+        functions: &'a [FunctionDeclare],
+        names: &OwnershipHints,
+    ) -> Option<&'a FunctionDeclare> {
+        let allocated_type = func
+            .return_type
+            .as_ref()
+            .and_then(data_pointer)
+            .or_else(|| {
+                Self::out_pointer_parameter(func)
+                    .and_then(|parameter_declare| parameter_declare.parameter_type.as_ref())
+                    .and_then(data_pointer)
+                    .and_then(|pointer_type| pointer_type.pointee_type.as_deref())
+            })
+            .and_then(pointee_name)?;
+        functions.iter().find(|function_declare| {
+            Self::classify(function_declare, names) == FunctionOwnership::Deallocator
+                && Self::owned_parameter(function_declare)
+                    .and_then(|parameter_declare| parameter_declare.parameter_type.as_ref())
+                    .and_then(pointee_name)
+                    == Some(allocated_type)
+        })
+    }
+
+    /// The parameter a deallocator takes ownership of, its first data pointer.
+    pub fn owned_parameter(func: &FunctionDeclare) -> Option<&ParameterDeclare> {
+        Self::pointer_parameters(func).next()
+    }
+
+    /// The `T **` parameter an allocator returns the new object through.
+    pub fn out_pointer_parameter(func: &FunctionDeclare) -> Option<&ParameterDeclare> {
+        Self::pointer_parameters(func).find(|parameter_declare| {
+            parameter_declare
+                .parameter_type
+                .as_ref()
+                .and_then(data_pointer)
+                .and_then(|pointer_type| pointer_type.pointee_type.as_deref())
+                .filter(|pointee_type| !pointee_type.is_const)
+                .and_then(data_pointer)
+                .is_some()
+        })
+    }
+
+    /// The pointer parameters the function may write through: pointers to non-const data that
+    /// are not annotated `_In_`.
+    pub fn mutable_parameters(func: &FunctionDeclare) -> Vec<&ParameterDeclare> {
+        Self::pointer_parameters(func)
+            .filter(|parameter_declare| match &parameter_declare.direction {
+                Some(ParameterDirection::Out) | Some(ParameterDirection::InOut) => true,
+                Some(ParameterDirection::In) | Some(ParameterDirection::InReads { .. }) => false,
+                None => parameter_declare
+                    .parameter_type
+                    .as_ref()
+                    .and_then(data_pointer)
+                    .and_then(|pointer_type| pointer_type.pointee_type.as_deref())
+                    .map(|pointee_type| !pointee_type.is_const)
+                    .unwrap_or(false),
+            })
+            .collect()
+    }
+
+    /// The named parameters that point to data, function pointers are left out.
+    pub fn pointer_parameters(func: &FunctionDeclare) -> impl Iterator<Item = &ParameterDeclare> {
+        func.parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                FunctionParameter::Named(parameter_declare) => Some(parameter_declare),
+                FunctionParameter::Variadic(_) => None,
+            })
+            .filter(|parameter_declare| {
+                parameter_declare
+                    .parameter_type
+                    .as_ref()
+                    .and_then(data_pointer)
+                    .is_some()
+            })
+    }
+}
+
+impl FunctionDeclare {
+    /// Whether the caller owns the pointer the function returns and has to free it: the
+    /// function returns a pointer to data and is either named like an allocator or marked
+    /// `warn_unused_result`, which allocation functions commonly are.
+    pub fn return_type_is_caller_owns(&self) -> bool {
+        self.guess_caller_owns_return(&OwnershipHints::default()) == Some(true)
+    }
+
+    /// `Some(false)` when the function does not return a pointer to data, `None` when it does
+    /// but neither its name nor its attributes say who owns it, or the return type is unknown.
+    pub(crate) fn guess_caller_owns_return(&self, names: &OwnershipHints) -> Option<bool> {
+        if data_pointer(self.return_type.as_ref()?).is_none() {
+            Some(false)
+        } else if names.is_alloc_name(&self.function_name)
+            || self.attributes.contains(&Attribute::WarnUnusedResult)
+        {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+/// `t` itself, or what it is a typedef of, if that is a pointer to anything but a function.
+fn data_pointer(t: &Type) -> Option<&Type> {
+    let pointer_type = if t.is_pointer() {
+        t
+    } else {
+        t.canonical_type.as_deref().filter(|t| t.is_pointer())?
+    };
+    let is_function = pointer_type
+        .pointee_type
+        .as_deref()
+        .map(|pointee_type| {
+            let pointee_type = pointee_type
+                .canonical_type
+                .as_deref()
+                .unwrap_or(pointee_type);
+            matches!(
+                pointee_type.type_kind,
+                TypeKind::FunctionPrototype | TypeKind::FunctionNoPrototype
+            )
+        })
+        .unwrap_or(false);
+    Some(pointer_type).filter(|_| !is_function)
+}
+
+/// The canonical name of what the pointer `t` points to, without its `const`.
+fn pointee_name(t: &Type) -> Option<&str> {
+    let pointee_type = data_pointer(t)?.pointee_type.as_deref()?;
+    let name = pointee_type
+        .canonical_type
+        .as_deref()
+        .unwrap_or(pointee_type)
+        .type_name
+        .as_str();
+    Some(name.strip_prefix("const ").unwrap_or(name))
+}
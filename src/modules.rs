@@ -0,0 +1,158 @@
+use crate::{SourceFile, SourceLocation};
+use std::{
+    collections::HashMap,
+    path::{Component, Path},
+};
+
+impl SourceFile {
+    /// Splits the declarations by the directory of the header declaring them, so that each
+    /// directory can become a module of its own. Keys are directory paths joined with `/`,
+    /// declarations without a location or from a header outside of any directory go under `""`,
+    /// as does the include guard of the parsed file.
+    pub fn group_by_module(&self) -> HashMap<String, SourceFile> {
+        self.group_by_module_with_prefix("")
+    }
+
+    /// Like `group_by_module`, with `prefix` stripped from header paths first, so that with a
+    /// prefix of `/usr/include` the declarations of `/usr/include/openssl/evp.h` go under
+    /// `openssl`.
+    pub fn group_by_module_with_prefix(&self, prefix: &str) -> HashMap<String, SourceFile> {
+        self.group_by_path(|path| {
+            let path = Path::new(path);
+            let path = path.strip_prefix(prefix).unwrap_or(path);
+            joined_components(path.parent().unwrap_or_else(|| Path::new("")))
+        })
+    }
+
+    /// Splits the declarations by the header declaring them. Keys are the
+    /// header paths without `prefix` and the extension, so `/usr/include/openssl/evp.h` goes
+    /// under `openssl/evp` with a prefix of `/usr/include`.
+    pub fn group_by_file_with_prefix(&self, prefix: &str) -> HashMap<String, SourceFile> {
+        self.group_by_path(|path| {
+            let path = Path::new(path);
+            let path = path.strip_prefix(prefix).unwrap_or(path);
+            joined_components(&path.with_extension(""))
+        })
+    }
+
+    fn group_by_path(&self, group_key: impl Fn(&str) -> String) -> HashMap<String, SourceFile> {
+        let mut groups: HashMap<String, SourceFile> = HashMap::new();
+        let group_key = |location: Option<&SourceLocation>| {
+            location
+                .map(|location| group_key(&location.path))
+                .unwrap_or_default()
+        };
+        for type_declare in self.type_declares.iter() {
+            self.group(&mut groups, group_key(type_declare.location()))
+                .type_declares
+                .push(type_declare.clone());
+        }
+        for forward_declaration in self.forward_declarations.iter() {
+            self.group(
+                &mut groups,
+                group_key(forward_declaration.location.as_ref()),
+            )
+            .forward_declarations
+            .push(forward_declaration.clone());
+        }
+        for function_declare in self.function_declares.iter() {
+            self.group(&mut groups, group_key(function_declare.location.as_ref()))
+                .function_declares
+                .push(function_declare.clone());
+        }
+        for function_template in self.function_templates.iter() {
+            let location = function_template.function.location.as_ref();
+            self.group(&mut groups, group_key(location))
+                .function_templates
+                .push(function_template.clone());
+        }
+        for class_template in self.class_templates.iter() {
+            self.group(
+                &mut groups,
+                group_key(class_template.class.location.as_ref()),
+            )
+            .class_templates
+            .push(class_template.clone());
+        }
+        for variable_declare in self.variable_declares.iter() {
+            self.group(&mut groups, group_key(variable_declare.location.as_ref()))
+                .variable_declares
+                .push(variable_declare.clone());
+        }
+        for constant in self.constants.iter() {
+            self.group(&mut groups, group_key(constant.location.as_ref()))
+                .constants
+                .push(constant.clone());
+        }
+        for constexpr_declare in self.constexpr_declares.iter() {
+            self.group(&mut groups, group_key(constexpr_declare.location.as_ref()))
+                .constexpr_declares
+                .push(constexpr_declare.clone());
+        }
+        for macro_definition in self.macro_definitions.iter() {
+            self.group(&mut groups, group_key(macro_definition.location.as_ref()))
+                .macro_definitions
+                .push(macro_definition.clone());
+        }
+        for macro_function in self.macro_functions.iter() {
+            self.group(&mut groups, group_key(macro_function.location.as_ref()))
+                .macro_functions
+                .push(macro_function.clone());
+        }
+        for macro_expansion in self.macro_expansions.iter() {
+            self.group(&mut groups, group_key(macro_expansion.location.as_ref()))
+                .macro_expansions
+                .push(macro_expansion.clone());
+        }
+        for include_directive in self.include_directives.iter() {
+            self.group(&mut groups, group_key(include_directive.location.as_ref()))
+                .include_directives
+                .push(include_directive.clone());
+        }
+        for namespace_declare in self.namespace_declares.iter() {
+            self.group(&mut groups, group_key(namespace_declare.location.as_ref()))
+                .namespace_declares
+                .push(namespace_declare.clone());
+        }
+        if let Some(include_guard) = &self.include_guard {
+            self.group(&mut groups, String::new()).include_guard = Some(include_guard.clone());
+        }
+        groups
+    }
+
+    fn group<'a>(
+        &self,
+        groups: &'a mut HashMap<String, SourceFile>,
+        key: String,
+    ) -> &'a mut SourceFile {
+        groups.entry(key).or_insert_with(|| SourceFile {
+            target_triple: self.target_triple.clone(),
+            ..SourceFile::new(self.path.to_owned())
+        })
+    }
+}
+
+/// Makes a path like the keys of `group_by_module` usable as a module name, `net/my-lib`
+/// becomes `net_my_lib`.
+pub fn module_name(path: &str) -> String {
+    let name = path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// The normal components of `path` joined with `/`, whatever the platform separator is.
+fn joined_components(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,196 @@
+use crate::{codegen, markdown::MarkdownTableEmitter, modules::module_name, SourceFile};
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+/// The formats `SourceFile::write_to_dir` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    RustFfi,
+    Json,
+    Yaml,
+    PythonCtypes,
+    Markdown,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::RustFfi => "rs",
+            OutputFormat::Json => "json",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::PythonCtypes => "py",
+            OutputFormat::Markdown => "md",
+        }
+    }
+
+    /// The file that makes the generated files one package, re-exporting all of them.
+    fn index_file_name(self) -> Option<&'static str> {
+        match self {
+            OutputFormat::RustFfi => Some("mod.rs"),
+            OutputFormat::PythonCtypes => Some("__init__.py"),
+            OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Markdown => None,
+        }
+    }
+}
+
+/// How the declarations are spread over the written files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitBy {
+    /// A file per directory of headers, see `SourceFile::group_by_module`.
+    Module,
+    /// A file per header.
+    File,
+    /// A single file named after the parsed file.
+    None,
+}
+
+impl SplitBy {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "module" => Some(SplitBy::Module),
+            "file" => Some(SplitBy::File),
+            "none" => Some(SplitBy::None),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the declarations of a `SourceFile` to a directory, one file per group of them.
+pub struct DirectoryWriter<'a> {
+    source_file: &'a SourceFile,
+    format: OutputFormat,
+    split_by: SplitBy,
+    module_prefix: String,
+    library_name: Option<String>,
+    use_bitflags: bool,
+}
+
+impl<'a> DirectoryWriter<'a> {
+    pub fn new(source_file: &'a SourceFile, format: OutputFormat) -> Self {
+        Self {
+            source_file,
+            format,
+            split_by: SplitBy::Module,
+            module_prefix: String::new(),
+            library_name: None,
+            use_bitflags: false,
+        }
+    }
+
+    pub fn split_by(mut self, split_by: SplitBy) -> Self {
+        self.split_by = split_by;
+        self
+    }
+
+    /// Stripped from header paths before grouping, so that files are not named after the
+    /// directories every header is in.
+    pub fn module_prefix(mut self, module_prefix: String) -> Self {
+        self.module_prefix = module_prefix;
+        self
+    }
+
+    /// The library Rust functions are linked against and Python ones loaded from.
+    pub fn library_name(mut self, library_name: String) -> Self {
+        self.library_name = Some(library_name);
+        self
+    }
+
+    /// See `RustFfiGenerator::bitflags`.
+    pub fn bitflags(mut self, use_bitflags: bool) -> Self {
+        self.use_bitflags = use_bitflags;
+        self
+    }
+
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        let file_stem = Path::new(&self.source_file.path)
+            .file_stem()
+            .map(|file_stem| file_stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut groups = match self.split_by {
+            SplitBy::Module => self
+                .source_file
+                .group_by_module_with_prefix(&self.module_prefix)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            SplitBy::File => self
+                .source_file
+                .group_by_file_with_prefix(&self.module_prefix)
+                .into_iter()
+                .collect(),
+            SplitBy::None => vec![(String::new(), self.source_file.clone())],
+        };
+        groups.sort_by(|(key, _), (other_key, _)| key.cmp(other_key));
+
+        fs::create_dir_all(dir)?;
+        let mut module_names = Vec::new();
+        for (key, source_file) in groups.iter() {
+            // Declarations outside of any group are named after the parsed file.
+            let module_name = match key.as_str() {
+                "" => module_name(&file_stem),
+                key => module_name(key),
+            };
+            let file_name = format!("{}.{}", module_name, self.format.extension());
+            fs::write(dir.join(file_name), self.render(source_file)?)?;
+            module_names.push(module_name);
+        }
+        if let Some(index_file_name) = self.format.index_file_name() {
+            let mut index_file = fs::File::create(dir.join(index_file_name))?;
+            for module_name in module_names.iter() {
+                match self.format {
+                    OutputFormat::RustFfi => {
+                        writeln!(index_file, "pub mod {0};\npub use {0}::*;", module_name)?
+                    }
+                    _ => writeln!(index_file, "from .{} import *", module_name)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render(&self, source_file: &SourceFile) -> io::Result<String> {
+        match self.format {
+            OutputFormat::RustFfi => {
+                let mut generator =
+                    codegen::RustFfiGenerator::new(source_file).bitflags(self.use_bitflags);
+                if let Some(library_name) = &self.library_name {
+                    generator = generator.dll_name(library_name.to_owned());
+                }
+                // `mod.rs` re-exports every module, so the types of the others are in scope.
+                Ok(format!(
+                    "#[allow(unused_imports)]\nuse super::*;\n\n{}",
+                    generator.generate()
+                ))
+            }
+            OutputFormat::PythonCtypes => {
+                let mut generator = codegen::CtypesGenerator::new(source_file);
+                if let Some(library_name) = &self.library_name {
+                    generator = generator.library_name(library_name.to_owned());
+                }
+                Ok(generator.generate())
+            }
+            OutputFormat::Markdown => Ok(MarkdownTableEmitter::new(source_file).emit()),
+            #[cfg(feature = "serde")]
+            OutputFormat::Json => serde_json::to_string_pretty(source_file)
+                .map(|json| json + "\n")
+                .map_err(io::Error::from),
+            #[cfg(not(feature = "serde"))]
+            OutputFormat::Json => Err(io::Error::other("JSON output requires the `serde` feature")),
+            #[cfg(feature = "yaml")]
+            OutputFormat::Yaml => serde_yaml::to_string(source_file).map_err(io::Error::other),
+            #[cfg(not(feature = "yaml"))]
+            OutputFormat::Yaml => Err(io::Error::other("YAML output requires the `yaml` feature")),
+        }
+    }
+}
+
+impl SourceFile {
+    /// Writes one file per module group of the declarations to `dir`, named after the module,
+    /// plus a `mod.rs` or `__init__.py` re-exporting them for Rust and Python output. See
+    /// `DirectoryWriter` for the other ways to split them up.
+    pub fn write_to_dir(&self, dir: &Path, format: OutputFormat) -> Result<(), io::Error> {
+        DirectoryWriter::new(self, format).write(dir)
+    }
+}
@@ -0,0 +1,279 @@
+use crate::MacroDefinition;
+use std::convert::TryFrom;
+
+impl MacroDefinition {
+    /// Evaluates the body of an object-like macro such as `#define MAX_SIZE (64 * 1024)` as a
+    /// constant integer expression. Only integer literals, parentheses and the arithmetic,
+    /// bitwise, comparison, logical and conditional operators are understood, a body naming
+    /// another macro or casting gives `None`, as does overflow or a division by zero. An unsigned
+    /// result keeps its bits, see [`MacroDefinition::evaluate_unsigned`].
+    pub fn evaluate(&self) -> Option<i64> {
+        self.evaluate_value().map(|value| value.bits as i64)
+    }
+
+    /// Like [`MacroDefinition::evaluate`], but only for a body whose type is unsigned in C,
+    /// because it has a literal with a `u` suffix or one too large for `long long`. So
+    /// `#define ALL 0xFFFFFFFFFFFFFFFFULL` gives `u64::MAX` where `evaluate` gives `-1`.
+    pub fn evaluate_unsigned(&self) -> Option<u64> {
+        self.evaluate_value()
+            .filter(|value| value.unsigned)
+            .map(|value| value.bits)
+    }
+
+    fn evaluate_value(&self) -> Option<Value> {
+        let mut parser = ExpressionParser {
+            tokens: &self.body_tokens,
+            position: 0,
+        };
+        let value = parser.conditional()?;
+        if parser.position == self.body_tokens.len() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+/// An intermediate result, with the bits of an unsigned value stored as is.
+#[derive(Debug, Clone, Copy)]
+struct Value {
+    bits: u64,
+    unsigned: bool,
+}
+
+impl Value {
+    fn signed(value: i64) -> Self {
+        Self {
+            bits: value as u64,
+            unsigned: false,
+        }
+    }
+
+    fn truth(value: bool) -> Self {
+        Self::signed(value as i64)
+    }
+
+    fn is_true(self) -> bool {
+        self.bits != 0
+    }
+}
+
+/// Binary operators from the loosest to the tightest binding.
+const BINARY_OPERATORS: &[&[&str]] = &[
+    &["||"],
+    &["&&"],
+    &["|"],
+    &["^"],
+    &["&"],
+    &["==", "!="],
+    &["<", "<=", ">", ">="],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+/// A recursive descent parser over macro body tokens that evaluates as it goes.
+struct ExpressionParser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl<'a> ExpressionParser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).map(|token| token.as_str())
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek()?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn expect(&mut self, expected: &str) -> Option<()> {
+        if self.next()? == expected {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn conditional(&mut self) -> Option<Value> {
+        let condition = self.binary(0)?;
+        if self.peek() != Some("?") {
+            return Some(condition);
+        }
+        self.position += 1;
+        let then_value = self.conditional()?;
+        self.expect(":")?;
+        let else_value = self.conditional()?;
+        let unsigned = then_value.unsigned || else_value.unsigned;
+        let value = if condition.is_true() {
+            then_value
+        } else {
+            else_value
+        };
+        Some(Value { unsigned, ..value })
+    }
+
+    fn binary(&mut self, level: usize) -> Option<Value> {
+        if level == BINARY_OPERATORS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        while let Some(operator) = self
+            .peek()
+            .filter(|token| BINARY_OPERATORS[level].contains(token))
+        {
+            self.position += 1;
+            let right = self.binary(level + 1)?;
+            left = apply_binary(operator, left, right)?;
+        }
+        Some(left)
+    }
+
+    fn unary(&mut self) -> Option<Value> {
+        match self.next()? {
+            "+" => self.unary(),
+            "-" => {
+                let value = self.unary()?;
+                if value.unsigned {
+                    Some(Value {
+                        bits: value.bits.wrapping_neg(),
+                        ..value
+                    })
+                } else {
+                    (value.bits as i64).checked_neg().map(Value::signed)
+                }
+            }
+            "~" => {
+                let value = self.unary()?;
+                Some(Value {
+                    bits: !value.bits,
+                    ..value
+                })
+            }
+            "!" => Some(Value::truth(!self.unary()?.is_true())),
+            "(" => {
+                let value = self.conditional()?;
+                self.expect(")")?;
+                Some(value)
+            }
+            literal => parse_integer_literal(literal),
+        }
+    }
+}
+
+fn apply_binary(operator: &str, left: Value, right: Value) -> Option<Value> {
+    match operator {
+        "||" => return Some(Value::truth(left.is_true() || right.is_true())),
+        "&&" => return Some(Value::truth(left.is_true() && right.is_true())),
+        "<<" | ">>" => {
+            // The result has the type of the left operand.
+            let shift = if right.unsigned {
+                u32::try_from(right.bits).ok()?
+            } else {
+                u32::try_from(right.bits as i64).ok()?
+            };
+            return if left.unsigned {
+                let bits = if operator == "<<" {
+                    left.bits.checked_shl(shift)?
+                } else {
+                    left.bits.checked_shr(shift)?
+                };
+                Some(Value { bits, ..left })
+            } else {
+                let left = left.bits as i64;
+                if operator == "<<" {
+                    Some(shift)
+                        .filter(|&shift| shift < 64)
+                        .and_then(|shift| left.checked_mul(1 << shift))
+                        .map(Value::signed)
+                } else {
+                    left.checked_shr(shift).map(Value::signed)
+                }
+            };
+        }
+        _ => {}
+    }
+    // Like C, a signed operand is converted when the other one is unsigned.
+    if left.unsigned || right.unsigned {
+        apply_unsigned(operator, left.bits, right.bits)
+    } else {
+        apply_signed(operator, left.bits as i64, right.bits as i64)
+    }
+}
+
+fn apply_signed(operator: &str, left: i64, right: i64) -> Option<Value> {
+    match operator {
+        "|" => Some(Value::signed(left | right)),
+        "^" => Some(Value::signed(left ^ right)),
+        "&" => Some(Value::signed(left & right)),
+        "==" => Some(Value::truth(left == right)),
+        "!=" => Some(Value::truth(left != right)),
+        "<" => Some(Value::truth(left < right)),
+        "<=" => Some(Value::truth(left <= right)),
+        ">" => Some(Value::truth(left > right)),
+        ">=" => Some(Value::truth(left >= right)),
+        "+" => left.checked_add(right).map(Value::signed),
+        "-" => left.checked_sub(right).map(Value::signed),
+        "*" => left.checked_mul(right).map(Value::signed),
+        "/" => left.checked_div(right).map(Value::signed),
+        "%" => left.checked_rem(right).map(Value::signed),
+        _ => None,
+    }
+}
+
+/// Unsigned arithmetic wraps around in C instead of overflowing.
+fn apply_unsigned(operator: &str, left: u64, right: u64) -> Option<Value> {
+    let bits = match operator {
+        "==" => return Some(Value::truth(left == right)),
+        "!=" => return Some(Value::truth(left != right)),
+        "<" => return Some(Value::truth(left < right)),
+        "<=" => return Some(Value::truth(left <= right)),
+        ">" => return Some(Value::truth(left > right)),
+        ">=" => return Some(Value::truth(left >= right)),
+        "|" => left | right,
+        "^" => left ^ right,
+        "&" => left & right,
+        "+" => left.wrapping_add(right),
+        "-" => left.wrapping_sub(right),
+        "*" => left.wrapping_mul(right),
+        "/" => left.checked_div(right)?,
+        "%" => left.checked_rem(right)?,
+        _ => return None,
+    };
+    Some(Value {
+        bits,
+        unsigned: true,
+    })
+}
+
+/// Parses a decimal, hexadecimal, octal or binary integer literal. It is unsigned if it has a
+/// `u` suffix or does not fit in `long long`, `l` suffixes are ignored.
+fn parse_integer_literal(literal: &str) -> Option<Value> {
+    let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    let suffix = &literal[digits.len()..];
+    let (digits, radix) = if let Some(hex_digits) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        (hex_digits, 16)
+    } else if let Some(binary_digits) = digits
+        .strip_prefix("0b")
+        .or_else(|| digits.strip_prefix("0B"))
+    {
+        (binary_digits, 2)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (&digits[1..], 8)
+    } else {
+        (digits, 10)
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    let bits = u64::from_str_radix(digits, radix).ok()?;
+    Some(Value {
+        bits,
+        unsigned: suffix.contains(['u', 'U']) || bits > i64::MAX as u64,
+    })
+}
@@ -0,0 +1,139 @@
+use crate::Type;
+use clang::TypeKind;
+use std::fmt;
+
+/// Formats a `Type` as C syntax by walking its structure rather than relying on libclang's
+/// display name, e.g. `int (*)(char *, long)` for a function pointer.
+pub struct TypePrinter<'a> {
+    t: &'a Type,
+}
+
+impl<'a> TypePrinter<'a> {
+    pub fn new(t: &'a Type) -> Self {
+        Self { t }
+    }
+
+    pub fn print(&self) -> String {
+        self.to_string()
+    }
+
+    /// Prints `t` wrapped around `declarator`, which is empty for an abstract type or holds the
+    /// declared name.
+    pub fn print_declaration(&self, declarator: &str) -> String {
+        Self::declare(self.t, declarator.to_owned())
+    }
+
+    fn declare(t: &Type, declarator: String) -> String {
+        match t.type_kind {
+            TypeKind::Pointer => match &t.pointee_type {
+                Some(pointee_type) => {
+                    let declarator = Self::qualify_pointer(t, declarator);
+                    let declarator = if Self::binds_tighter(pointee_type) {
+                        format!("(*{})", declarator)
+                    } else {
+                        format!("*{}", declarator)
+                    };
+                    Self::declare(pointee_type, declarator)
+                }
+                None => Self::leaf(&t.type_name, declarator),
+            },
+            TypeKind::ConstantArray | TypeKind::IncompleteArray | TypeKind::VariableArray => {
+                match &t.array_element_type {
+                    Some(element_type) => {
+                        let declarator = match t.array_size {
+                            Some(array_size) if t.type_kind == TypeKind::ConstantArray => {
+                                format!("{}[{}]", declarator, array_size)
+                            }
+                            _ => format!("{}[]", declarator),
+                        };
+                        Self::declare(element_type, declarator)
+                    }
+                    None => Self::leaf(&t.type_name, declarator),
+                }
+            }
+            TypeKind::FunctionPrototype | TypeKind::FunctionNoPrototype => {
+                match &t.function_proto {
+                    Some(function_proto) => {
+                        let parameters = if function_proto.parameter_types.is_empty()
+                            && t.type_kind == TypeKind::FunctionPrototype
+                        {
+                            "void".to_owned()
+                        } else {
+                            function_proto
+                                .parameter_types
+                                .iter()
+                                .map(|parameter_type| Self::declare(parameter_type, String::new()))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        };
+                        Self::declare(
+                            &function_proto.return_type,
+                            format!("{}({})", declarator, parameters),
+                        )
+                    }
+                    None => Self::leaf(&t.type_name, declarator),
+                }
+            }
+            // The named type has the full name, the qualifiers stay on the elaborated one.
+            TypeKind::Elaborated => match t.elaborated_inner() {
+                Some(inner_type) => {
+                    let mut name = inner_type.type_name.to_owned();
+                    if t.is_volatile && !inner_type.is_volatile {
+                        name = format!("volatile {}", name);
+                    }
+                    if t.is_const && !inner_type.is_const {
+                        name = format!("const {}", name);
+                    }
+                    Self::leaf(&name, declarator)
+                }
+                None => Self::leaf(&t.type_name, declarator),
+            },
+            _ => Self::leaf(&t.type_name, declarator),
+        }
+    }
+
+    /// Qualifiers of the pointer itself go right of the `*`, as in `char *const`.
+    fn qualify_pointer(t: &Type, declarator: String) -> String {
+        let qualifiers = [
+            (t.is_const, "const"),
+            (t.is_volatile, "volatile"),
+            (t.is_restrict, "restrict"),
+        ]
+        .iter()
+        .filter(|(is_qualified, _)| *is_qualified)
+        .map(|(_, qualifier)| *qualifier)
+        .collect::<Vec<_>>();
+        if qualifiers.is_empty() {
+            declarator
+        } else if declarator.is_empty() {
+            qualifiers.join(" ")
+        } else {
+            format!("{} {}", qualifiers.join(" "), declarator)
+        }
+    }
+
+    /// Array and function declarators bind tighter than `*`, so a pointer to them needs parens.
+    fn binds_tighter(t: &Type) -> bool {
+        t.is_array()
+            || matches!(
+                t.type_kind,
+                TypeKind::FunctionPrototype | TypeKind::FunctionNoPrototype
+            )
+    }
+
+    fn leaf(type_name: &str, declarator: String) -> String {
+        let hugs_base_type = declarator.starts_with('[')
+            || (declarator.starts_with('(') && !declarator.starts_with("(*"));
+        if declarator.is_empty() || hugs_base_type {
+            format!("{}{}", type_name, declarator)
+        } else {
+            format!("{} {}", type_name, declarator)
+        }
+    }
+}
+
+impl fmt::Display for TypePrinter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Self::declare(self.t, String::new()))
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{EntityVisitor, SourceFile, TypeDeclaration};
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+};
+
+/// Indices into `SourceFile::type_declares` by tag name, typedef name and USR, so that a type
+/// referenced from a signature can be found without scanning the whole list. When a type is
+/// declared several times the index points at its definition.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityRegistry {
+    pub by_name: HashMap<String, usize>,
+    pub by_usr: HashMap<String, usize>,
+    /// How many type declarations the file had when the registry was built.
+    type_declare_count: usize,
+}
+
+impl EntityRegistry {
+    pub fn lookup_by_name<'a>(
+        &self,
+        source_file: &'a SourceFile,
+        name: &str,
+    ) -> Option<&'a dyn TypeDeclaration> {
+        self.by_name
+            .get(name)
+            .map(|&index| &source_file.type_declares[index] as &dyn TypeDeclaration)
+    }
+
+    pub fn lookup_by_usr<'a>(
+        &self,
+        source_file: &'a SourceFile,
+        usr: &str,
+    ) -> Option<&'a dyn TypeDeclaration> {
+        self.by_usr
+            .get(usr)
+            .map(|&index| &source_file.type_declares[index] as &dyn TypeDeclaration)
+    }
+}
+
+/// The registry `SourceFile::registry` builds on first use. It is derived from the declarations,
+/// so two files compare equal whether or not it was built.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RegistryCache(RefCell<Option<EntityRegistry>>);
+
+impl PartialEq for RegistryCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl SourceFile {
+    pub fn build_registry(&self) -> EntityRegistry {
+        let mut registry = EntityRegistry {
+            type_declare_count: self.type_declares.len(),
+            ..EntityRegistry::default()
+        };
+        for (index, type_declare) in self.type_declares.iter().enumerate() {
+            for name in type_declare
+                .name()
+                .into_iter()
+                .chain(type_declare.typedef_name())
+            {
+                self.register(&mut registry.by_name, name, index);
+            }
+            if let Some(usr) = type_declare.usr() {
+                self.register(&mut registry.by_usr, usr, index);
+            }
+        }
+        registry
+    }
+
+    /// The registry of the file, built on first use and rebuilt once types were added or removed.
+    /// Replacing a declaration in place goes unnoticed, `clear_registry` has to be called then.
+    pub fn registry(&self) -> Ref<'_, EntityRegistry> {
+        let cache = self.registry.0.borrow();
+        if matches!(&*cache, Some(registry) if registry.type_declare_count == self.type_declares.len())
+        {
+            return Ref::map(cache, |cache| cache.as_ref().unwrap());
+        }
+        drop(cache);
+        *self.registry.0.borrow_mut() = Some(self.build_registry());
+        Ref::map(self.registry.0.borrow(), |cache| cache.as_ref().unwrap())
+    }
+
+    pub fn clear_registry(&mut self) {
+        *self.registry.0.get_mut() = None;
+    }
+
+    /// Points `key` at the declaration at `index`, unless it already points at a definition.
+    fn register(&self, map: &mut HashMap<String, usize>, key: &str, index: usize) {
+        let is_definition = |index: usize| self.type_declares[index].is_definition();
+        match map.get(key) {
+            Some(&existing_index) if is_definition(existing_index) || !is_definition(index) => {}
+            _ => {
+                map.insert(key.to_owned(), index);
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use clang::{Accessibility, CallingConvention, Linkage, StorageClass, TypeKind};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+macro_rules! remote_option {
+    ($module:ident, $remote:ty, $def:literal) => {
+        pub(crate) mod $module {
+            use super::*;
+
+            #[derive(Serialize, Deserialize)]
+            struct Wrapper(#[serde(with = $def)] $remote);
+
+            pub(crate) fn serialize<S: Serializer>(
+                value: &Option<$remote>,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                value.map(Wrapper).serialize(serializer)
+            }
+
+            pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Option<$remote>, D::Error> {
+                Option::<Wrapper>::deserialize(deserializer)
+                    .map(|value| value.map(|Wrapper(inner)| inner))
+            }
+        }
+    };
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Accessibility")]
+pub(crate) enum AccessibilityDef {
+    Private,
+    Protected,
+    Public,
+}
+
+remote_option!(option_accessibility, Accessibility, "AccessibilityDef");
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Linkage")]
+pub(crate) enum LinkageDef {
+    Automatic,
+    Internal,
+    External,
+    UniqueExternal,
+}
+
+remote_option!(option_linkage, Linkage, "LinkageDef");
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "StorageClass")]
+pub(crate) enum StorageClassDef {
+    None,
+    Auto,
+    Register,
+    Static,
+    Extern,
+    PrivateExtern,
+    OpenClWorkGroupLocal,
+}
+
+remote_option!(option_storage_class, StorageClass, "StorageClassDef");
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "CallingConvention")]
+pub(crate) enum CallingConventionDef {
+    Unexposed,
+    Cdecl,
+    Fastcall,
+    Pascal,
+    Stdcall,
+    Thiscall,
+    Vectorcall,
+    Swift,
+    PreserveMost,
+    PreserveAll,
+    Aapcs,
+    AapcsVfp,
+    IntelOcl,
+    RegCall,
+    SysV64,
+    Win64,
+}
+
+remote_option!(
+    option_calling_convention,
+    CallingConvention,
+    "CallingConventionDef"
+);
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "TypeKind")]
+pub(crate) enum TypeKindDef {
+    Unexposed,
+    Void,
+    Bool,
+    CharS,
+    CharU,
+    SChar,
+    UChar,
+    WChar,
+    Char16,
+    Char32,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Long,
+    ULong,
+    LongLong,
+    ULongLong,
+    Int128,
+    UInt128,
+    Half,
+    Float16,
+    ShortAccum,
+    Accum,
+    LongAccum,
+    UShortAccum,
+    UAccum,
+    ULongAccum,
+    Float,
+    Double,
+    LongDouble,
+    Nullptr,
+    Complex,
+    Dependent,
+    Overload,
+    ObjCId,
+    ObjCClass,
+    ObjCSel,
+    Float128,
+    ObjCInterface,
+    ObjCObjectPointer,
+    Pointer,
+    BlockPointer,
+    MemberPointer,
+    LValueReference,
+    RValueReference,
+    Enum,
+    Record,
+    Typedef,
+    FunctionPrototype,
+    FunctionNoPrototype,
+    ConstantArray,
+    DependentSizedArray,
+    IncompleteArray,
+    VariableArray,
+    Vector,
+    Auto,
+    Elaborated,
+    Pipe,
+    OCLImage1dRO,
+    OCLImage1dArrayRO,
+    OCLImage1dBufferRO,
+    OCLImage2dRO,
+    OCLImage2dArrayRO,
+    OCLImage2dDepthRO,
+    OCLImage2dArrayDepthRO,
+    OCLImage2dMSAARO,
+    OCLImage2dArrayMSAARO,
+    OCLImage2dMSAADepthRO,
+    OCLImage2dArrayMSAADepthRO,
+    OCLImage3dRO,
+    OCLImage1dWO,
+    OCLImage1dArrayWO,
+    OCLImage1dBufferWO,
+    OCLImage2dWO,
+    OCLImage2dArrayWO,
+    OCLImage2dDepthWO,
+    OCLImage2dArrayDepthWO,
+    OCLImage2dMSAAWO,
+    OCLImage2dArrayMSAAWO,
+    OCLImage2dMSAADepthWO,
+    OCLImage2dArrayMSAADepthWO,
+    OCLImage3dWO,
+    OCLImage1dRW,
+    OCLImage1dArrayRW,
+    OCLImage1dBufferRW,
+    OCLImage2dRW,
+    OCLImage2dArrayRW,
+    OCLImage2dDepthRW,
+    OCLImage2dArrayDepthRW,
+    OCLImage2dMSAARW,
+    OCLImage2dArrayMSAARW,
+    OCLImage2dMSAADepthRW,
+    OCLImage2dArrayMSAADepthRW,
+    OCLImage3dRW,
+    OCLSampler,
+    OCLEvent,
+    OCLQueue,
+    OCLReserveID,
+    ObjCObject,
+    ObjCTypeParam,
+    Attributed,
+    OCLIntelSubgroupAVCMcePayload,
+    OCLIntelSubgroupAVCImePayload,
+    OCLIntelSubgroupAVCRefPayload,
+    OCLIntelSubgroupAVCSicPayload,
+    OCLIntelSubgroupAVCMceResult,
+    OCLIntelSubgroupAVCImeResult,
+    OCLIntelSubgroupAVCRefResult,
+    OCLIntelSubgroupAVCSicResult,
+    OCLIntelSubgroupAVCImeResultSingleRefStreamout,
+    OCLIntelSubgroupAVCImeResultDualRefStreamout,
+    OCLIntelSubgroupAVCImeSingleRefStreamin,
+    OCLIntelSubgroupAVCImeDualRefStreamin,
+}
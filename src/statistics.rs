@@ -0,0 +1,93 @@
+use crate::{MemberDeclare, SourceFile, TypeDeclaration, TypeDeclare};
+use std::fmt;
+
+/// Counts of what was parsed from a header, for a quick sanity check.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AstStatistics {
+    pub total_functions: usize,
+    pub total_types: usize,
+    pub enum_count: usize,
+    pub struct_count: usize,
+    pub union_count: usize,
+    /// Typedef names, whether they are declared on their own or name a struct, union or enum.
+    pub typedef_count: usize,
+    pub total_enum_constants: usize,
+    /// Fields of structs, unions and classes, including those of nested records.
+    pub total_struct_fields: usize,
+    pub total_function_parameters: usize,
+}
+
+impl fmt::Display for AstStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Functions: {}, Parameters: {}, Types: {}, Structs: {}, Unions: {}, Enums: {}, \
+             Typedefs: {}, Fields: {}, Enum constants: {}",
+            self.total_functions,
+            self.total_function_parameters,
+            self.total_types,
+            self.struct_count,
+            self.union_count,
+            self.enum_count,
+            self.typedef_count,
+            self.total_struct_fields,
+            self.total_enum_constants
+        )
+    }
+}
+
+impl AstStatistics {
+    fn count_members(&mut self, members: &[MemberDeclare]) {
+        for member in members.iter() {
+            match member {
+                MemberDeclare::Field(_) => self.total_struct_fields += 1,
+                MemberDeclare::Struct(struct_declare) => self.count_members(&struct_declare.fields),
+                MemberDeclare::Union(union_declare) => self.count_members(&union_declare.fields),
+                MemberDeclare::Enum(enum_declare) => {
+                    self.total_enum_constants += enum_declare.constants.len()
+                }
+            }
+        }
+    }
+}
+
+impl SourceFile {
+    pub fn statistics(&self) -> AstStatistics {
+        let mut statistics = AstStatistics {
+            total_functions: self.function_declares.len(),
+            total_types: self.type_declares.len(),
+            total_function_parameters: self
+                .function_declares
+                .iter()
+                .map(|function_declare| function_declare.parameters.len())
+                .sum(),
+            ..AstStatistics::default()
+        };
+        for type_declare in self.type_declares.iter() {
+            if type_declare.typedef_name().is_some() {
+                statistics.typedef_count += 1;
+            }
+            match type_declare {
+                TypeDeclare::Enum(enum_declare) => {
+                    statistics.enum_count += 1;
+                    statistics.total_enum_constants += enum_declare.constants.len();
+                }
+                TypeDeclare::Struct(struct_declare) => {
+                    statistics.struct_count += 1;
+                    statistics.count_members(&struct_declare.fields);
+                }
+                TypeDeclare::Union(union_declare) => {
+                    statistics.union_count += 1;
+                    statistics.count_members(&union_declare.fields);
+                }
+                TypeDeclare::Class(class_declare) => {
+                    statistics.count_members(&class_declare.fields)
+                }
+                TypeDeclare::Typedef(_) | TypeDeclare::FunctionPointer(_) => {
+                    statistics.typedef_count += 1
+                }
+            }
+        }
+        statistics
+    }
+}
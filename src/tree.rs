@@ -0,0 +1,60 @@
+use clang::Entity;
+
+pub type EntityFilter = Box<dyn Fn(&Entity) -> bool>;
+
+/// Prints the raw clang entity tree below an entity, one entity per line, which is mostly
+/// useful for finding out how clang represents a construct before teaching the visitors about it.
+pub struct TreePrinter {
+    /// Number of spaces each level of the tree is indented by.
+    pub indent_width: usize,
+    /// Entities deeper than this below the root are not printed.
+    pub max_depth: Option<usize>,
+    pub show_system_headers: bool,
+    /// Entities rejected by the filter are skipped together with their children.
+    pub filter: Option<EntityFilter>,
+}
+
+impl TreePrinter {
+    pub fn new() -> Self {
+        Self {
+            indent_width: 4,
+            max_depth: None,
+            show_system_headers: false,
+            filter: None,
+        }
+    }
+
+    /// Prints `entity` itself and the descendants that pass `show_system_headers` and `filter`.
+    pub fn print(&self, entity: Entity) {
+        println!("{:?}", entity);
+        self.print_children(entity, 1);
+    }
+
+    fn print_children(&self, entity: Entity, level: usize) {
+        if self
+            .max_depth
+            .map(|max_depth| level > max_depth)
+            .unwrap_or(false)
+        {
+            return;
+        }
+        for child in entity.get_children() {
+            if !self.show_system_headers && child.is_in_system_header() {
+                continue;
+            }
+            if let Some(filter) = &self.filter {
+                if !filter(&child) {
+                    continue;
+                }
+            }
+            println!("{}{:?}", " ".repeat(level * self.indent_width), child);
+            self.print_children(child, level + 1);
+        }
+    }
+}
+
+impl Default for TreePrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
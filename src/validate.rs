@@ -0,0 +1,225 @@
+use crate::{
+    EntityVisitor, FunctionParameter, MemberDeclare, SourceFile, Type, TypeDeclaration, TypeDeclare,
+};
+use clang::TypeKind;
+use std::fmt;
+
+/// A semantic issue found in a parsed `SourceFile`. None of them stop parsing, callers decide
+/// whether to fail, skip the declaration or carry on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// libclang gave no result type for the function.
+    MissingReturnType { function_name: String },
+    /// The size of the field is unknown, usually because its type is incomplete.
+    UnknownFieldSize {
+        record_name: String,
+        field_name: String,
+    },
+    /// libclang could not evaluate the value of the constant.
+    MissingEnumValue {
+        enum_name: String,
+        constant_name: String,
+    },
+    /// A type that is only forward declared is held by value instead of behind a pointer. Fields
+    /// are given as `record.field`, parameters as `function(parameter)`.
+    ForwardDeclarationByValue {
+        entity_name: String,
+        type_name: String,
+    },
+    /// A `typeof(...)` or `__auto_type` type was left undeduced, see `Type::is_deduced`.
+    UndeducedType {
+        entity_name: String,
+        type_name: String,
+    },
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationWarning::MissingReturnType { function_name } => {
+                write!(f, "{} has no return type", function_name)
+            }
+            ValidationWarning::UnknownFieldSize {
+                record_name,
+                field_name,
+            } => write!(f, "{}.{} has unknown size", record_name, field_name),
+            ValidationWarning::MissingEnumValue {
+                enum_name,
+                constant_name,
+            } => write!(f, "{}::{} has no value", enum_name, constant_name),
+            ValidationWarning::ForwardDeclarationByValue {
+                entity_name,
+                type_name,
+            } => write!(
+                f,
+                "{} uses forward declared type {} by value",
+                entity_name, type_name
+            ),
+            ValidationWarning::UndeducedType {
+                entity_name,
+                type_name,
+            } => write!(f, "{} has undeduced type {}", entity_name, type_name),
+        }
+    }
+}
+
+impl SourceFile {
+    /// Walks all declarations and collects the semantic issues found in them.
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+        for type_declare in self.type_declares.iter() {
+            let name = type_declare
+                .name()
+                .or_else(|| type_declare.typedef_name())
+                .unwrap_or("<anonymous>");
+            match type_declare {
+                TypeDeclare::Struct(declare) => {
+                    self.validate_members(name, &declare.fields, &mut warnings)
+                }
+                TypeDeclare::Union(declare) => {
+                    self.validate_members(name, &declare.fields, &mut warnings)
+                }
+                TypeDeclare::Class(declare) => {
+                    self.validate_members(name, &declare.fields, &mut warnings)
+                }
+                TypeDeclare::Enum(declare) => {
+                    for constant in declare
+                        .constants
+                        .iter()
+                        .filter(|constant| constant.constant_value.is_none())
+                    {
+                        warnings.push(ValidationWarning::MissingEnumValue {
+                            enum_name: name.to_owned(),
+                            constant_name: constant.name.to_owned(),
+                        });
+                    }
+                }
+                TypeDeclare::Typedef(_) | TypeDeclare::FunctionPointer(_) => {}
+            }
+        }
+        for function_declare in self.function_declares.iter() {
+            let function_name = &function_declare.function_name;
+            match &function_declare.return_type {
+                Some(return_type) => self.validate_type(function_name, return_type, &mut warnings),
+                None => warnings.push(ValidationWarning::MissingReturnType {
+                    function_name: function_name.to_owned(),
+                }),
+            }
+            for (index, parameter) in function_declare.parameters.iter().enumerate() {
+                if let FunctionParameter::Named(parameter_declare) = parameter {
+                    if let Some(parameter_type) = &parameter_declare.parameter_type {
+                        self.validate_type(
+                            &format!(
+                                "{}({})",
+                                function_name,
+                                parameter_declare.binding_name(index)
+                            ),
+                            parameter_type,
+                            &mut warnings,
+                        );
+                    }
+                }
+            }
+        }
+        for variable_declare in self.variable_declares.iter() {
+            if let Some(variable_type) = &variable_declare.variable_type {
+                self.validate_type(&variable_declare.name, variable_type, &mut warnings);
+            }
+        }
+        warnings
+    }
+
+    fn validate_members(
+        &self,
+        record_name: &str,
+        members: &[MemberDeclare],
+        warnings: &mut Vec<ValidationWarning>,
+    ) {
+        for member in members.iter() {
+            match member {
+                MemberDeclare::Field(field_declare) => {
+                    let field_name = field_declare.name().unwrap_or("<unnamed>");
+                    if field_declare.size_bytes.is_none() && !field_declare.is_flexible_array_member
+                    {
+                        warnings.push(ValidationWarning::UnknownFieldSize {
+                            record_name: record_name.to_owned(),
+                            field_name: field_name.to_owned(),
+                        });
+                    }
+                    if let Some(field_type) = &field_declare.field_type {
+                        self.validate_type(
+                            &format!("{}.{}", record_name, field_name),
+                            field_type,
+                            warnings,
+                        );
+                    }
+                }
+                MemberDeclare::Struct(declare) => {
+                    self.validate_members(record_name, &declare.fields, warnings)
+                }
+                MemberDeclare::Union(declare) => {
+                    self.validate_members(record_name, &declare.fields, warnings)
+                }
+                MemberDeclare::Enum(_) => {}
+            }
+        }
+    }
+
+    fn validate_type(&self, entity_name: &str, t: &Type, warnings: &mut Vec<ValidationWarning>) {
+        if let Some(deduced_type) = find_deduced(t) {
+            warnings.push(ValidationWarning::UndeducedType {
+                entity_name: entity_name.to_owned(),
+                type_name: deduced_type.type_name.to_owned(),
+            });
+        }
+        let mut value_type = t.resolve_canonical();
+        while let Some(element_type) = &value_type.array_element_type {
+            value_type = element_type.resolve_canonical();
+        }
+        if value_type.type_kind != TypeKind::Record {
+            return;
+        }
+        let type_name = value_type.tagless_name();
+        if self.is_forward_declared_only(type_name) {
+            warnings.push(ValidationWarning::ForwardDeclarationByValue {
+                entity_name: entity_name.to_owned(),
+                type_name: type_name.to_owned(),
+            });
+        }
+    }
+
+    /// Whether the file declares the type without ever defining it.
+    fn is_forward_declared_only(&self, name: &str) -> bool {
+        let mut type_declares = self.type_declares.iter().filter(|type_declare| {
+            type_declare.name() == Some(name) || type_declare.typedef_name() == Some(name)
+        });
+        match type_declares.next() {
+            Some(type_declare) => {
+                !type_declare.is_definition()
+                    && !type_declares.any(|type_declare| type_declare.is_definition())
+            }
+            // With `--skip-forward-decls` the declarations are only kept by name.
+            None => self
+                .forward_declarations
+                .iter()
+                .any(|forward_declaration| forward_declaration.name == name),
+        }
+    }
+}
+
+/// The first undeduced type making up `t`.
+fn find_deduced(t: &Type) -> Option<&Type> {
+    if t.is_deduced {
+        return Some(t);
+    }
+    let function_types = t.function_proto.iter().flat_map(|function_proto| {
+        std::iter::once(function_proto.return_type.as_ref())
+            .chain(function_proto.parameter_types.iter())
+    });
+    t.pointee_type
+        .as_deref()
+        .into_iter()
+        .chain(t.array_element_type.as_deref())
+        .chain(function_types)
+        .find_map(find_deduced)
+}
@@ -0,0 +1,58 @@
+use clang::TypeKind;
+use test_clang_rust::{
+    EnumConstantDeclare, EnumDeclare, FunctionDeclare, FunctionParameter, ParameterDeclare,
+    SourceFile, SourceLocation, Type, TypeDeclare,
+};
+
+fn move_point(dx_type_name: &str, line_number: u32) -> FunctionDeclare {
+    let mut function_declare = FunctionDeclare::new("move_point".to_owned());
+    function_declare.return_type = Some(Type::new(TypeKind::Void, "void".to_owned()));
+    let mut parameter_declare = ParameterDeclare::new(Some("dx".to_owned()));
+    parameter_declare.parameter_type = Some(Type::new(TypeKind::Int, dx_type_name.to_owned()));
+    function_declare
+        .parameters
+        .push(FunctionParameter::Named(parameter_declare));
+    function_declare.location = Some(SourceLocation::new(("api.h".to_owned(), line_number, 1)));
+    function_declare
+}
+
+fn color() -> TypeDeclare {
+    let mut color = EnumDeclare::new(Some("color".to_owned()), None);
+    color
+        .constants
+        .push(EnumConstantDeclare::new("RED".to_owned()));
+    TypeDeclare::Enum(color)
+}
+
+#[test]
+fn test_compute_hash() {
+    let mut source_file = SourceFile::new("api.h".to_owned());
+    source_file.type_declares.push(color());
+    source_file.function_declares.push(move_point("int", 3));
+    source_file
+        .function_declares
+        .push(FunctionDeclare::new("reset".to_owned()));
+
+    let mut moved = SourceFile::new("api.h".to_owned());
+    moved
+        .function_declares
+        .push(FunctionDeclare::new("reset".to_owned()));
+    moved.function_declares.push(move_point("int", 12));
+    moved.type_declares.push(color());
+    assert_eq!(source_file.compute_hash(), moved.compute_hash());
+
+    let mut changed = SourceFile::new("api.h".to_owned());
+    changed.type_declares.push(color());
+    changed.function_declares.push(move_point("long", 3));
+    changed
+        .function_declares
+        .push(FunctionDeclare::new("reset".to_owned()));
+    assert_ne!(source_file.compute_hash(), changed.compute_hash());
+
+    if let TypeDeclare::Enum(color) = &mut moved.type_declares[0] {
+        color
+            .constants
+            .push(EnumConstantDeclare::new("GREEN".to_owned()));
+    }
+    assert_ne!(source_file.compute_hash(), moved.compute_hash());
+}
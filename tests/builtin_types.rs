@@ -0,0 +1,117 @@
+use clang::TypeKind;
+use test_clang_rust::{
+    builtin_types::BuiltinTypeMapper, codegen::RustFfiGenerator, SourceFile, Type, TypeDeclare,
+    TypedefDeclare,
+};
+
+fn sizes(target_triple: &str, kinds: &[TypeKind]) -> Vec<Option<u8>> {
+    let type_mapper = BuiltinTypeMapper::new(target_triple.to_owned());
+    kinds
+        .iter()
+        .map(|&kind| type_mapper.map_type_size(kind))
+        .collect()
+}
+
+#[test]
+fn test_fixed_sizes() {
+    let kinds = [
+        TypeKind::Bool,
+        TypeKind::CharS,
+        TypeKind::Short,
+        TypeKind::Int,
+        TypeKind::LongLong,
+        TypeKind::Float,
+        TypeKind::Double,
+        TypeKind::UInt128,
+    ];
+    let expected = vec![
+        Some(1),
+        Some(1),
+        Some(2),
+        Some(4),
+        Some(8),
+        Some(4),
+        Some(8),
+        Some(16),
+    ];
+    for target_triple in ["x86_64-pc-windows-msvc", "i686-unknown-linux-gnu"] {
+        assert_eq!(sizes(target_triple, &kinds), expected);
+    }
+    assert_eq!(
+        sizes("x86_64-unknown-linux-gnu", &[TypeKind::Record]),
+        vec![None]
+    );
+}
+
+#[test]
+fn test_target_dependent_sizes() {
+    let kinds = [
+        TypeKind::Long,
+        TypeKind::WChar,
+        TypeKind::LongDouble,
+        TypeKind::Pointer,
+    ];
+    assert_eq!(
+        sizes("x86_64-unknown-linux-gnu", &kinds),
+        vec![Some(8), Some(4), Some(16), Some(8)]
+    );
+    assert_eq!(
+        sizes("x86_64-pc-windows-msvc", &kinds),
+        vec![Some(4), Some(2), Some(8), Some(8)]
+    );
+    assert_eq!(
+        sizes("i686-unknown-linux-gnu", &kinds),
+        vec![Some(4), Some(4), Some(12), Some(4)]
+    );
+    assert_eq!(
+        sizes("aarch64-apple-darwin", &kinds),
+        vec![Some(8), Some(4), Some(8), Some(8)]
+    );
+    assert_eq!(sizes("arm64_32-apple-watchos", &kinds[..1]), vec![Some(4)]);
+}
+
+#[test]
+fn test_type_size_resolves_typedefs_and_arrays() {
+    let type_mapper = BuiltinTypeMapper::new("i686-unknown-linux-gnu".to_owned());
+    let mut size_t = Type::new(TypeKind::Typedef, "size_t".to_owned());
+    size_t.canonical_type = Some(Box::new(Type::new(
+        TypeKind::ULong,
+        "unsigned long".to_owned(),
+    )));
+    let mut sizes = Type::new(TypeKind::ConstantArray, "size_t[3]".to_owned());
+    sizes.array_element_type = Some(Box::new(size_t.clone()));
+    sizes.array_size = Some(3);
+
+    assert_eq!(type_mapper.type_size(&size_t), Some(4));
+    assert_eq!(type_mapper.type_size(&sizes), Some(12));
+}
+
+#[test]
+fn test_rust_bindings_use_target_sizes() {
+    let mut source_file = SourceFile::new("types.h".to_owned());
+    source_file.type_declares = vec![
+        TypeDeclare::Typedef(TypedefDeclare::new(
+            "native_long".to_owned(),
+            Type::new(TypeKind::Long, "long".to_owned()),
+        )),
+        TypeDeclare::Typedef(TypedefDeclare::new(
+            "native_wchar".to_owned(),
+            Type::new(TypeKind::WChar, "wchar_t".to_owned()),
+        )),
+    ];
+    let portable = RustFfiGenerator::new(&source_file).generate();
+    assert!(portable.contains("pub type native_long = std::os::raw::c_long;"));
+
+    source_file.target_triple = Some("x86_64-pc-windows-msvc".to_owned());
+    let windows = RustFfiGenerator::new(&source_file).generate();
+    assert!(windows.contains("pub type native_long = i32;"));
+    assert!(windows.contains("pub type native_wchar = u16;"));
+
+    let linux = RustFfiGenerator::new(&source_file)
+        .type_mapper(BuiltinTypeMapper::new(
+            "x86_64-unknown-linux-gnu".to_owned(),
+        ))
+        .generate();
+    assert!(linux.contains("pub type native_long = i64;"));
+    assert!(linux.contains("pub type native_wchar = i32;"));
+}
@@ -0,0 +1,1305 @@
+#![cfg(feature = "serde")]
+
+use serde_json::Value;
+use std::{path::Path, process::Command};
+
+fn run_fixture(name: &str, args: &[&str]) -> Vec<u8> {
+    let fixture_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name);
+    let output = Command::new(env!("CARGO_BIN_EXE_test-clang-rust"))
+        .args(args)
+        .arg(fixture_path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output.stdout
+}
+
+fn parse_fixture_with_args(name: &str, args: &[&str]) -> Value {
+    let mut json_args = vec!["--format=json"];
+    json_args.extend_from_slice(args);
+    serde_json::from_slice(&run_fixture(name, &json_args)).unwrap()
+}
+
+fn parse_fixture(name: &str) -> Value {
+    parse_fixture_with_args(name, &[])
+}
+
+#[test]
+fn test_bit_field_width() {
+    let source_file = parse_fixture("bitfield.h");
+    let fields = &source_file["type_declares"][0]["Struct"]["fields"];
+
+    assert_eq!(fields[0]["Field"]["name"], "version");
+    assert_eq!(fields[0]["Field"]["is_bit_field"], true);
+    assert_eq!(fields[0]["Field"]["bit_width"], 3);
+
+    assert_eq!(fields[1]["Field"]["name"], "flags");
+    assert_eq!(fields[1]["Field"]["is_bit_field"], true);
+    assert_eq!(fields[1]["Field"]["bit_width"], 5);
+
+    assert_eq!(fields[2]["Field"]["name"], "length");
+    assert_eq!(fields[2]["Field"]["is_bit_field"], false);
+    assert_eq!(fields[2]["Field"]["bit_width"], Value::Null);
+}
+
+#[test]
+fn test_rust_bit_fields() {
+    let bindings = String::from_utf8(run_fixture("bitfield.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains(
+        "pub struct packet_header {\n    // bit-fields version: 3 bits, flags: 5 bits\n    \
+         pub __bitfield_0: u32,\n    pub length: u32,\n}\n"
+    ));
+    assert!(bindings.contains(
+        "    pub fn flags(&self) -> u32 {\n        (self.__bitfield_0 >> 3) & 0x1f\n    }\n"
+    ));
+    assert!(bindings.contains(
+        "    pub fn set_flags(&mut self, value: u32) {\n        self.__bitfield_0 = \
+         (self.__bitfield_0 & !(0x1f << 3)) | (((value as u32) & 0x1f) << 3);\n    }\n"
+    ));
+    assert!(bindings.contains(
+        "pub struct file_mode {\n    // bit-fields permissions: 12 bits, owner: 20 bits\n    \
+         pub __bitfield_0: u64,\n    pub size: std::os::raw::c_ulong,\n}\n"
+    ));
+
+    let bindings =
+        String::from_utf8(run_fixture("anonymous_member.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains(
+        "    pub fn flags(&self) -> i32 {\n        ((self.__bitfield_0 << 24) as i32) >> 28\n    }\n"
+    ));
+}
+
+#[test]
+fn test_forward_declaration() {
+    let source_file = parse_fixture("forward_decl.h");
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    assert_eq!(type_declares.len(), 3);
+
+    assert_eq!(type_declares[0]["Struct"]["struct_name"], "node");
+    assert_eq!(type_declares[0]["Struct"]["is_definition"], false);
+    assert_eq!(type_declares[1]["Struct"]["struct_name"], "list");
+    assert_eq!(type_declares[1]["Struct"]["is_definition"], true);
+    assert_eq!(type_declares[2]["Struct"]["struct_name"], "node");
+    assert_eq!(type_declares[2]["Struct"]["is_definition"], true);
+
+    let source_file = parse_fixture_with_args("forward_decl.h", &["--skip-forward-decls"]);
+    assert_eq!(source_file["type_declares"].as_array().unwrap().len(), 2);
+    let forward_declarations = source_file["forward_declarations"].as_array().unwrap();
+    assert_eq!(forward_declarations.len(), 1);
+    assert_eq!(forward_declarations[0]["name"], "node");
+    assert_eq!(forward_declarations[0]["kind"], "Struct");
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn test_calling_convention() {
+    let source_file = parse_fixture("calling_convention.h");
+    let function_declares = &source_file["function_declares"];
+
+    assert_eq!(function_declares[0]["function_name"], "win64_add");
+    assert_eq!(function_declares[0]["calling_convention"], "Win64");
+    assert_eq!(function_declares[1]["function_name"], "cdecl_add");
+    assert_eq!(function_declares[1]["calling_convention"], "Cdecl");
+}
+
+#[test]
+fn test_scoped_enum() {
+    let source_file = parse_fixture_with_args("scoped_enum.hpp", &["--language", "c++"]);
+    let type_declares = &source_file["type_declares"];
+
+    assert_eq!(type_declares[0]["Enum"]["enum_name"], "Color");
+    assert_eq!(type_declares[0]["Enum"]["is_scoped"], true);
+    assert_eq!(type_declares[1]["Enum"]["enum_name"], "Direction");
+    assert_eq!(type_declares[1]["Enum"]["is_scoped"], false);
+}
+
+#[test]
+fn test_atomic_type() {
+    let source_file = parse_fixture("atomic.h");
+    let fields = &source_file["type_declares"][0]["Struct"]["fields"];
+
+    assert_eq!(fields[0]["Field"]["name"], "value");
+    assert_eq!(fields[0]["Field"]["field_type"]["is_atomic"], true);
+    assert_eq!(fields[1]["Field"]["name"], "plain");
+    assert_eq!(fields[1]["Field"]["field_type"]["is_atomic"], false);
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_round_trip() {
+    let source_file = parse_fixture("api.h");
+    let yaml_source_file: serde_yaml::Value =
+        serde_yaml::from_slice(&run_fixture("api.h", &["--format=yaml"])).unwrap();
+
+    let type_declares = yaml_source_file["type_declares"].as_sequence().unwrap();
+    let function_declares = yaml_source_file["function_declares"].as_sequence().unwrap();
+    assert_eq!(type_declares.len(), 3);
+    assert_eq!(function_declares.len(), 3);
+    assert_eq!(
+        type_declares.len(),
+        source_file["type_declares"].as_array().unwrap().len()
+    );
+    assert_eq!(
+        function_declares.len(),
+        source_file["function_declares"].as_array().unwrap().len()
+    );
+}
+
+#[test]
+fn test_special_member_functions() {
+    let source_file = parse_fixture_with_args(
+        "special_members.hpp",
+        &["--language", "c++", "--std", "c++17"],
+    );
+    let class = &source_file["type_declares"][0]["Class"];
+    let constructor_kinds = class["constructors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|constructor| {
+            (
+                constructor["kind"].as_str().unwrap(),
+                constructor["is_explicit"].as_bool().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        constructor_kinds,
+        vec![
+            ("Default", false),
+            ("Copy", false),
+            ("Move", false),
+            ("Converting", false),
+            ("Other", true),
+            ("Other", false),
+        ]
+    );
+    assert_eq!(class["destructor"]["function_name"], "~celsius");
+    assert_eq!(class["destructor"]["is_defaulted"], true);
+    assert_eq!(class["methods"][0]["function_name"], "operator=");
+
+    let conversion_functions = class["conversion_functions"].as_array().unwrap();
+    assert_eq!(conversion_functions.len(), 2);
+    assert_eq!(
+        conversion_functions[0]["target_type"]["type_name"],
+        "double"
+    );
+    assert_eq!(conversion_functions[0]["is_explicit"], false);
+    assert_eq!(conversion_functions[1]["target_type"]["type_name"], "bool");
+    assert_eq!(conversion_functions[1]["is_explicit"], true);
+}
+
+#[test]
+fn test_class_members() {
+    let source_file = parse_fixture_with_args("class_members.hpp", &["--language", "c++"]);
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    assert_eq!(type_declares.len(), 2);
+    let shape = &type_declares[0]["Class"];
+    assert_eq!(shape["destructor"]["function_name"], "~shape");
+    assert_eq!(shape["destructor"]["is_virtual"], true);
+
+    let circle = &type_declares[1]["Class"];
+    assert_eq!(circle["destructor"]["function_name"], "~circle");
+    assert_eq!(circle["destructor"]["is_virtual"], true);
+    let fields = circle["fields"].as_array().unwrap();
+    assert_eq!(fields.len(), 4);
+    assert_eq!(fields[0]["Struct"]["struct_name"], "point");
+    assert_eq!(fields[1]["Enum"]["enum_name"], "fill");
+    assert_eq!(fields[2]["Field"]["name"], "center");
+    assert_eq!(fields[3]["Field"]["name"], "radius");
+    assert!(circle["methods"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_struct_methods() {
+    let source_file = parse_fixture_with_args("struct_methods.hpp", &["--language", "c++"]);
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    assert_eq!(type_declares.len(), 2);
+    let vec2 = &type_declares[0]["Struct"];
+    assert_eq!(vec2["struct_name"], "vec2");
+    let fields = vec2["fields"].as_array().unwrap();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0]["Field"]["name"], "x");
+    assert_eq!(fields[1]["Field"]["name"], "y");
+
+    let number = &type_declares[1]["Union"];
+    assert_eq!(number["union_name"], "number");
+    let fields = number["fields"].as_array().unwrap();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0]["Field"]["name"], "i");
+    assert_eq!(fields[1]["Field"]["name"], "f");
+}
+
+#[test]
+fn test_templates() {
+    let source_file =
+        parse_fixture_with_args("templates.hpp", &["--language", "c++", "--std", "c++17"]);
+    let function_template = &source_file["function_templates"][0];
+    assert_eq!(
+        function_template["template_parameters"][0]["Type"]["name"],
+        "T"
+    );
+    assert_eq!(function_template["function"]["function_name"], "max_of");
+    assert_eq!(
+        function_template["function"]["parameters"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let class_templates = source_file["class_templates"].as_array().unwrap();
+    assert_eq!(class_templates.len(), 2);
+    let fixed_array = &class_templates[0];
+    assert_eq!(fixed_array["class"]["class_name"], "fixed_array");
+    assert_eq!(fixed_array["template_parameters"][0]["Type"]["name"], "T");
+    assert_eq!(
+        fixed_array["template_parameters"][1]["NonType"]["name"],
+        "N"
+    );
+    assert_eq!(fixed_array["class"]["fields"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        class_templates[1]["template_parameters"][0]["Template"]["name"],
+        "Container"
+    );
+}
+
+#[test]
+fn test_extern_c_block() {
+    for args in [
+        &["--language", "c"][..],
+        &["--language", "c++", "--std", "c++17"],
+    ] {
+        let source_file = parse_fixture_with_args("extern_c.h", args);
+        assert_eq!(
+            source_file["type_declares"][0]["Struct"]["struct_name"],
+            "buffer"
+        );
+        let function_names = source_file["function_declares"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|function_declare| function_declare["function_name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(function_names, vec!["buffer_new", "buffer_free"]);
+    }
+
+    let source_file =
+        parse_fixture_with_args("extern_c.h", &["--language", "c++", "--std", "c++17"]);
+    let io = &source_file["namespace_declares"][0];
+    assert_eq!(io["name"], "io");
+    assert_eq!(io["function_declares"][0]["function_name"], "io_flush");
+}
+
+#[test]
+fn test_namespace() {
+    let source_file =
+        parse_fixture_with_args("namespace.hpp", &["--language", "c++", "--std", "c++17"]);
+    let namespace_declares = source_file["namespace_declares"].as_array().unwrap();
+    assert_eq!(namespace_declares.len(), 3);
+
+    let outer = &namespace_declares[0];
+    assert_eq!(outer["name"], "outer");
+    assert_eq!(outer["type_declares"][0]["Struct"]["struct_name"], "point");
+    assert_eq!(outer["type_declares"][0]["Struct"]["is_recursive"], false);
+    assert_eq!(outer["type_declares"][1]["Struct"]["struct_name"], "link");
+    assert_eq!(outer["type_declares"][1]["Struct"]["is_recursive"], true);
+    assert_eq!(outer["function_declares"][0]["function_name"], "distance");
+    assert_eq!(outer["constexpr_declares"][0]["name"], "max_depth");
+    assert_eq!(outer["constants"][0]["name"], "min_depth");
+    assert!(outer["variable_declares"].as_array().unwrap().is_empty());
+    assert_eq!(outer["nested_namespaces"][0]["name"], "inner");
+    assert_eq!(
+        outer["nested_namespaces"][0]["enclosing_namespace"],
+        "outer"
+    );
+    assert_eq!(
+        outer["nested_namespaces"][0]["variable_declares"][0]["name"],
+        "counter"
+    );
+
+    let detail = &namespace_declares[1]["nested_namespaces"][0];
+    assert_eq!(detail["name"], "detail");
+    assert_eq!(detail["enclosing_namespace"], "outer");
+    assert_eq!(detail["type_declares"][0]["Enum"]["is_scoped"], true);
+
+    let anonymous = &namespace_declares[2];
+    assert_eq!(anonymous["name"], Value::Null);
+    assert_eq!(anonymous["function_declares"][0]["function_name"], "helper");
+}
+
+#[test]
+fn test_inline_function() {
+    let source_file = parse_fixture("inline.h");
+    let function_declares = &source_file["function_declares"];
+
+    assert_eq!(function_declares[0]["function_name"], "regular");
+    assert_eq!(function_declares[0]["is_inline"], false);
+    assert_eq!(function_declares[0]["is_static_inline"], false);
+
+    assert_eq!(function_declares[1]["function_name"], "twice");
+    assert_eq!(function_declares[1]["is_inline"], true);
+    assert_eq!(function_declares[1]["is_static_inline"], false);
+
+    assert_eq!(function_declares[2]["function_name"], "square");
+    assert_eq!(function_declares[2]["is_inline"], true);
+    assert_eq!(function_declares[2]["is_static_inline"], true);
+
+    assert_eq!(function_declares[3]["function_name"], "clamp");
+    assert_eq!(function_declares[3]["is_inline"], true);
+    assert_eq!(function_declares[3]["is_static_inline"], true);
+
+    let source_file = parse_fixture_with_args("inline.h", &["--skip-inline"]);
+    let function_declares = source_file["function_declares"].as_array().unwrap();
+    assert_eq!(function_declares.len(), 1);
+    assert_eq!(function_declares[0]["function_name"], "regular");
+}
+
+#[test]
+fn test_typedef_alias() {
+    let source_file = parse_fixture("typedef_alias.h");
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    let typedef_declare = |name: &str| {
+        type_declares
+            .iter()
+            .find(|type_declare| type_declare["Typedef"]["name"] == name)
+            .map(|type_declare| &type_declare["Typedef"])
+            .unwrap()
+    };
+
+    let size_type = typedef_declare("size_type");
+    assert_eq!(size_type["underlying_type"]["type_kind"], "ULong");
+
+    let handle = typedef_declare("Handle");
+    assert_eq!(handle["underlying_type"]["type_kind"], "Pointer");
+    assert_eq!(
+        handle["underlying_type"]["pointee_type"]["type_kind"],
+        "Void"
+    );
+
+    let widget_ref = typedef_declare("widget_ref");
+    assert_eq!(widget_ref["underlying_type"]["type_kind"], "Pointer");
+
+    let length_type = typedef_declare("length_type");
+    assert_eq!(length_type["underlying_type"]["type_kind"], "Typedef");
+    assert_eq!(length_type["underlying_type"]["type_name"], "size_type");
+}
+
+#[test]
+fn test_recursive_type() {
+    let source_file = parse_fixture_with_args("recursive.h", &["--skip-forward-decls"]);
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    let is_recursive = |kind: &str, name_key: &str, name: &str| {
+        type_declares
+            .iter()
+            .find(|type_declare| type_declare[kind][name_key] == name)
+            .map(|type_declare| type_declare[kind]["is_recursive"].clone())
+            .unwrap()
+    };
+
+    assert_eq!(is_recursive("Struct", "struct_name", "node"), true);
+    assert_eq!(is_recursive("Struct", "struct_name", "tree"), true);
+    assert_eq!(is_recursive("Struct", "struct_name", "a"), true);
+    assert_eq!(is_recursive("Struct", "struct_name", "b"), true);
+    assert_eq!(is_recursive("Union", "union_name", "value"), true);
+    assert_eq!(is_recursive("Struct", "struct_name", "point"), false);
+
+    // A record nested in a field is flagged on its own, not the one holding it.
+    assert_eq!(is_recursive("Struct", "struct_name", "list"), false);
+    let list = type_declares
+        .iter()
+        .find(|type_declare| type_declare["Struct"]["struct_name"] == "list")
+        .unwrap();
+    assert_eq!(
+        list["Struct"]["fields"][0]["Struct"]["struct_name"],
+        "entry"
+    );
+    assert_eq!(list["Struct"]["fields"][0]["Struct"]["is_recursive"], true);
+
+    let source_file = parse_fixture_with_args(
+        "recursive.hpp",
+        &["--language", "c++", "--skip-forward-decls"],
+    );
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    let is_recursive = |name: &str| {
+        type_declares
+            .iter()
+            .find(|type_declare| type_declare["Class"]["class_name"] == name)
+            .map(|type_declare| type_declare["Class"]["is_recursive"].clone())
+            .unwrap()
+    };
+    assert_eq!(is_recursive("Node"), true);
+    assert_eq!(is_recursive("Child"), true);
+    assert_eq!(is_recursive("Parent"), true);
+    assert_eq!(is_recursive("Point"), false);
+}
+
+#[test]
+fn test_api_diff() {
+    let fixtures_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures");
+    let output = Command::new(env!("CARGO_BIN_EXE_test-clang-rust"))
+        .arg("diff")
+        .arg(fixtures_path.join("api.h"))
+        .arg(fixtures_path.join("api_v2.h"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "- function pick_color",
+            "+ function bounding_rect",
+            "~ function move_point",
+            "    old: void move_point(struct point *p, int dx, int dy)",
+            "    new: void move_point(struct point *p, long dx, long dy)",
+            "- type size",
+            "+ type rect",
+        ]
+    );
+}
+
+#[test]
+fn test_parallel_jobs() {
+    let fixtures_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures");
+    let run = |jobs: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_test-clang-rust"))
+            .args(["--format=json", "--jobs", jobs])
+            .arg(fixtures_path.join("api.h"))
+            .arg(fixtures_path.join("callbacks.h"))
+            .arg(fixtures_path.join("bitfield.h"))
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        output.stdout
+    };
+    assert_eq!(run("3"), run("1"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_test-clang-rust"))
+        .args(["--format=json", "--jobs", "2"])
+        .arg(fixtures_path.join("api.h"))
+        .arg(fixtures_path.join("missing.h"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn test_macro_function() {
+    let source_file = parse_fixture("macros.h");
+    let macro_definitions = source_file["macro_definitions"].as_array().unwrap();
+    assert_eq!(macro_definitions.len(), 1);
+    assert_eq!(macro_definitions[0]["name"], "VERSION");
+
+    let macro_functions = &source_file["macro_functions"];
+    assert_eq!(macro_functions[0]["name"], "MAX");
+    assert_eq!(
+        macro_functions[0]["parameter_names"],
+        serde_json::json!(["a", "b"])
+    );
+    assert_eq!(macro_functions[0]["body_tokens"][0], "(");
+    assert_eq!(macro_functions[1]["name"], "LOG");
+    assert_eq!(
+        macro_functions[1]["parameter_names"],
+        serde_json::json!(["format", "..."])
+    );
+    assert_eq!(macro_functions[2]["name"], "NOTHING");
+    assert_eq!(macro_functions[2]["parameter_names"], serde_json::json!([]));
+    assert_eq!(macro_functions[2]["body_tokens"], serde_json::json!(["0"]));
+}
+
+#[test]
+fn test_anonymous_member() {
+    let source_file = parse_fixture("anonymous_member.h");
+    let fields = &source_file["type_declares"][0]["Struct"]["fields"];
+
+    assert_eq!(fields[0]["Field"]["name"], "kind");
+    assert_eq!(fields[0]["Field"]["is_anonymous"], false);
+    assert_eq!(fields[1]["Struct"]["struct_name"], Value::Null);
+    assert_eq!(fields[2]["Field"]["name"], Value::Null);
+    assert_eq!(fields[2]["Field"]["is_anonymous"], true);
+    assert_eq!(fields[3]["Union"]["union_name"], Value::Null);
+    assert_eq!(fields[4]["Field"]["is_anonymous"], true);
+
+    assert_eq!(fields[5]["Field"]["name"], Value::Null);
+    assert_eq!(fields[5]["Field"]["is_anonymous"], false);
+    assert_eq!(fields[5]["Field"]["bit_width"], 4);
+    assert_eq!(fields[6]["Field"]["name"], "flags");
+
+    let bindings =
+        String::from_utf8(run_fixture("anonymous_member.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains("    pub x: i32,\n    pub y: i32,\n"));
+}
+
+#[test]
+fn test_markdown_format() {
+    let markdown = String::from_utf8(run_fixture("api.h", &["--format=markdown"])).unwrap();
+    let lines = markdown.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "| Function | Return type | Parameters |");
+    assert_eq!(lines[1], "| --- | --- | --- |");
+    assert_eq!(lines[2], "| `add` | `int` | `int a`, `int b` |");
+    assert_eq!(
+        lines[3],
+        "| `move_point` | `void` | [`struct point *p`](#point), `int dx`, `int dy` |"
+    );
+    assert_eq!(
+        lines[4],
+        "| `pick_color` | [`enum color`](#color) | `const char *name` |"
+    );
+    assert!(lines.contains(&"## Types"));
+    assert!(lines.contains(&"### point"));
+    assert!(lines.contains(&"- `int x`"));
+}
+
+#[test]
+fn test_enum_constant_comment() {
+    let source_file = parse_fixture("enum_comments.h");
+    let constants = &source_file["type_declares"][0]["Enum"]["constants"];
+    assert_eq!(constants[0]["comment"], "fire engine red");
+    assert_eq!(constants[1]["comment"], "grass green");
+    assert_eq!(constants[2]["comment"], "Sky blue.");
+    assert_eq!(constants[3]["comment"], Value::Null);
+    assert_eq!(constants[4]["comment"], Value::Null);
+
+    let source_file = parse_fixture_with_args("enum_comments.h", &["--parse-all-comments"]);
+    let constants = &source_file["type_declares"][0]["Enum"]["constants"];
+    assert_eq!(constants[3]["comment"], "plain comment");
+
+    let bindings = String::from_utf8(run_fixture("enum_comments.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains("    /// fire engine red\n    RED = 0,\n"));
+}
+
+#[test]
+fn test_tree() {
+    let tree = String::from_utf8(run_fixture(
+        "api.h",
+        &["--tree", "--tree-depth=1", "--tree-indent=2"],
+    ))
+    .unwrap();
+    let lines = tree.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("Entity { kind: TranslationUnit"));
+    assert!(lines[1..]
+        .iter()
+        .all(|line| line.starts_with("  Entity { kind: ")));
+    assert!(lines.iter().any(|line| line.contains("kind: FunctionDecl")));
+    assert!(!tree.contains("source_file"));
+}
+
+#[test]
+fn test_nested_records() {
+    let source_file = parse_fixture("nested_records.h");
+    let type_declares = &source_file["type_declares"];
+
+    let fields = &type_declares[0]["Struct"]["fields"];
+    assert_eq!(fields[0]["Struct"]["struct_name"], "inner");
+    assert_eq!(fields[0]["Struct"]["fields"][0]["Field"]["name"], "a");
+    assert_eq!(fields[1]["Field"]["name"], "inner");
+    assert_eq!(fields[2]["Field"]["name"], "b");
+
+    let fields = &type_declares[1]["Struct"]["fields"];
+    assert_eq!(fields[0]["Enum"]["enum_name"], "mode");
+    assert_eq!(fields[0]["Enum"]["constants"][0]["name"], "MODE_READ");
+    assert_eq!(
+        fields[0]["Enum"]["constants"][1]["constant_value"]["signed"],
+        4
+    );
+    assert_eq!(fields[1]["Field"]["name"], "mode");
+    assert_eq!(fields[2]["Field"]["name"], "value");
+
+    let fields = &type_declares[2]["Struct"]["fields"];
+    assert_eq!(fields[0]["Union"]["union_name"], "number");
+    assert_eq!(fields[1]["Field"]["name"], "number");
+
+    let fields = &type_declares[3]["Union"]["fields"];
+    assert_eq!(fields[0]["Struct"]["struct_name"], "pair");
+    assert_eq!(fields[0]["Struct"]["fields"][1]["Field"]["name"], "y");
+    assert_eq!(fields[1]["Field"]["name"], "pair");
+    assert_eq!(fields[2]["Field"]["name"], "raw");
+}
+
+#[test]
+fn test_export_list() {
+    let export_list_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("api.exports");
+    let source_file = parse_fixture_with_args(
+        "api.h",
+        &["--export-list", export_list_path.to_str().unwrap()],
+    );
+
+    let function_declares = source_file["function_declares"].as_array().unwrap();
+    assert_eq!(function_declares.len(), 1);
+    assert_eq!(function_declares[0]["function_name"], "add");
+
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    assert_eq!(type_declares.len(), 2);
+    assert_eq!(type_declares[0]["Struct"]["struct_name"], "point");
+    assert_eq!(type_declares[1]["Struct"]["typedef_name"], "size");
+}
+
+#[test]
+fn test_static_const_constants() {
+    let source_file = parse_fixture("constants.h");
+    let constants = source_file["constants"].as_array().unwrap();
+    assert_eq!(constants.len(), 3);
+    assert_eq!(constants[0]["name"], "MAX_SIZE");
+    assert_eq!(constants[0]["value"]["signed"], 1024);
+    assert_eq!(constants[1]["name"], "FLAGS_MASK");
+    assert_eq!(constants[1]["value"]["unsigned"], 0xff00);
+    assert_eq!(constants[2]["name"], "MIN_OFFSET");
+    assert_eq!(constants[2]["value"]["signed"], -(1i64 << 40));
+
+    let variable_declares = source_file["variable_declares"].as_array().unwrap();
+    assert_eq!(variable_declares.len(), 2);
+    assert_eq!(variable_declares[0]["name"], "NAME");
+    assert_eq!(variable_declares[1]["name"], "shared_limit");
+
+    let bindings = String::from_utf8(run_fixture("constants.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains("pub const MAX_SIZE: i32 = 1024;\n"));
+    assert!(bindings.contains("pub const FLAGS_MASK: u32 = 65280;\n"));
+    assert!(bindings.contains("pub const MIN_OFFSET: i64 = -1099511627776;\n"));
+}
+
+#[test]
+fn test_function_pointer_typedef() {
+    let source_file = parse_fixture("callbacks.h");
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+
+    let callback = &type_declares[0]["FunctionPointer"];
+    assert_eq!(callback["name"], "callback_t");
+    assert_eq!(callback["return_type"]["type_kind"], "Void");
+    assert_eq!(callback["parameter_types"][0]["type_kind"], "Int");
+    assert_eq!(callback["parameter_types"][1]["type_kind"], "Pointer");
+    assert_eq!(callback["is_variadic"], false);
+
+    let printf_like = &type_declares[1]["FunctionPointer"];
+    assert_eq!(printf_like["name"], "printf_like_t");
+    assert_eq!(printf_like["is_variadic"], true);
+
+    let bindings = String::from_utf8(run_fixture("callbacks.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains(
+        "pub type callback_t = Option<unsafe extern \"C\" fn(i32, *mut std::os::raw::c_void)>;\n"
+    ));
+    assert!(bindings.contains(
+        "pub type printf_like_t = Option<unsafe extern \"C\" fn(*const std::os::raw::c_char, ...) -> i32>;\n"
+    ));
+    assert!(bindings.contains("    pub on_event: callback_t,\n"));
+}
+
+#[test]
+fn test_enum_aliases() {
+    let bindings = String::from_utf8(run_fixture("enum_aliases.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains("pub struct status(pub i32);\n"));
+    assert!(bindings.contains("pub const E_OK: status = status(0);\n"));
+    assert!(bindings.contains("pub const E_SUCCESS: status = status(0);\n"));
+    assert!(bindings.contains("pub const E_FAIL: status = status(-1);\n"));
+    assert!(!bindings.contains("pub enum status"));
+}
+
+#[test]
+fn test_statistics() {
+    let statistics = String::from_utf8(run_fixture("api.h", &["--stats"])).unwrap();
+    assert_eq!(
+        statistics,
+        "Functions: 3, Parameters: 6, Types: 3, Structs: 2, Unions: 0, Enums: 1, Typedefs: 1, \
+         Fields: 4, Enum constants: 3\n"
+    );
+
+    let statistics =
+        String::from_utf8(run_fixture("api.h", &["--stats", "--function-prefix=add"])).unwrap();
+    assert!(statistics.starts_with("Functions: 1, Parameters: 2, "));
+}
+
+#[test]
+fn test_skip_system_headers() {
+    let source_file = parse_fixture_with_args("system_includes.h", &["--skip-system-headers"]);
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    assert_eq!(type_declares.len(), 1);
+    assert_eq!(type_declares[0]["Enum"]["enum_name"], "log_level");
+    assert_eq!(
+        source_file["function_declares"][0]["function_name"],
+        "log_message"
+    );
+
+    let source_file = parse_fixture("system_includes.h");
+    assert!(source_file["type_declares"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_flexible_array_member() {
+    let source_file = parse_fixture("flexible_array.h");
+    let fields = &source_file["type_declares"][0]["Struct"]["fields"];
+    assert_eq!(fields[0]["Field"]["is_flexible_array_member"], false);
+    assert_eq!(fields[1]["Field"]["is_flexible_array_member"], true);
+    assert_eq!(
+        source_file["type_declares"][0]["Struct"]["has_flexible_array_member"],
+        true
+    );
+
+    let bindings =
+        String::from_utf8(run_fixture("flexible_array.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains("    // Flexible array member."));
+    assert!(bindings.contains("    pub payload: [std::os::raw::c_uchar; 0],\n"));
+    assert!(bindings.contains(
+        "#[doc = \"This struct has a flexible array member `payload`, allocate it with \
+         `packet::alloc`.\"]\n#[repr(C)]\npub struct packet {\n"
+    ));
+    assert!(bindings.contains(
+        "        let size = std::mem::size_of::<Self>() + len * \
+         std::mem::size_of::<std::os::raw::c_uchar>();\n"
+    ));
+    assert!(bindings.contains("    pub fn alloc(len: usize) -> *mut Self {\n"));
+}
+
+#[test]
+fn test_include_guard() {
+    let source_file = parse_fixture("pragma_once.h");
+    assert_eq!(source_file["include_guard"], "PragmaOnce");
+}
+
+#[test]
+fn test_exception_specification() {
+    let source_file =
+        parse_fixture_with_args("exceptions.hpp", &["--language", "c++", "--std", "c++14"]);
+    let function_declares = source_file["function_declares"].as_array().unwrap();
+    assert_eq!(function_declares[0]["exception_specification"], "NoExcept");
+    assert_eq!(function_declares[1]["exception_specification"], "NoExcept");
+    assert_eq!(
+        function_declares[2]["exception_specification"]["ThrowList"],
+        serde_json::json!(["parse_error", "int"])
+    );
+    assert_eq!(
+        function_declares[3]["exception_specification"],
+        "DynamicExcept"
+    );
+    assert_eq!(
+        function_declares[4]["exception_specification"],
+        "ComputedNoexcept"
+    );
+    assert_eq!(function_declares[5]["exception_specification"], Value::Null);
+}
+
+#[test]
+fn test_zig_bindings() {
+    let bindings = String::from_utf8(run_fixture("api.h", &["--emit=zig"])).unwrap();
+    assert!(bindings.contains(
+        "pub const color = enum(c_uint) {\n    RED = 0,\n    GREEN = 1,\n    BLUE = 2,\n    _,\n};\n"
+    ));
+    assert!(bindings.contains("pub const RED = color.RED;\n"));
+    assert!(
+        bindings.contains("pub const point = extern struct {\n    x: c_int,\n    y: c_int,\n};\n")
+    );
+    assert!(bindings.contains("pub const size = extern struct {\n"));
+    assert!(bindings.contains("pub extern fn add(a: c_int, b: c_int) c_int;\n"));
+    assert!(
+        bindings.contains("pub extern fn move_point(p: [*c]point, dx: c_int, dy: c_int) void;\n")
+    );
+    assert!(bindings.contains("pub extern fn pick_color(name: [*c]const u8) color;\n"));
+
+    let bindings = String::from_utf8(run_fixture("bitfield.h", &["--emit=zig"])).unwrap();
+    assert!(bindings.contains(
+        "pub const packet_header = packed struct {\n    version: u3,\n    flags: u5,\n    \
+         padding_0: u24,\n    length: c_uint,\n};\n"
+    ));
+    assert!(bindings.contains(
+        "pub const file_mode = packed struct {\n    permissions: u12,\n    owner: u20,\n    \
+         padding_0: u32,\n    size: c_ulong,\n};\n"
+    ));
+    let bindings = String::from_utf8(run_fixture(
+        "bitfield.h",
+        &["--emit=zig", "--target", "x86_64-pc-windows-msvc"],
+    ))
+    .unwrap();
+    assert!(bindings.contains(
+        "pub const file_mode = packed struct {\n    permissions: u12,\n    owner: u20,\n    \
+         size: c_ulong,\n};\n"
+    ));
+
+    let bindings = String::from_utf8(run_fixture("callbacks.h", &["--emit=zig"])).unwrap();
+    assert!(bindings
+        .contains("pub const callback_t = ?*const fn (c_int, ?*anyopaque) callconv(.C) void;\n"));
+    assert!(bindings.contains(
+        "pub const printf_like_t = ?*const fn ([*c]const u8, ...) callconv(.C) c_int;\n"
+    ));
+    assert!(bindings.contains("    on_event: callback_t,\n    user_data: ?*anyopaque,\n"));
+}
+
+#[test]
+fn test_csharp_bindings() {
+    let bindings = String::from_utf8(run_fixture("api.h", &["--emit=csharp"])).unwrap();
+    assert!(bindings.starts_with("using System;\nusing System.Runtime.InteropServices;\n"));
+    assert!(bindings
+        .contains("public enum color : uint\n{\n    RED = 0,\n    GREEN = 1,\n    BLUE = 2,\n}\n"));
+    assert!(bindings.contains(
+        "[StructLayout(LayoutKind.Sequential)]\npublic struct point\n{\n    public int x;\n    \
+         public int y;\n}\n"
+    ));
+    assert!(bindings.contains("public struct size\n"));
+    assert!(bindings.contains("    private const string LibraryName = \"api\";\n"));
+    assert!(bindings.contains(
+        "    [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]\n    \
+         public static extern int add(int a, int b);\n"
+    ));
+    assert!(bindings.contains("public static extern void move_point(IntPtr p, int dx, int dy);\n"));
+    assert!(bindings.contains(
+        "public static extern color pick_color([MarshalAs(UnmanagedType.LPStr)] string name);\n"
+    ));
+
+    let bindings = String::from_utf8(run_fixture(
+        "bitfield.h",
+        &["--emit=csharp", "--target", "x86_64-pc-windows-msvc"],
+    ))
+    .unwrap();
+    assert!(bindings.contains(
+        "    // permissions: 12 bits, owner: 20 bits\n    public uint __bitfield_0;\n    \
+         public uint size;\n"
+    ));
+
+    let bindings = String::from_utf8(run_fixture(
+        "callbacks.h",
+        &["--emit=csharp", "--dll-name", "events"],
+    ))
+    .unwrap();
+    assert!(bindings.contains("    private const string LibraryName = \"events\";\n"));
+    assert!(bindings.contains(
+        "[UnmanagedFunctionPointer(CallingConvention.Cdecl)]\npublic delegate void \
+         callback_t(int arg0, IntPtr arg1);\n"
+    ));
+    assert!(bindings.contains("// Variadic function pointer printf_like_t is passed as IntPtr\n"));
+    assert!(bindings.contains(
+        "    [MarshalAs(UnmanagedType.FunctionPtr)]\n    public callback_t on_event;\n    \
+         public IntPtr user_data;\n"
+    ));
+    assert!(bindings.contains(
+        "public static extern void register_callback(IntPtr loop, \
+         [MarshalAs(UnmanagedType.FunctionPtr)] callback_t callback);\n"
+    ));
+}
+
+#[test]
+fn test_constexpr() {
+    let args = ["--language", "c++", "--std", "c++11"];
+    let source_file = parse_fixture_with_args("constexpr.hpp", &args);
+    let constexpr_declares = source_file["constexpr_declares"].as_array().unwrap();
+    assert_eq!(constexpr_declares.len(), 4);
+    assert_eq!(constexpr_declares[0]["name"], "VERSION");
+    assert_eq!(constexpr_declares[0]["value"]["Integer"], 42);
+    assert_eq!(constexpr_declares[2]["value"]["Float"], 2.5);
+    assert_eq!(constexpr_declares[3]["value"]["Bool"], true);
+    assert_eq!(source_file["constants"][0]["name"], "LIMIT");
+
+    let mut emit_args = vec!["--emit=rust-ffi"];
+    emit_args.extend_from_slice(&args);
+    let bindings = String::from_utf8(run_fixture("constexpr.hpp", &emit_args)).unwrap();
+    assert!(bindings.contains("pub const VERSION: i32 = 42;\n"));
+    assert!(bindings.contains("pub const MASK: u64 = 18446744073709551615;\n"));
+    assert!(bindings.contains("pub const RATIO: f64 = 2.5;\n"));
+    assert!(bindings.contains("pub const ENABLED: bool = true;\n"));
+}
+
+#[test]
+fn test_language_standard_flag() {
+    let source_file = parse_fixture_with_args("namespace.hpp", &["--language", "c++17"]);
+    assert_eq!(
+        source_file["namespace_declares"].as_array().unwrap().len(),
+        3
+    );
+    let source_file = parse_fixture("namespace.hpp");
+    assert_eq!(
+        source_file["namespace_declares"].as_array().unwrap().len(),
+        3
+    );
+}
+
+#[test]
+fn test_opaque_records() {
+    let source_file = parse_fixture("opaque.h");
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    assert_eq!(type_declares.len(), 3);
+    assert_eq!(type_declares[0]["Struct"]["struct_name"], "handle");
+    assert_eq!(type_declares[0]["Struct"]["is_opaque"], true);
+    assert_eq!(type_declares[1]["Union"]["union_name"], "value");
+    assert_eq!(type_declares[1]["Union"]["is_opaque"], true);
+    assert_eq!(type_declares[2]["Struct"]["struct_name"], "point");
+    assert_eq!(type_declares[2]["Struct"]["is_opaque"], false);
+
+    let bindings = String::from_utf8(run_fixture("opaque.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains("pub enum handle {} // opaque\n"));
+    assert!(bindings.contains("pub enum value {} // opaque\n"));
+    assert!(bindings.contains("pub struct point {\n"));
+    assert!(bindings.contains("    pub fn close_handle(handle: *mut handle);\n"));
+}
+
+#[test]
+fn test_noreturn_functions() {
+    let source_file = parse_fixture("noreturn.h");
+    let function_declares = source_file["function_declares"].as_array().unwrap();
+    assert_eq!(function_declares[0]["function_name"], "fatal");
+    assert_eq!(function_declares[0]["is_noreturn"], true);
+    assert_eq!(function_declares[1]["function_name"], "panic_now");
+    assert_eq!(function_declares[1]["is_noreturn"], true);
+    assert_eq!(function_declares[2]["function_name"], "status");
+    assert_eq!(function_declares[2]["is_noreturn"], false);
+
+    let bindings = String::from_utf8(run_fixture("noreturn.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains("    pub fn panic_now(code: i32) -> !;\n"));
+    assert!(bindings.contains("    pub fn status() -> i32;\n"));
+}
+
+#[test]
+fn test_bitflags_enums() {
+    let bindings =
+        String::from_utf8(run_fixture("flags.h", &["--emit=rust-ffi", "--bitflags"])).unwrap();
+    assert!(bindings.contains("bitflags::bitflags! {\n"));
+    assert!(bindings.contains("    pub struct permission: u32 {\n"));
+    assert!(bindings.contains("        const PERMISSION_ALL = 7;\n"));
+    assert!(bindings.contains("pub enum color {\n"));
+
+    let bindings = String::from_utf8(run_fixture("flags.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(!bindings.contains("bitflags"));
+}
+
+#[test]
+fn test_declspec() {
+    let target_args = ["--target", "x86_64-pc-windows-msvc"];
+    let source_file = parse_fixture_with_args("declspec.h", &target_args);
+    let function_declares = source_file["function_declares"].as_array().unwrap();
+    assert_eq!(function_declares[0]["declspec"], "DllImport");
+    assert_eq!(function_declares[1]["declspec"], "DllExport");
+    assert_eq!(function_declares[2]["declspec"], "NoInline");
+    assert_eq!(source_file["variable_declares"][0]["declspec"], "DllImport");
+
+    let mut args = vec!["--emit=rust-ffi", "--dll-name", "device"];
+    args.extend_from_slice(&target_args);
+    let bindings = String::from_utf8(run_fixture("declspec.h", &args)).unwrap();
+    assert!(bindings.contains("#[link(name = \"device\")]\nextern \"C\" {\n"));
+}
+
+#[test]
+fn test_packed_records() {
+    let source_file = parse_fixture("packed.h");
+    let type_declares = source_file["type_declares"].as_array().unwrap();
+    assert_eq!(type_declares[0]["Struct"]["is_packed"], true);
+    assert_eq!(type_declares[0]["Struct"]["size_bytes"], 5);
+    assert_eq!(type_declares[1]["Union"]["is_packed"], true);
+    assert_eq!(type_declares[2]["Struct"]["is_packed"], false);
+
+    let bindings = String::from_utf8(run_fixture("packed.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(
+        bindings.contains("#[repr(C, packed)]\n#[derive(Copy, Clone)]\npub struct wire_header {")
+    );
+    assert!(bindings.contains("#[repr(C, packed)]\n#[derive(Copy, Clone)]\npub union wire_value {"));
+    assert!(bindings.contains("#[repr(C)]\n#[derive(Copy, Clone)]\npub struct aligned_header {"));
+}
+
+#[test]
+fn test_typeof_is_resolved() {
+    let source_file = parse_fixture("typeof.h");
+    let shadow_counter = &source_file["variable_declares"][1];
+    assert_eq!(shadow_counter["name"], "shadow_counter");
+    assert_eq!(shadow_counter["variable_type"]["type_kind"], "Long");
+    assert_eq!(shadow_counter["variable_type"]["is_deduced"], false);
+
+    let next_counter = &source_file["function_declares"][0];
+    assert_eq!(next_counter["return_type"]["type_kind"], "Long");
+    assert_eq!(
+        next_counter["parameters"][0]["Named"]["parameter_type"]["pointee_type"]["type_kind"],
+        "Long"
+    );
+}
+
+#[test]
+fn test_sal_parameter_directions() {
+    let source_file = parse_fixture("sal.h");
+    let function_declares = source_file["function_declares"].as_array().unwrap();
+    let directions = function_declares
+        .iter()
+        .flat_map(|function_declare| function_declare["parameters"].as_array().unwrap())
+        .map(|parameter| parameter["Named"]["direction"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(directions[0], "In");
+    assert_eq!(directions[1], "Out");
+    assert_eq!(directions[2], "InOut");
+    assert_eq!(directions[3]["InReads"]["size_param"], "count");
+    assert_eq!(directions[4], Value::Null);
+}
+
+#[test]
+fn test_void_parameters() {
+    let source_file = parse_fixture("void_parameters.h");
+    let function_declares = source_file["function_declares"].as_array().unwrap();
+    assert_eq!(function_declares[0]["has_void_parameters"], true);
+    assert_eq!(function_declares[1]["has_void_parameters"], false);
+    assert_eq!(function_declares[2]["has_void_parameters"], false);
+}
+
+#[test]
+fn test_unnamed_parameters() {
+    let source_file = parse_fixture("unnamed_params.h");
+    let parameters = &source_file["function_declares"][0]["parameters"];
+    assert_eq!(parameters[0]["Named"]["name"], Value::Null);
+    assert_eq!(parameters[0]["Named"]["parameter_type"]["type_name"], "int");
+    assert_eq!(parameters[1]["Named"]["name"], "weight");
+
+    let bindings =
+        String::from_utf8(run_fixture("unnamed_params.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains("pub fn blend(arg0: i32, weight: i32, arg2: f64) -> i32;"));
+    assert!(bindings.contains("pub fn reset(arg0: *mut std::os::raw::c_void);"));
+}
+
+#[test]
+fn test_records_nested_in_union() {
+    let source_file = parse_fixture("nested_in_union.h");
+    let type_declares = &source_file["type_declares"];
+
+    let fields = &type_declares[0]["Union"]["fields"];
+    assert_eq!(fields[0]["Field"]["name"], "i");
+    assert_eq!(fields[1]["Field"]["name"], "f");
+
+    let fields = &type_declares[1]["Union"]["fields"];
+    assert_eq!(fields[0]["Struct"]["struct_name"], "rgb");
+    assert_eq!(fields[0]["Struct"]["fields"][2]["Field"]["name"], "b");
+    assert_eq!(fields[1]["Field"]["name"], "rgb");
+    assert_eq!(fields[2]["Field"]["name"], "packed");
+
+    let fields = &type_declares[2]["Union"]["fields"];
+    assert_eq!(fields[0]["Union"]["union_name"], "half");
+    assert_eq!(fields[0]["Union"]["fields"][1]["Field"]["name"], "u");
+    assert_eq!(fields[1]["Field"]["name"], "half");
+    assert_eq!(
+        fields[2]["Union"]["fields"][0]["Field"]["name"],
+        "signed_value"
+    );
+    assert_eq!(fields[3]["Field"]["is_anonymous"], true);
+
+    let fields = &type_declares[3]["Union"]["fields"];
+    assert_eq!(fields[0]["Enum"]["enum_name"], "shape");
+    assert_eq!(
+        fields[0]["Enum"]["constants"][1]["constant_value"]["signed"],
+        2
+    );
+    assert_eq!(fields[1]["Field"]["name"], "shape");
+    assert_eq!(fields[2]["Field"]["name"], "raw");
+}
+
+#[test]
+fn test_target_layout() {
+    let pointer_sizes = ["x86_64-unknown-linux-gnu", "i686-unknown-linux-gnu"]
+        .iter()
+        .map(|target_triple| {
+            let source_file =
+                parse_fixture_with_args("target_layout.h", &["--target", target_triple]);
+            let node = &source_file["type_declares"][0]["Struct"];
+            assert_eq!(
+                node["size_bytes"],
+                node["fields"][1]["Field"]["size_bytes"].as_u64().unwrap() * 2
+            );
+            node["fields"][1]["Field"]["size_bytes"].as_u64().unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(pointer_sizes, vec![8, 4]);
+
+    let layout = String::from_utf8(run_fixture(
+        "target_layout.h",
+        &["--layout", "--target", "i686-unknown-linux-gnu"],
+    ))
+    .unwrap();
+    assert!(layout.contains("struct node: size 8, align 4\n"));
+}
+
+#[test]
+fn test_query_functions_by_type() {
+    let output = String::from_utf8(run_fixture(
+        "file_api.h",
+        &["--query", "return-type=struct file *"],
+    ))
+    .unwrap();
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        [
+            "struct file *file_open(const char *path)",
+            "FILE_T *file_dup(FILE_T *f)"
+        ]
+    );
+
+    let output =
+        String::from_utf8(run_fixture("file_api.h", &["--query=param-type=FILE_T *"])).unwrap();
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        ["FILE_T *file_dup(FILE_T *f)"]
+    );
+}
+
+#[test]
+fn test_ownership_safety_comments() {
+    let bindings = String::from_utf8(run_fixture("ownership.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains(
+        "    // SAFETY: the caller owns the returned object and must release it with \
+         `buffer_free`.\n    pub fn buffer_new("
+    ));
+    assert!(bindings.contains(
+        "    // SAFETY: takes ownership of `buf`, which must not be used afterwards.\n    \
+         pub fn buffer_free("
+    ));
+    assert!(bindings.contains(
+        "    // SAFETY: `buf` must be valid for writes for the duration of the call.\n    \
+         pub fn buffer_append("
+    ));
+    assert!(bindings.contains(
+        "    // SAFETY: `buf` must be valid for reads for the duration of the call.\n    \
+         pub fn buffer_length("
+    ));
+    // Nothing to say about functions without pointers.
+    assert!(bindings.contains(";\n    pub fn version() -> i32;\n"));
+    assert!(bindings.contains(
+        "    // SAFETY: the pointers held by `v` must be valid for the duration of the call.\n    \
+         pub fn print_view(v: view);\n"
+    ));
+    // `warn_unused_result` makes up for a name that does not look like an allocator's.
+    assert!(bindings.contains(
+        "    // SAFETY: the caller owns the returned object and must release it with \
+         `buffer_free`.\n    pub fn buffer_clone("
+    ));
+
+    let source_file = parse_fixture("ownership.h");
+    let functions = source_file["function_declares"].as_array().unwrap();
+    // Names only count when classifying, with the naming conventions given there.
+    assert_eq!(functions[0]["caller_owns_return"], Value::Null);
+    assert_eq!(functions[1]["caller_owns_return"], false);
+    assert_eq!(functions[3]["caller_owns_return"], false);
+    assert_eq!(functions[6]["caller_owns_return"], true);
+}
+
+#[test]
+fn test_constant_macros() {
+    let bindings =
+        String::from_utf8(run_fixture("constant_macros.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings.contains("pub const MAX_SIZE: usize = 65536;\n"));
+    assert!(bindings.contains("pub const FLAGS_MASK: usize = 15;\n"));
+    assert!(bindings.contains("pub const NEGATIVE: i64 = -1;\n"));
+    assert!(bindings.contains("pub const ALL: u64 = 18446744073709551615;\n"));
+    assert!(bindings.contains("// could not evaluate: #define GREETING \"hello\"\n"));
+    assert!(!bindings.contains("CONSTANT_MACROS_H"));
+}
+
+#[test]
+fn test_module_prefix() {
+    let module_prefix = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("modules");
+    let bindings = String::from_utf8(run_fixture(
+        "modules/umbrella.h",
+        &[
+            "--emit=rust-ffi",
+            "--skip-system-headers",
+            "--module-prefix",
+            module_prefix.to_str().unwrap(),
+        ],
+    ))
+    .unwrap();
+    assert!(bindings.starts_with("extern \"C\" {\n    pub fn library_version() -> i32;\n}\n"));
+    assert!(bindings.contains("pub mod net {\n    #[allow(unused_imports)]\n    use super::*;\n"));
+    assert!(bindings.contains("    pub struct socket_address {\n        pub host: u32,\n"));
+    assert!(bindings.contains("    pub const NET_DEFAULT_PORT: usize = 443;\n"));
+    assert!(bindings.contains("        pub fn socket_connect("));
+    assert!(bindings.contains("    pub mod tls {\n"));
+    assert!(bindings.contains("            pub fn tls_handshake(socket: i32) -> i32;\n"));
+}
+
+#[test]
+fn test_output_dir() {
+    let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures");
+    let output_dir = std::env::temp_dir().join(format!(
+        "test_clang_rust_cli_output_dir_{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&output_dir);
+    run_fixture(
+        "modules/umbrella.h",
+        &[
+            "--emit=python-ctypes",
+            "--skip-system-headers",
+            "--split-by=file",
+            "--module-prefix",
+            fixtures_dir.join("modules").to_str().unwrap(),
+            "--output-dir",
+            output_dir.to_str().unwrap(),
+        ],
+    );
+    let init = std::fs::read_to_string(output_dir.join("__init__.py")).unwrap();
+    assert!(init.contains("from .net_socket import *\n"));
+    assert!(init.contains("from .net_tls_context import *\n"));
+    let context = std::fs::read_to_string(output_dir.join("net_tls_context.py")).unwrap();
+    assert!(context.contains("tls_handshake = _lib.tls_handshake\n"));
+    std::fs::remove_dir_all(&output_dir).unwrap();
+}
+
+#[test]
+fn test_block_pointers() {
+    // Blocks are only enabled by default for Apple targets.
+    let args = ["--target", "x86_64-apple-macosx10.15"];
+    let source_file = parse_fixture_with_args("blocks.h", &args);
+    let comparator = &source_file["type_declares"][0]["Typedef"]["underlying_type"];
+    assert_eq!(comparator["type_kind"], "BlockPointer");
+    assert_eq!(comparator["block_return_type"]["type_name"], "int");
+    assert_eq!(
+        comparator["block_parameter_types"][1]["type_name"],
+        "const void *"
+    );
+    let handler = &source_file["function_declares"][0]["parameters"][1]["Named"]["parameter_type"];
+    assert_eq!(handler["block_return_type"]["type_name"], "void");
+    assert_eq!(handler["block_parameter_types"], serde_json::json!([]));
+
+    let mut emit_args = vec!["--emit=rust-ffi"];
+    emit_args.extend_from_slice(&args);
+    let bindings = String::from_utf8(run_fixture("blocks.h", &emit_args)).unwrap();
+    assert!(bindings.contains(
+        "handler: /* block pointers cannot be represented in Rust: void (^)(void) */ \
+         *mut std::os::raw::c_void"
+    ));
+}
+
+#[test]
+fn test_array_parameters() {
+    let source_file = parse_fixture("array_params.h");
+    let functions = source_file["function_declares"].as_array().unwrap();
+    let is_array_parameter = |function_index: usize, parameter_index: usize| {
+        functions[function_index]["parameters"][parameter_index]["Named"]["is_array_parameter"]
+            .clone()
+    };
+    assert_eq!(is_array_parameter(0, 0), true);
+    assert_eq!(is_array_parameter(0, 1), false);
+    assert_eq!(is_array_parameter(1, 0), true);
+    assert_eq!(is_array_parameter(2, 0), true);
+    assert_eq!(is_array_parameter(3, 0), false);
+    assert_eq!(is_array_parameter(3, 1), false);
+    assert_eq!(is_array_parameter(4, 0), false);
+
+    let bindings = String::from_utf8(run_fixture("array_params.h", &["--emit=rust-ffi"])).unwrap();
+    assert!(bindings
+        .contains("    pub fn sum(values: *const i32, count: std::os::raw::c_ulong) -> i32;\n"));
+    assert!(bindings.contains(
+        "    pub fn fill(buffer: *mut std::os::raw::c_char, value: std::os::raw::c_char);\n"
+    ));
+    assert!(bindings.contains("    pub fn scale(vector: *mut f32, factor: f32);\n"));
+}
@@ -0,0 +1,49 @@
+//! Fixture factories shared by the integration tests that build declarations by hand.
+#![allow(dead_code)]
+
+use clang::TypeKind;
+use test_clang_rust::{
+    FieldDeclare, FunctionDeclare, MemberDeclare, SourceLocation, StructDeclare, Type, TypeDeclare,
+};
+
+pub fn int_type() -> Type {
+    Type::new(TypeKind::Int, "int".to_owned())
+}
+
+pub fn record_type(name: &str) -> Type {
+    Type::new(TypeKind::Record, format!("struct {}", name))
+}
+
+pub fn pointer_to(pointee_type: Type) -> Type {
+    let mut pointer_type = Type::new(TypeKind::Pointer, String::new());
+    pointer_type.pointee_type = Some(Box::new(pointee_type));
+    pointer_type
+}
+
+pub fn array_of(element_type: Type, array_size: usize) -> Type {
+    let mut array_type = Type::new(TypeKind::ConstantArray, String::new());
+    array_type.array_element_type = Some(Box::new(element_type));
+    array_type.array_size = Some(array_size);
+    array_type
+}
+
+/// A struct definition with one field of each type, named `field_0`, `field_1` and so on.
+pub fn struct_declare(name: &str, field_types: Vec<Type>) -> TypeDeclare {
+    let mut struct_declare = StructDeclare::new(Some(name.to_owned()), None);
+    struct_declare.is_definition = true;
+    for (index, field_type) in field_types.into_iter().enumerate() {
+        let mut field_declare = FieldDeclare::new(Some(format!("field_{}", index)));
+        field_declare.field_type = Some(field_type);
+        struct_declare
+            .fields
+            .push(MemberDeclare::Field(field_declare));
+    }
+    TypeDeclare::Struct(struct_declare)
+}
+
+/// A function declared at the start of the file at `path`, if one is given.
+pub fn function(name: &str, path: Option<&str>) -> FunctionDeclare {
+    let mut function_declare = FunctionDeclare::new(name.to_owned());
+    function_declare.location = path.map(|path| SourceLocation::new((path.to_owned(), 1, 1)));
+    function_declare
+}
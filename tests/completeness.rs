@@ -0,0 +1,71 @@
+mod common;
+
+use clang::TypeKind;
+use common::pointer_to;
+use test_clang_rust::{
+    completeness::{CompletenessChecker, UnresolvedTypeRef},
+    FieldDeclare, FunctionDeclare, FunctionParameter, MemberDeclare, ParameterDeclare, SourceFile,
+    StructDeclare, Type, TypeDeclare,
+};
+
+fn unresolved(entity_name: &str, type_name: &str) -> UnresolvedTypeRef {
+    UnresolvedTypeRef {
+        entity_name: entity_name.to_owned(),
+        type_name: type_name.to_owned(),
+    }
+}
+
+#[test]
+fn test_unresolved_types_are_reported() {
+    let mut buffer = StructDeclare::new(Some("buffer".to_owned()), None);
+    for (field_name, field_type) in [
+        (
+            "data",
+            pointer_to(Type::new(TypeKind::CharS, "char".to_owned())),
+        ),
+        ("len", Type::new(TypeKind::Typedef, "size_t".to_owned())),
+        (
+            "next",
+            pointer_to(Type::new(TypeKind::Elaborated, "struct buffer".to_owned())),
+        ),
+    ] {
+        let mut field_declare = FieldDeclare::new(Some(field_name.to_owned()));
+        field_declare.field_type = Some(field_type);
+        buffer.fields.push(MemberDeclare::Field(field_declare));
+    }
+
+    let mut write_buffer = FunctionDeclare::new("write_buffer".to_owned());
+    write_buffer.return_type = Some(Type::new(TypeKind::Typedef, "ssize_t".to_owned()));
+    for (parameter_name, parameter_type) in [
+        (
+            "file",
+            pointer_to(Type::new(TypeKind::Typedef, "FILE".to_owned())),
+        ),
+        (
+            "buffer",
+            pointer_to(Type::new(
+                TypeKind::Elaborated,
+                "const struct buffer".to_owned(),
+            )),
+        ),
+    ] {
+        let mut parameter_declare = ParameterDeclare::new(Some(parameter_name.to_owned()));
+        parameter_declare.parameter_type = Some(parameter_type);
+        write_buffer
+            .parameters
+            .push(FunctionParameter::Named(parameter_declare));
+    }
+
+    let mut source_file = SourceFile::new("buffer.h".to_owned());
+    source_file.type_declares.push(TypeDeclare::Struct(buffer));
+    source_file.function_declares.push(write_buffer);
+
+    assert_eq!(
+        CompletenessChecker::check(&source_file),
+        vec![
+            unresolved("buffer.len", "size_t"),
+            unresolved("write_buffer", "ssize_t"),
+            unresolved("write_buffer", "FILE"),
+        ]
+    );
+}
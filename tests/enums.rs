@@ -0,0 +1,93 @@
+use clang::TypeKind;
+use test_clang_rust::{EnumConstantDeclare, EnumConstantValue, EnumDeclare, Type};
+
+fn enum_declare(constants: &[(&str, i64)]) -> EnumDeclare {
+    let mut enum_declare = EnumDeclare::new(Some("status".to_owned()), None);
+    for &(name, value) in constants.iter() {
+        let mut constant = EnumConstantDeclare::new(name.to_owned());
+        constant.constant_value = Some(EnumConstantValue {
+            signed: value,
+            unsigned: value as u64,
+        });
+        enum_declare.constants.push(constant);
+    }
+    enum_declare
+}
+
+fn group_names(enum_declare: &EnumDeclare) -> Vec<Vec<&str>> {
+    enum_declare
+        .duplicate_value_groups()
+        .into_iter()
+        .map(|group| {
+            group
+                .into_iter()
+                .map(|constant| constant.name.as_str())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_enum_without_duplicates() {
+    let enum_declare = enum_declare(&[("RED", 0), ("GREEN", 1), ("BLUE", 2)]);
+    assert!(!enum_declare.has_duplicate_values());
+    assert!(enum_declare.duplicate_value_groups().is_empty());
+}
+
+#[test]
+fn test_enum_duplicate_value_groups() {
+    let enum_declare = enum_declare(&[
+        ("E_OK", 0),
+        ("E_FAIL", -1),
+        ("E_SUCCESS", 0),
+        ("E_AGAIN", 11),
+        ("E_ERROR", -1),
+        ("E_DONE", 0),
+    ]);
+    assert!(enum_declare.has_duplicate_values());
+    assert_eq!(
+        group_names(&enum_declare),
+        vec![
+            vec!["E_OK", "E_SUCCESS", "E_DONE"],
+            vec!["E_FAIL", "E_ERROR"]
+        ]
+    );
+}
+
+#[test]
+fn test_enum_is_flags() {
+    assert!(enum_declare(&[("READ", 1), ("WRITE", 2), ("EXEC", 4)]).is_flags());
+    assert!(enum_declare(&[
+        ("NONE", 0),
+        ("READ", 1),
+        ("WRITE", 2),
+        ("EXEC", 4),
+        ("ALL", 7)
+    ])
+    .is_flags());
+    assert!(!enum_declare(&[("RED", 0), ("GREEN", 1), ("BLUE", 2), ("ALPHA", 3)]).is_flags());
+    assert!(!enum_declare(&[("LOW", 1), ("HIGH", 2), ("ODD", 5)]).is_flags());
+
+    let mut open_flags = enum_declare(&[("O_RDONLY", 0), ("O_WRONLY", 1)]);
+    open_flags.enum_name = Some("open_flags".to_owned());
+    assert!(open_flags.is_flags());
+}
+
+#[test]
+fn test_enum_integer_type() {
+    let mut enum_declare = enum_declare(&[("OK", 0)]);
+    assert_eq!(enum_declare.integer_type_name(), None);
+    assert_eq!(enum_declare.rust_repr(), None);
+
+    let mut enum_type = Type::new(TypeKind::Typedef, "uint8_t".to_owned());
+    enum_type.canonical_type = Some(Box::new(Type::new(
+        TypeKind::UChar,
+        "unsigned char".to_owned(),
+    )));
+    enum_declare.enum_type = Some(enum_type);
+    assert_eq!(enum_declare.integer_type_name(), Some("unsigned char"));
+    assert_eq!(enum_declare.rust_repr(), Some("u8"));
+
+    enum_declare.enum_type = Some(Type::new(TypeKind::Int, "int".to_owned()));
+    assert_eq!(enum_declare.rust_repr(), Some("i32"));
+}
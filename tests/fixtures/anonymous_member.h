@@ -0,0 +1,13 @@
+struct event {
+    int kind;
+    struct {
+        int x;
+        int y;
+    };
+    union {
+        int code;
+        float value;
+    };
+    int : 4;
+    int flags : 4;
+};
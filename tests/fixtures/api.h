@@ -0,0 +1,15 @@
+enum color { RED, GREEN, BLUE };
+
+struct point {
+    int x;
+    int y;
+};
+
+typedef struct {
+    int width;
+    int height;
+} size;
+
+int add(int a, int b);
+void move_point(struct point *p, int dx, int dy);
+enum color pick_color(const char *name);
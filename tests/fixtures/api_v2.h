@@ -0,0 +1,15 @@
+enum color { RED, GREEN, BLUE };
+
+struct point {
+    int x;
+    int y;
+};
+
+struct rect {
+    struct point origin;
+    struct point extent;
+};
+
+int add(int lhs, int rhs);
+void move_point(struct point *p, long dx, long dy);
+struct rect bounding_rect(const struct point *points, int count);
@@ -0,0 +1,7 @@
+typedef float vec4[4];
+
+int sum(const int values[], unsigned long count);
+void fill(char buffer[16], char value);
+void scale(vec4 vector, float factor);
+void copy(const char *source, char *destination);
+void fill_rows(int (*rows)[4], int count);
@@ -0,0 +1,4 @@
+struct counter {
+    _Atomic int value;
+    int plain;
+};
@@ -0,0 +1,11 @@
+struct packet_header {
+    unsigned int version : 3;
+    unsigned int flags : 5;
+    unsigned int length;
+};
+
+struct file_mode {
+    unsigned long permissions : 12;
+    unsigned long owner : 20;
+    unsigned long size;
+};
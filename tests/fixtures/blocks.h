@@ -0,0 +1,3 @@
+typedef int (^comparator)(const void *left, const void *right);
+
+void dispatch_after_seconds(unsigned int seconds, void (^handler)(void));
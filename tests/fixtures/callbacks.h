@@ -0,0 +1,9 @@
+typedef void (*callback_t)(int, void *);
+typedef int (*printf_like_t)(const char *, ...);
+
+struct event_loop {
+    callback_t on_event;
+    void *user_data;
+};
+
+void register_callback(struct event_loop *loop, callback_t callback);
@@ -0,0 +1,2 @@
+int __attribute__((ms_abi)) win64_add(int a, int b);
+int cdecl_add(int a, int b);
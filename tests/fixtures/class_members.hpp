@@ -0,0 +1,28 @@
+class shape {
+public:
+    virtual ~shape();
+};
+
+class circle : public shape {
+public:
+    struct point {
+        double x;
+        double y;
+    };
+    enum fill { FILL_NONE, FILL_SOLID };
+    typedef double length_t;
+    using area_t = double;
+
+    static const int sides = 0;
+
+    ~circle() override;
+
+    template <typename T>
+    T scaled(T factor) const;
+
+    friend class canvas;
+
+private:
+    point center;
+    length_t radius;
+};
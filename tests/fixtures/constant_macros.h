@@ -0,0 +1,10 @@
+#ifndef CONSTANT_MACROS_H
+#define CONSTANT_MACROS_H
+
+#define MAX_SIZE (64 * 1024)
+#define FLAGS_MASK (0x0F | 010)
+#define NEGATIVE -1
+#define ALL 0xFFFFFFFFFFFFFFFFULL
+#define GREETING "hello"
+
+#endif
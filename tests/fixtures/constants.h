@@ -0,0 +1,6 @@
+static const int MAX_SIZE = 1024;
+static const unsigned int FLAGS_MASK = 0xff00u;
+static const long MIN_OFFSET = -(1L << 40);
+
+static const char *const NAME = "constants";
+extern const int shared_limit;
@@ -0,0 +1,5 @@
+constexpr int VERSION = 42;
+constexpr unsigned long long MASK = 0xffffffffffffffffULL;
+constexpr double RATIO = 2.5;
+constexpr bool ENABLED = true;
+static const int LIMIT = 16;
@@ -0,0 +1,4 @@
+__declspec(dllimport) int open_device(const char *name);
+__declspec(dllexport) void close_device(int device);
+__declspec(noinline) int poll_device(int device);
+__declspec(dllimport) extern int device_count;
@@ -0,0 +1,5 @@
+enum status {
+    E_OK = 0,
+    E_SUCCESS = 0,
+    E_FAIL = -1,
+};
@@ -0,0 +1,8 @@
+enum color {
+    RED = 0,    /**< fire engine red */
+    GREEN = 1,  ///< grass green
+    /** @brief Sky blue. */
+    BLUE = 2,
+    BLACK = 3,  /* plain comment */
+    WHITE = 4
+};
@@ -0,0 +1,8 @@
+struct parse_error {};
+
+void reset() noexcept;
+void legacy_reset() throw();
+void parse(const char *text) throw(parse_error, int);
+void load(const char *path) throw(...);
+void flush() noexcept(sizeof(int) == 4);
+void run();
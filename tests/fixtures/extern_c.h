@@ -0,0 +1,26 @@
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct buffer {
+    char *data;
+    unsigned long length;
+} buffer_t;
+
+buffer_t *buffer_new(unsigned long length);
+void buffer_free(buffer_t *buffer);
+
+#ifdef __cplusplus
+}
+
+static_assert(sizeof(buffer_t) > 0, "buffer_t is empty");
+using buffer_ref = buffer_t &;
+
+namespace io {
+using ::buffer_free;
+namespace detail {}
+using namespace detail;
+
+extern "C" int io_flush(buffer_t *buffer);
+}
+#endif
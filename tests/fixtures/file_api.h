@@ -0,0 +1,6 @@
+struct file;
+typedef struct file FILE_T;
+
+struct file *file_open(const char *path);
+FILE_T *file_dup(FILE_T *f);
+int file_close(struct file *f);
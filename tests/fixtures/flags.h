@@ -0,0 +1,12 @@
+enum permission {
+    PERMISSION_READ = 1,
+    PERMISSION_WRITE = 2,
+    PERMISSION_EXEC = 4,
+    PERMISSION_ALL = 7,
+};
+
+enum color {
+    RED,
+    GREEN,
+    BLUE,
+};
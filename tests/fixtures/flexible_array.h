@@ -0,0 +1,4 @@
+struct packet {
+    unsigned short length;
+    unsigned char payload[];
+};
@@ -0,0 +1,10 @@
+struct node;
+
+struct list {
+    struct node *head;
+};
+
+struct node {
+    int value;
+    struct node *next;
+};
@@ -0,0 +1,9 @@
+int regular(int value);
+
+inline int twice(int value) { return value * 2; }
+
+static inline int square(int value) { return value * value; }
+
+__attribute__((always_inline)) static inline int clamp(int value, int low, int high) {
+    return value < low ? low : value > high ? high : value;
+}
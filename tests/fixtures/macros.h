@@ -0,0 +1,4 @@
+#define VERSION 3
+#define MAX(a, b) ((a) > (b) ? (a) : (b))
+#define LOG(format, ...) log_message(format, __VA_ARGS__)
+#define NOTHING() 0
@@ -0,0 +1,6 @@
+union value {
+    int integer;
+    double real;
+    const char *text;
+    unsigned char bytes[8];
+};
@@ -0,0 +1,8 @@
+#define NET_DEFAULT_PORT 443
+
+struct socket_address {
+    unsigned int host;
+    unsigned short port;
+};
+
+int socket_connect(const struct socket_address *address);
@@ -0,0 +1 @@
+int tls_handshake(int socket);
@@ -0,0 +1,4 @@
+#include "net/socket.h"
+#include "net/tls/context.h"
+
+int library_version(void);
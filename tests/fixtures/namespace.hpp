@@ -0,0 +1,27 @@
+namespace outer {
+struct point {
+    int x;
+    int y;
+};
+
+struct link {
+    link *next;
+};
+
+int distance(point a, point b);
+
+constexpr int max_depth = 8;
+static const int min_depth = 1;
+
+namespace inner {
+extern int counter;
+}
+}
+
+namespace outer::detail {
+enum class mode { fast, slow };
+}
+
+namespace {
+void helper();
+}
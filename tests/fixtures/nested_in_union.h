@@ -0,0 +1,27 @@
+union plain {
+    int i;
+    float f;
+};
+
+union with_struct {
+    struct rgb {
+        unsigned char r, g, b;
+    } rgb;
+    unsigned int packed;
+};
+
+union with_union {
+    union half {
+        short s;
+        unsigned short u;
+    } half;
+    union {
+        int signed_value;
+        unsigned int unsigned_value;
+    };
+};
+
+union with_enum {
+    enum shape { SHAPE_CIRCLE, SHAPE_SQUARE = 2 } shape;
+    int raw;
+};
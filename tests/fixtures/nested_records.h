@@ -0,0 +1,26 @@
+struct outer {
+    struct inner {
+        int a;
+    } inner;
+    int b;
+};
+
+struct with_enum {
+    enum mode { MODE_READ, MODE_WRITE = 4 } mode;
+    int value;
+};
+
+struct with_union {
+    union number {
+        int i;
+        float f;
+    } number;
+};
+
+union with_struct {
+    struct pair {
+        int x;
+        int y;
+    } pair;
+    long raw;
+};
@@ -0,0 +1,3 @@
+_Noreturn void fatal(const char *message);
+__attribute__((noreturn)) void panic_now(int code);
+int status(void);
@@ -0,0 +1,10 @@
+struct handle;
+union value;
+
+struct point {
+    int x;
+    int y;
+};
+
+struct handle *open_handle(const char *path);
+void close_handle(struct handle *handle);
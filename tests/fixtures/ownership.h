@@ -0,0 +1,16 @@
+typedef struct buffer buffer;
+
+buffer *buffer_new(unsigned long capacity);
+void buffer_free(buffer *buf);
+int buffer_append(buffer *buf, const char *data, unsigned long length);
+unsigned long buffer_length(const buffer *buf);
+int version(void);
+
+struct view {
+    const char *data;
+    unsigned long length;
+};
+
+void print_view(struct view v);
+
+__attribute__((warn_unused_result)) buffer *buffer_clone(const buffer *buf);
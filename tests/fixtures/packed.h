@@ -0,0 +1,14 @@
+struct __attribute__((packed)) wire_header {
+    unsigned char kind;
+    unsigned int length;
+};
+
+union __attribute__((packed)) wire_value {
+    unsigned char byte;
+    unsigned int word;
+};
+
+struct aligned_header {
+    unsigned char kind;
+    unsigned int length;
+};
@@ -0,0 +1,2 @@
+int copy_buffer(const char *source, char *destination, unsigned long length);
+void free_list(void **items);
@@ -0,0 +1,34 @@
+struct node {
+    int value;
+    struct node *next;
+};
+
+typedef struct tree tree_t;
+struct tree {
+    tree_t *children[2];
+};
+
+struct a;
+struct b {
+    struct a *peer;
+};
+struct a {
+    struct b *peer;
+};
+
+union value {
+    long integer;
+    union value *boxed;
+};
+
+struct point {
+    int x;
+    int y;
+};
+
+struct list {
+    struct entry {
+        int value;
+        struct entry *next;
+    } head;
+};
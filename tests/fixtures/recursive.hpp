@@ -0,0 +1,18 @@
+class Node {
+public:
+    int value;
+    Node *next;
+};
+
+class Parent;
+class Child {
+    Parent *parent;
+};
+class Parent {
+    Child *children[4];
+};
+
+class Point {
+    int x;
+    int y;
+};
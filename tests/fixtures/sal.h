@@ -0,0 +1,7 @@
+#define _In_
+#define _Out_
+#define _Inout_
+#define _In_reads_(size)
+
+int read_config(_In_ const char *path, _Out_ int *value, _Inout_ int *flags);
+int checksum(_In_reads_(count) const unsigned char *data, unsigned long count);
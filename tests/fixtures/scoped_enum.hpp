@@ -0,0 +1,3 @@
+enum class Color : unsigned char { Red, Green, Blue };
+
+enum Direction { North, South };
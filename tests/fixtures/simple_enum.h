@@ -0,0 +1,5 @@
+enum log_level {
+    LOG_DEBUG,
+    LOG_INFO = 10,
+    LOG_ERROR = -1,
+};
@@ -0,0 +1,18 @@
+class celsius {
+public:
+    celsius();
+    celsius(const celsius &other);
+    celsius(celsius &&other);
+    celsius(double degrees);
+    explicit celsius(int degrees);
+    celsius(double degrees, int precision);
+    ~celsius() = default;
+
+    celsius &operator=(const celsius &other);
+
+    operator double() const;
+    explicit operator bool() const;
+
+private:
+    double degrees;
+};
@@ -0,0 +1,26 @@
+struct vec2 {
+    typedef float scalar_t;
+    using length_t = float;
+
+    vec2();
+    explicit vec2(scalar_t both);
+
+    scalar_t length() const;
+    static vec2 zero();
+
+    static const int dimensions = 2;
+
+    scalar_t x;
+
+private:
+    scalar_t y;
+
+    static_assert(sizeof(scalar_t) == 4, "scalar_t must be a float");
+};
+
+union number {
+    int as_int() const;
+
+    int i;
+    float f;
+};
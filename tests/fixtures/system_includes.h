@@ -0,0 +1,4 @@
+#include <stddef.h>
+#include "simple_enum.h"
+
+size_t log_message(enum log_level level, const char *message);
@@ -0,0 +1,13 @@
+template <typename T>
+T max_of(T a, T b);
+
+template <typename T, int N>
+struct fixed_array {
+    T items[N];
+    int size;
+};
+
+template <template <typename> class Container, typename T>
+class wrapper {
+    Container<T> inner;
+};
@@ -0,0 +1,8 @@
+typedef unsigned long size_type;
+
+typedef void *Handle;
+
+struct widget;
+typedef struct widget *widget_ref;
+
+typedef size_type length_type;
@@ -0,0 +1,5 @@
+typedef unsigned int handle_base_t;
+typedef handle_base_t handle_t;
+typedef handle_t window_handle_t;
+
+window_handle_t create_window(handle_t parent);
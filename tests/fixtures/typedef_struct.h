@@ -0,0 +1,4 @@
+typedef struct vec2 {
+    float x;
+    float y;
+} vec2_t;
@@ -0,0 +1,4 @@
+extern long counter;
+extern __typeof__(counter) shadow_counter;
+
+__typeof__(counter) next_counter(__typeof__(counter) *current);
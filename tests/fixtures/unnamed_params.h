@@ -0,0 +1,2 @@
+int blend(int, int weight, double);
+void reset(void *);
@@ -0,0 +1,3 @@
+int get_version(void);
+int get_legacy_version();
+int printf_like(const char *format, ...);
@@ -0,0 +1,56 @@
+use test_clang_rust::{codegen::CHeaderEmitter, IncludeGuard, SourceFile};
+
+#[test]
+fn test_pragma_once() {
+    let source = "/* Copyright */\n#pragma once\n\nint answer(void);\n";
+    assert_eq!(IncludeGuard::detect(source), Some(IncludeGuard::PragmaOnce));
+    assert_eq!(
+        IncludeGuard::detect("#  pragma   once // guard\n"),
+        Some(IncludeGuard::PragmaOnce)
+    );
+}
+
+#[test]
+fn test_macro_guard() {
+    let source = "/*\n * Licensed under MIT.\n */\n\n#ifndef API_H\n#define API_H\n\nint answer(void);\n\n#endif\n";
+    assert_eq!(
+        IncludeGuard::detect(source),
+        Some(IncludeGuard::MacroGuard {
+            macro_name: "API_H".to_owned()
+        })
+    );
+}
+
+#[test]
+fn test_no_guard() {
+    assert_eq!(IncludeGuard::detect("int answer(void);\n"), None);
+    // The macro tested and the macro defined have to match.
+    assert_eq!(
+        IncludeGuard::detect("#ifndef API_H\n#define API_H_\n#endif\n"),
+        None
+    );
+    // A guard has to come before any declaration.
+    assert_eq!(
+        IncludeGuard::detect("int answer(void);\n#ifndef API_H\n#define API_H\n#endif\n"),
+        None
+    );
+}
+
+#[test]
+fn test_emitted_guard() {
+    let mut source_file = SourceFile::new("api.h".to_owned());
+    assert!(CHeaderEmitter::new(&source_file)
+        .emit()
+        .starts_with("#ifndef API_H\n#define API_H\n"));
+
+    source_file.include_guard = Some(IncludeGuard::MacroGuard {
+        macro_name: "MY_API_H_INCLUDED".to_owned(),
+    });
+    let header = CHeaderEmitter::new(&source_file).emit();
+    assert!(header.starts_with("#ifndef MY_API_H_INCLUDED\n#define MY_API_H_INCLUDED\n"));
+    assert!(header.ends_with("#endif /* MY_API_H_INCLUDED */\n"));
+
+    source_file.include_guard = Some(IncludeGuard::PragmaOnce);
+    let header = CHeaderEmitter::new(&source_file).emit();
+    assert_eq!(header, "#pragma once\n\n");
+}
@@ -0,0 +1,173 @@
+use clang::TypeKind;
+use test_clang_rust::{
+    layout::LayoutReport, FieldDeclare, MemberDeclare, SourceFile, StructDeclare, Type,
+    TypeDeclare, UnionDeclare,
+};
+
+fn field(
+    name: &str,
+    type_kind: TypeKind,
+    type_name: &str,
+    bit_offset: u64,
+    size: u64,
+) -> MemberDeclare {
+    let mut field_declare = FieldDeclare::new(Some(name.to_owned()));
+    field_declare.field_type = Some(Type::new(type_kind, type_name.to_owned()));
+    field_declare.bit_offset = Some(bit_offset);
+    field_declare.byte_offset = Some(bit_offset / 8);
+    field_declare.size_bytes = Some(size);
+    MemberDeclare::Field(field_declare)
+}
+
+#[test]
+fn test_struct_layout_shows_padding() {
+    let mut header = StructDeclare::new(Some("header".to_owned()), None);
+    header.is_definition = true;
+    header.size_bytes = Some(16);
+    header.align_bytes = Some(8);
+    header.fields = vec![
+        field("tag", TypeKind::CharS, "char", 0, 1),
+        field("length", TypeKind::Long, "long", 64, 8),
+    ];
+    let source_file = SourceFile::new("header.h".to_owned());
+
+    assert_eq!(
+        LayoutReport::for_struct(&header, &source_file),
+        "struct header: size 16, align 8\n\
+         offset  size  name             type\n     \
+              0     1  tag              char\n     \
+              1     7  *** padding ***\n     \
+              8     8  length           long\n"
+    );
+}
+
+#[test]
+fn test_bit_fields_are_flagged() {
+    let mut flags = StructDeclare::new(Some("flags".to_owned()), None);
+    flags.is_definition = true;
+    flags.size_bytes = Some(4);
+    flags.align_bytes = Some(4);
+    for (name, bit_offset, bit_width) in [("readable", 0, 1), ("mode", 1, 3)] {
+        if let MemberDeclare::Field(mut field_declare) =
+            field(name, TypeKind::UInt, "unsigned int", bit_offset, 4)
+        {
+            field_declare.is_bit_field = true;
+            field_declare.bit_width = Some(bit_width);
+            flags.fields.push(MemberDeclare::Field(field_declare));
+        }
+    }
+    let report = LayoutReport::for_struct(&flags, &SourceFile::new("flags.h".to_owned()));
+    let lines = report.lines().collect::<Vec<_>>();
+
+    assert_eq!(
+        lines[2],
+        "   0.0  1 bits  readable         unsigned int (bit-field)"
+    );
+    assert_eq!(
+        lines[3],
+        "   0.1  3 bits  mode             unsigned int (bit-field)"
+    );
+    assert_eq!(lines[4], "     1       3  *** padding ***");
+}
+
+#[test]
+fn test_layout_report_covers_structs_and_unions() {
+    let mut value = UnionDeclare::new(Some("value".to_owned()), None);
+    value.is_definition = true;
+    value.size_bytes = Some(8);
+    value.align_bytes = Some(8);
+    value.fields = vec![
+        field("i", TypeKind::Int, "int", 0, 4),
+        field("d", TypeKind::Double, "double", 0, 8),
+    ];
+    let mut source_file = SourceFile::new("value.h".to_owned());
+    source_file.type_declares.push(TypeDeclare::Union(value));
+    source_file
+        .type_declares
+        .push(TypeDeclare::Struct(StructDeclare::new(
+            Some("handle".to_owned()),
+            None,
+        )));
+
+    assert_eq!(
+        source_file.layout_report(),
+        "union value: size 8, align 8\n\
+         offset  size  name  type\n     \
+              0     4  i     int\n     \
+              0     8  d     double\n"
+    );
+}
+
+#[test]
+fn test_all_fields_recursive() {
+    let anonymous_field = || {
+        let mut field_declare = FieldDeclare::new(None);
+        field_declare.is_anonymous = true;
+        MemberDeclare::Field(field_declare)
+    };
+    let mut inner_union = UnionDeclare::new(None, None);
+    inner_union
+        .fields
+        .push(field("code", TypeKind::Int, "int", 0, 4));
+    inner_union
+        .fields
+        .push(field("value", TypeKind::Float, "float", 0, 4));
+    let mut inner_struct = StructDeclare::new(None, None);
+    inner_struct
+        .fields
+        .push(field("x", TypeKind::Int, "int", 0, 4));
+    inner_struct.fields.push(MemberDeclare::Union(inner_union));
+    inner_struct.fields.push(anonymous_field());
+    let mut named_struct = StructDeclare::new(Some("position".to_owned()), None);
+    named_struct
+        .fields
+        .push(field("hidden", TypeKind::Int, "int", 0, 4));
+
+    let mut event = StructDeclare::new(Some("event".to_owned()), None);
+    event.fields.push(field("kind", TypeKind::Int, "int", 0, 4));
+    event.fields.push(MemberDeclare::Struct(inner_struct));
+    event.fields.push(anonymous_field());
+    event.fields.push(MemberDeclare::Struct(named_struct));
+    event.fields.push(field(
+        "position",
+        TypeKind::Record,
+        "struct position",
+        96,
+        4,
+    ));
+
+    let names = event
+        .all_fields_recursive()
+        .iter()
+        .map(|field_declare| field_declare.name.as_deref().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["kind", "x", "code", "value", "position"]);
+}
+
+#[test]
+fn test_builtin_field_sizes_follow_the_target() {
+    let mut entry = StructDeclare::new(Some("entry".to_owned()), None);
+    entry.is_definition = true;
+    entry.size_bytes = Some(12);
+    entry.align_bytes = Some(4);
+    for (name, type_kind, type_name, bit_offset) in [
+        ("key", TypeKind::Long, "long", 0),
+        ("value", TypeKind::Pointer, "void *", 32),
+    ] {
+        if let MemberDeclare::Field(mut field_declare) =
+            field(name, type_kind, type_name, bit_offset, 0)
+        {
+            // Declarations read back from JSON written by an older version have no sizes.
+            field_declare.size_bytes = None;
+            entry.fields.push(MemberDeclare::Field(field_declare));
+        }
+    }
+    let mut source_file = SourceFile::new("entry.h".to_owned());
+    source_file.target_triple = Some("i686-unknown-linux-gnu".to_owned());
+    let report = LayoutReport::for_struct(&entry, &source_file);
+    let lines = report.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines[2], "     0     4  key              long");
+    assert_eq!(lines[3], "     4     4  value            void *");
+    assert_eq!(lines[4], "     8     4  *** padding ***");
+}
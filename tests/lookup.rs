@@ -0,0 +1,142 @@
+mod common;
+
+use clang::TypeKind;
+use common::function;
+use test_clang_rust::{
+    FunctionDeclare, FunctionParameter, ParameterDeclare, SourceFile, StructDeclare, Type,
+    TypeDeclare, TypedefDeclare, VariadicParameterDeclare,
+};
+
+fn source_file() -> SourceFile {
+    let mut source_file = SourceFile::new("test.h".to_owned());
+    source_file.function_declares = vec![
+        function("buf_new", None),
+        function("buf_free", None),
+        function("str_len", None),
+    ];
+    let mut buf_definition = StructDeclare::new(Some("buf".to_owned()), None);
+    buf_definition.is_definition = true;
+    source_file.type_declares = vec![
+        TypeDeclare::Struct(StructDeclare::new(Some("buf".to_owned()), None)),
+        TypeDeclare::Struct(buf_definition),
+        TypeDeclare::Typedef(TypedefDeclare::new(
+            "buf_t".to_owned(),
+            Type::new(TypeKind::Record, "struct buf".to_owned()),
+        )),
+    ];
+    source_file
+}
+
+#[test]
+fn test_find_by_name() {
+    let source_file = source_file();
+    assert_eq!(
+        source_file
+            .find_function_by_name("buf_free")
+            .map(|function_declare| function_declare.function_name.as_str()),
+        Some("buf_free")
+    );
+    assert!(source_file.find_function_by_name("buf").is_none());
+
+    let buf = source_file.find_type_by_name("buf").unwrap();
+    assert!(buf.is_definition());
+    assert_eq!(
+        source_file.find_type_by_name("buf_t").unwrap().name(),
+        Some("buf_t")
+    );
+    assert!(source_file.find_type_by_name("str").is_none());
+}
+
+#[test]
+fn test_by_prefix() {
+    let source_file = source_file();
+    let function_names: Vec<&str> = source_file
+        .functions_by_prefix("buf_")
+        .map(|function_declare| function_declare.function_name.as_str())
+        .collect();
+    assert_eq!(function_names, vec!["buf_new", "buf_free"]);
+    assert_eq!(source_file.types_by_prefix("buf").count(), 3);
+    assert_eq!(source_file.types_by_prefix("buf_").count(), 1);
+}
+
+#[test]
+fn test_registry() {
+    let mut source_file = source_file();
+    if let TypeDeclare::Struct(buf_definition) = &mut source_file.type_declares[1] {
+        buf_definition.usr = Some("c:@S@buf".to_owned());
+    }
+    let registry = source_file.build_registry();
+    assert_eq!(registry.by_name.get("buf"), Some(&1));
+    assert_eq!(registry.by_name.get("buf_t"), Some(&2));
+    assert_eq!(registry.by_usr.get("c:@S@buf"), Some(&1));
+    assert!(registry
+        .lookup_by_usr(&source_file, "c:@S@buf")
+        .unwrap()
+        .is_definition());
+    assert!(registry.lookup_by_name(&source_file, "str").is_none());
+
+    assert_eq!(*source_file.registry(), registry);
+    source_file
+        .type_declares
+        .push(TypeDeclare::Struct(StructDeclare::new(
+            Some("str".to_owned()),
+            None,
+        )));
+    assert_eq!(source_file.registry().by_name.get("str"), Some(&3));
+}
+
+#[test]
+fn test_argument_by_name() {
+    let mut buf_append = FunctionDeclare::new("buf_append".to_owned());
+    buf_append.parameters = vec![
+        FunctionParameter::Named(ParameterDeclare::new(Some("buf".to_owned()))),
+        FunctionParameter::Named(ParameterDeclare::new(Some("format".to_owned()))),
+        FunctionParameter::Variadic(VariadicParameterDeclare),
+    ];
+    assert_eq!(
+        buf_append
+            .argument_by_name("format")
+            .and_then(|parameter_declare| parameter_declare.name.as_deref()),
+        Some("format")
+    );
+    assert!(buf_append.argument_by_name("len").is_none());
+    assert_eq!(buf_append.argument_count(), 2);
+    assert!(!buf_append.has_void_parameters());
+}
+
+#[test]
+fn test_functions_by_type() {
+    let mut source_file = source_file();
+    let mut buf_t_pointer = Type::new(TypeKind::Pointer, "buf_t *".to_owned());
+    buf_t_pointer.canonical_type = Some(Box::new(Type::new(
+        TypeKind::Pointer,
+        "struct buf *".to_owned(),
+    )));
+    source_file.function_declares[0].return_type = Some(buf_t_pointer);
+    let mut parameter_declare = ParameterDeclare::new(Some("buf".to_owned()));
+    parameter_declare.parameter_type =
+        Some(Type::new(TypeKind::Pointer, "struct buf *".to_owned()));
+    source_file.function_declares[1]
+        .parameters
+        .push(FunctionParameter::Named(parameter_declare));
+
+    let function_names = |function_declares: Vec<&FunctionDeclare>| {
+        function_declares
+            .into_iter()
+            .map(|function_declare| function_declare.function_name.to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        function_names(source_file.functions_returning_type("struct buf *")),
+        vec!["buf_new"]
+    );
+    assert_eq!(
+        function_names(source_file.functions_returning_type("buf_t *")),
+        vec!["buf_new"]
+    );
+    assert_eq!(
+        function_names(source_file.functions_taking_type("struct buf *")),
+        vec!["buf_free"]
+    );
+    assert!(source_file.functions_taking_type("buf_t *").is_empty());
+}
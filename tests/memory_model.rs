@@ -0,0 +1,165 @@
+mod common;
+
+use clang::TypeKind;
+use common::function;
+use test_clang_rust::{
+    memory_model::{FunctionOwnership, MemoryModel, OwnershipHints},
+    Attribute, FunctionDeclare, FunctionParameter, ParameterDeclare, ParameterDirection, Type,
+};
+
+fn pointer(pointee_name: &str, is_const: bool) -> Type {
+    let mut pointee_type = Type::new(TypeKind::Record, pointee_name.to_owned());
+    pointee_type.is_const = is_const;
+    let mut pointer_type = Type::new(TypeKind::Pointer, format!("{} *", pointee_name));
+    pointer_type.pointee_type = Some(Box::new(pointee_type));
+    pointer_type
+}
+
+fn function_with(name: &str, return_type: Type, parameters: Vec<(&str, Type)>) -> FunctionDeclare {
+    let mut function_declare = function(name, None);
+    function_declare.return_type = Some(return_type);
+    for (parameter_name, parameter_type) in parameters {
+        let mut parameter_declare = ParameterDeclare::new(Some(parameter_name.to_owned()));
+        parameter_declare.parameter_type = Some(parameter_type);
+        function_declare
+            .parameters
+            .push(FunctionParameter::Named(parameter_declare));
+    }
+    function_declare
+}
+
+fn void() -> Type {
+    Type::new(TypeKind::Void, "void".to_owned())
+}
+
+#[test]
+fn test_classify_ownership() {
+    let hints = OwnershipHints::default();
+    let create = function_with("create_session", pointer("session", false), vec![]);
+    let destroy = function_with(
+        "session_destroy",
+        void(),
+        vec![("s", pointer("session", false))],
+    );
+    let update = function_with(
+        "session_update",
+        void(),
+        vec![("s", pointer("session", false))],
+    );
+    let name = function_with(
+        "session_name",
+        void(),
+        vec![("s", pointer("session", true))],
+    );
+    let functions = vec![
+        function_with("free_buffer", void(), vec![("b", pointer("buffer", false))]),
+        destroy,
+    ];
+
+    assert_eq!(
+        MemoryModel::classify(&create, &hints),
+        FunctionOwnership::Allocator
+    );
+    assert_eq!(
+        MemoryModel::classify(&functions[1], &hints),
+        FunctionOwnership::Deallocator
+    );
+    assert_eq!(
+        MemoryModel::classify(&update, &hints),
+        FunctionOwnership::Transformer
+    );
+    assert_eq!(
+        MemoryModel::classify(&name, &hints),
+        FunctionOwnership::Observer
+    );
+    assert_eq!(
+        MemoryModel::matching_deallocator(&create, &functions, &hints)
+            .map(|function_declare| function_declare.function_name.as_str()),
+        Some("session_destroy")
+    );
+
+    // Named like an allocator, but nothing is returned to own.
+    let new_line = function_with("new_line", void(), vec![]);
+    assert_eq!(
+        MemoryModel::classify(&new_line, &hints),
+        FunctionOwnership::Observer
+    );
+
+    let mut annotated = function_with(
+        "session_read",
+        void(),
+        vec![("s", pointer("session", false))],
+    );
+    if let FunctionParameter::Named(parameter_declare) = &mut annotated.parameters[0] {
+        parameter_declare.direction = Some(ParameterDirection::In);
+    }
+    assert_eq!(
+        MemoryModel::classify(&annotated, &hints),
+        FunctionOwnership::Observer
+    );
+
+    let hints = OwnershipHints {
+        alloc_prefixes: vec!["make_".to_owned()],
+        ..OwnershipHints::default()
+    };
+    let make = function_with("make_session", pointer("session", false), vec![]);
+    assert_eq!(
+        MemoryModel::classify(&make, &hints),
+        FunctionOwnership::Allocator
+    );
+}
+
+#[test]
+fn test_return_type_is_caller_owns() {
+    let mut clone = function_with(
+        "session_clone",
+        pointer("session", false),
+        vec![("s", pointer("session", true))],
+    );
+    assert!(!clone.return_type_is_caller_owns());
+    clone.attributes.push(Attribute::WarnUnusedResult);
+    assert!(clone.return_type_is_caller_owns());
+
+    assert!(
+        function_with("session_new", pointer("session", false), vec![])
+            .return_type_is_caller_owns()
+    );
+    // Without a pointer there is nothing to free, whatever the name says.
+    assert!(!function_with("counter_new", void(), vec![]).return_type_is_caller_owns());
+
+    // What the declaration says is taken into account when classifying.
+    let hints = OwnershipHints::default();
+    let mut current = function_with("session_current", pointer("session", false), vec![]);
+    assert_eq!(
+        MemoryModel::classify(&current, &hints),
+        FunctionOwnership::Observer
+    );
+    current.caller_owns_return = Some(true);
+    assert_eq!(
+        MemoryModel::classify(&current, &hints),
+        FunctionOwnership::Allocator
+    );
+}
+
+#[test]
+fn test_custom_hints_replace_the_default_names() {
+    let hints = OwnershipHints {
+        alloc_prefixes: vec!["make_".to_owned()],
+        alloc_suffixes: Vec::new(),
+        ..OwnershipHints::default()
+    };
+    let create = function_with("create_session", pointer("session", false), vec![]);
+    let make = function_with("make_session", pointer("session", false), vec![]);
+    assert_eq!(
+        MemoryModel::classify(&create, &hints),
+        FunctionOwnership::Observer
+    );
+    assert_eq!(
+        MemoryModel::classify(&make, &hints),
+        FunctionOwnership::Allocator
+    );
+    assert_eq!(
+        MemoryModel::classify(&create, &OwnershipHints::default()),
+        FunctionOwnership::Allocator
+    );
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use common::function;
+use test_clang_rust::{
+    modules::module_name, ConstantDeclare, IncludeGuard, MacroDefinition, SourceFile,
+    SourceLocation,
+};
+
+fn function_names(source_file: &SourceFile) -> Vec<&str> {
+    source_file
+        .function_declares
+        .iter()
+        .map(|function_declare| function_declare.function_name.as_str())
+        .collect()
+}
+
+#[test]
+fn test_group_by_module() {
+    let mut source_file = SourceFile::new("/usr/include/openssl/ssl.h".to_owned());
+    source_file.function_declares.extend(vec![
+        function("EVP_DigestInit", Some("/usr/include/openssl/evp.h")),
+        function("SSL_new", Some("/usr/include/openssl/ssl.h")),
+        function("crypto_init", Some("/usr/include/openssl/crypto/init.h")),
+        function("strlen", Some("/usr/include/string.h")),
+        function("builtin", None),
+    ]);
+
+    let modules = source_file.group_by_module();
+    assert_eq!(modules.len(), 4);
+    assert_eq!(
+        function_names(&modules["usr/include/openssl"]),
+        vec!["EVP_DigestInit", "SSL_new"]
+    );
+    assert_eq!(function_names(&modules[""]), vec!["builtin"]);
+
+    let modules = source_file.group_by_module_with_prefix("/usr/include");
+    let mut module_paths = modules.keys().map(|key| key.as_str()).collect::<Vec<_>>();
+    module_paths.sort_unstable();
+    assert_eq!(module_paths, vec!["", "openssl", "openssl/crypto"]);
+    assert_eq!(function_names(&modules[""]), vec!["strlen", "builtin"]);
+    assert_eq!(
+        function_names(&modules["openssl/crypto"]),
+        vec!["crypto_init"]
+    );
+    assert_eq!(modules["openssl"].path, source_file.path);
+}
+
+#[test]
+fn test_group_by_file() {
+    let mut source_file = SourceFile::new("/usr/include/openssl/ssl.h".to_owned());
+    source_file.function_declares.extend(vec![
+        function("EVP_DigestInit", Some("/usr/include/openssl/evp.h")),
+        function("SSL_new", Some("/usr/include/openssl/ssl.h")),
+        function("SSL_free", Some("/usr/include/openssl/ssl.h")),
+    ]);
+
+    let files = source_file.group_by_file_with_prefix("/usr/include");
+    assert_eq!(files.len(), 2);
+    assert_eq!(
+        function_names(&files["openssl/evp"]),
+        vec!["EVP_DigestInit"]
+    );
+    assert_eq!(
+        function_names(&files["openssl/ssl"]),
+        vec!["SSL_new", "SSL_free"]
+    );
+}
+
+#[test]
+fn test_group_by_module_keeps_every_kind() {
+    let mut source_file = SourceFile::new("/usr/include/openssl/ssl.h".to_owned());
+    let mut constant = ConstantDeclare::new("SSL_MAX_BUFFER".to_owned());
+    constant.location = Some(SourceLocation::new((
+        "/usr/include/openssl/ssl.h".to_owned(),
+        3,
+        1,
+    )));
+    source_file.constants.push(constant);
+    let mut macro_definition = MacroDefinition::new("EVP_MAX_MD_SIZE".to_owned());
+    macro_definition.body_tokens = vec!["64".to_owned()];
+    macro_definition.location = Some(SourceLocation::new((
+        "/usr/include/openssl/evp.h".to_owned(),
+        5,
+        9,
+    )));
+    source_file.macro_definitions.push(macro_definition);
+    source_file
+        .macro_definitions
+        .push(MacroDefinition::new("__STDC__".to_owned()));
+    source_file.include_guard = Some(IncludeGuard::PragmaOnce);
+
+    let modules = source_file.group_by_module_with_prefix("/usr/include");
+    assert_eq!(modules.len(), 2);
+    assert_eq!(modules["openssl"].constants[0].name, "SSL_MAX_BUFFER");
+    assert_eq!(
+        modules["openssl"].macro_definitions[0].name,
+        "EVP_MAX_MD_SIZE"
+    );
+    assert_eq!(modules[""].macro_definitions[0].name, "__STDC__");
+    assert_eq!(modules[""].include_guard, Some(IncludeGuard::PragmaOnce));
+
+    let files = source_file.group_by_file_with_prefix("/usr/include");
+    assert_eq!(files["openssl/ssl"].constants.len(), 1);
+    assert_eq!(files["openssl/evp"].macro_definitions.len(), 1);
+}
+
+#[test]
+fn test_module_name() {
+    assert_eq!(module_name("net/my-lib"), "net_my_lib");
+    assert_eq!(module_name("3d"), "_3d");
+    assert_eq!(module_name("openssl"), "openssl");
+}
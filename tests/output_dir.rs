@@ -0,0 +1,108 @@
+mod common;
+
+use common::function;
+use std::{fs, path::PathBuf};
+use test_clang_rust::{
+    output_dir::{DirectoryWriter, OutputFormat, SplitBy},
+    MacroDefinition, SourceFile, SourceLocation,
+};
+
+fn source_file() -> SourceFile {
+    let mut source_file = SourceFile::new("/usr/include/mylib.h".to_owned());
+    source_file.function_declares.extend(vec![
+        function("mylib_init", Some("/usr/include/mylib.h")),
+        function("net_connect", Some("/usr/include/net/socket.h")),
+        function("tls_handshake", Some("/usr/include/net/tls.h")),
+    ]);
+    let mut macro_definition = MacroDefinition::new("NET_DEFAULT_PORT".to_owned());
+    macro_definition.body_tokens = vec!["443".to_owned()];
+    macro_definition.location = Some(SourceLocation::new((
+        "/usr/include/net/socket.h".to_owned(),
+        3,
+        9,
+    )));
+    source_file.macro_definitions.push(macro_definition);
+    source_file
+}
+
+/// A fresh directory under the system temp directory for one test.
+fn output_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "test_clang_rust_output_dir_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn file_names(dir: &PathBuf) -> Vec<String> {
+    let mut file_names = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    file_names.sort();
+    file_names
+}
+
+#[test]
+fn test_write_rust_modules() {
+    let dir = output_dir("rust");
+    source_file()
+        .write_to_dir(&dir, OutputFormat::RustFfi)
+        .unwrap();
+
+    assert_eq!(
+        file_names(&dir),
+        vec!["mod.rs", "usr_include.rs", "usr_include_net.rs"]
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("mod.rs")).unwrap(),
+        "pub mod usr_include;\npub use usr_include::*;\n\
+         pub mod usr_include_net;\npub use usr_include_net::*;\n"
+    );
+    let net = fs::read_to_string(dir.join("usr_include_net.rs")).unwrap();
+    assert!(net.starts_with("#[allow(unused_imports)]\nuse super::*;\n"));
+    assert!(net.contains("pub fn net_connect()"));
+    assert!(net.contains("pub fn tls_handshake()"));
+    assert!(net.contains("pub const NET_DEFAULT_PORT: "));
+    assert!(!net.contains("mylib_init"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_write_python_files() {
+    let dir = output_dir("python");
+    DirectoryWriter::new(&source_file(), OutputFormat::PythonCtypes)
+        .split_by(SplitBy::File)
+        .module_prefix("/usr/include".to_owned())
+        .library_name("mylib".to_owned())
+        .write(&dir)
+        .unwrap();
+
+    assert_eq!(
+        file_names(&dir),
+        vec!["__init__.py", "mylib.py", "net_socket.py", "net_tls.py"]
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("__init__.py")).unwrap(),
+        "from .mylib import *\nfrom .net_socket import *\nfrom .net_tls import *\n"
+    );
+    let socket = fs::read_to_string(dir.join("net_socket.py")).unwrap();
+    assert!(socket.contains("_lib = CDLL(find_library(\"mylib\") or \"mylib\")"));
+    assert!(socket.contains("net_connect = _lib.net_connect"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_write_single_markdown_file() {
+    let dir = output_dir("markdown");
+    DirectoryWriter::new(&source_file(), OutputFormat::Markdown)
+        .split_by(SplitBy::None)
+        .write(&dir)
+        .unwrap();
+
+    // Only Rust and Python output get an index file.
+    assert_eq!(file_names(&dir), vec!["mylib.md"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
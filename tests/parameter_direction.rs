@@ -0,0 +1,38 @@
+use test_clang_rust::ParameterDirection;
+
+fn tokens(source: &str) -> Vec<String> {
+    source
+        .split_whitespace()
+        .map(|token| token.to_owned())
+        .collect()
+}
+
+#[test]
+fn test_parse_parameter_direction() {
+    assert_eq!(
+        ParameterDirection::parse(&tokens("_In_ const char * name")),
+        Some(ParameterDirection::In)
+    );
+    assert_eq!(
+        ParameterDirection::parse(&tokens("_Out_opt_ int * result")),
+        Some(ParameterDirection::Out)
+    );
+    assert_eq!(
+        ParameterDirection::parse(&tokens("_Out_writes_ ( size ) char * buffer")),
+        Some(ParameterDirection::Out)
+    );
+    assert_eq!(
+        ParameterDirection::parse(&tokens("_Inout_ struct state * state")),
+        Some(ParameterDirection::InOut)
+    );
+    assert_eq!(
+        ParameterDirection::parse(&tokens("_In_reads_bytes_ ( count * 2 ) const void * data")),
+        Some(ParameterDirection::InReads {
+            size_param: "count*2".to_owned()
+        })
+    );
+    assert_eq!(
+        ParameterDirection::parse(&tokens("const char * name")),
+        None
+    );
+}
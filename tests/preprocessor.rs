@@ -0,0 +1,67 @@
+use test_clang_rust::MacroDefinition;
+
+fn evaluate(body: &str) -> Option<i64> {
+    let mut macro_definition = MacroDefinition::new("VALUE".to_owned());
+    macro_definition.body_tokens = body
+        .split_whitespace()
+        .map(|token| token.to_owned())
+        .collect();
+    macro_definition.evaluate()
+}
+
+fn evaluate_unsigned(body: &str) -> Option<u64> {
+    let mut macro_definition = MacroDefinition::new("VALUE".to_owned());
+    macro_definition.body_tokens = body
+        .split_whitespace()
+        .map(|token| token.to_owned())
+        .collect();
+    macro_definition.evaluate_unsigned()
+}
+
+#[test]
+fn test_evaluate_macro() {
+    assert_eq!(evaluate("( 64 * 1024 )"), Some(65536));
+    assert_eq!(evaluate("1 + 2 * 3 - 4 / 2"), Some(5));
+    assert_eq!(evaluate("( 1 + 2 ) * 3 % 4"), Some(1));
+    assert_eq!(evaluate("0x1F"), Some(31));
+    assert_eq!(evaluate("0X10 + 010"), Some(24));
+    assert_eq!(evaluate("0"), Some(0));
+    assert_eq!(evaluate("1u << 4 | 1UL << 2"), Some(20));
+    assert_eq!(evaluate("0xFF & ~ 0x0F ^ 0x01"), Some(0xF1));
+    assert_eq!(evaluate("256 >> 4"), Some(16));
+    assert_eq!(evaluate("- 1"), Some(-1));
+    assert_eq!(evaluate("! 0 && 2 > 1"), Some(1));
+    assert_eq!(evaluate("1 ? 2 : 3"), Some(2));
+    assert_eq!(evaluate("0xFFFFFFFFFFFFFFFF"), Some(-1));
+
+    assert_eq!(evaluate(""), None);
+    assert_eq!(evaluate("\"hello\""), None);
+    assert_eq!(evaluate("OTHER_MACRO * 2"), None);
+    assert_eq!(evaluate("( size_t ) 64"), None);
+    assert_eq!(evaluate("1 / 0"), None);
+    assert_eq!(evaluate("( 1 + 2"), None);
+    assert_eq!(evaluate("1 2"), None);
+    assert_eq!(evaluate("09"), None);
+    assert_eq!(evaluate("1.5"), None);
+}
+
+#[test]
+fn test_evaluate_unsigned_macro() {
+    assert_eq!(evaluate_unsigned("0xFFFFFFFFFFFFFFFFULL"), Some(u64::MAX));
+    assert_eq!(evaluate_unsigned("0xFFFFFFFFFFFFFFFF"), Some(u64::MAX));
+    assert_eq!(evaluate_unsigned("18446744073709551615"), Some(u64::MAX));
+    assert_eq!(evaluate_unsigned("1ULL << 63"), Some(1 << 63));
+    assert_eq!(evaluate_unsigned("~ 0u"), Some(u64::MAX));
+    assert_eq!(evaluate_unsigned("0u - 1"), Some(u64::MAX));
+    assert_eq!(evaluate_unsigned("( 64 * 1024 ) | 1U"), Some(65537));
+
+    assert_eq!(evaluate_unsigned("64"), None);
+    assert_eq!(evaluate_unsigned("- 1"), None);
+    assert_eq!(evaluate_unsigned("1u < 2"), None);
+    assert_eq!(evaluate_unsigned("1 << 4u"), None);
+    assert_eq!(evaluate_unsigned("1u / 0"), None);
+    assert_eq!(evaluate_unsigned("1ULL << 64"), None);
+
+    assert_eq!(evaluate("0xFFFFFFFFFFFFFFFFULL"), Some(-1));
+    assert_eq!(evaluate("- 1 < 0u"), Some(0));
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use clang::TypeKind;
+use common::{array_of, int_type, pointer_to};
+use test_clang_rust::{
+    printer::TypePrinter, FunctionDeclare, FunctionParameter, FunctionProtoInfo, ParameterDeclare,
+    Type, VariadicParameterDeclare,
+};
+
+fn function_returning(return_type: Type, parameter_types: Vec<Type>) -> Type {
+    let mut function_type = Type::new(TypeKind::FunctionPrototype, String::new());
+    function_type.function_proto = Some(FunctionProtoInfo {
+        return_type: Box::new(return_type),
+        parameter_types,
+    });
+    function_type
+}
+
+#[test]
+fn test_print_pointer_and_array() {
+    let char_type = Type::new(TypeKind::CharS, "char".to_owned());
+    assert_eq!(TypePrinter::new(&pointer_to(char_type)).print(), "char *");
+    assert_eq!(
+        TypePrinter::new(&array_of(pointer_to(int_type()), 4)).print(),
+        "int *[4]"
+    );
+    assert_eq!(
+        TypePrinter::new(&pointer_to(array_of(int_type(), 4))).print(),
+        "int (*)[4]"
+    );
+}
+
+#[test]
+fn test_print_function_pointer() {
+    let callback = pointer_to(function_returning(
+        int_type(),
+        vec![
+            pointer_to(Type::new(TypeKind::Void, "void".to_owned())),
+            int_type(),
+        ],
+    ));
+    assert_eq!(TypePrinter::new(&callback).print(), "int (*)(void *, int)");
+    assert_eq!(
+        TypePrinter::new(&callback).print_declaration("on_event"),
+        "int (*on_event)(void *, int)"
+    );
+    assert_eq!(
+        TypePrinter::new(&function_returning(int_type(), Vec::new())).print(),
+        "int(void)"
+    );
+}
+
+#[test]
+fn test_print_qualified_pointer() {
+    let mut const_char_type = Type::new(TypeKind::CharS, "const char".to_owned());
+    const_char_type.is_const = true;
+    let mut const_pointer = pointer_to(const_char_type);
+    const_pointer.is_const = true;
+    assert_eq!(
+        TypePrinter::new(&const_pointer).print(),
+        "const char *const"
+    );
+    assert_eq!(
+        TypePrinter::new(&const_pointer).print_declaration("name"),
+        "const char *const name"
+    );
+}
+
+#[test]
+fn test_function_signature_string() {
+    let parameter = |name: &str, parameter_type: Type| {
+        let mut parameter_declare = ParameterDeclare::new(Some(name.to_owned()));
+        parameter_declare.parameter_type = Some(parameter_type);
+        FunctionParameter::Named(parameter_declare)
+    };
+    let char_type = Type::new(TypeKind::CharS, "char".to_owned());
+
+    let mut log_message = FunctionDeclare::new("log_message".to_owned());
+    log_message.return_type = Some(Type::new(TypeKind::Void, "void".to_owned()));
+    log_message.parameters = vec![
+        parameter("level", int_type()),
+        parameter("format", pointer_to(char_type)),
+        FunctionParameter::Variadic(VariadicParameterDeclare),
+    ];
+    assert_eq!(
+        log_message.signature_string(),
+        "void log_message(int level, char *format, ...)"
+    );
+
+    let mut get_handler = FunctionDeclare::new("get_handler".to_owned());
+    get_handler.return_type = Some(pointer_to(function_returning(int_type(), vec![int_type()])));
+    assert_eq!(
+        get_handler.signature_string(),
+        "int (*get_handler(void))(int)"
+    );
+
+    let mut unnamed_parameter = ParameterDeclare::new(None);
+    unnamed_parameter.parameter_type = Some(int_type());
+    let mut set_level = FunctionDeclare::new("set_level".to_owned());
+    set_level.return_type = Some(Type::new(TypeKind::Void, "void".to_owned()));
+    set_level.parameters = vec![FunctionParameter::Named(unnamed_parameter)];
+    assert_eq!(set_level.signature_string(), "void set_level(int)");
+}
+
+#[test]
+fn test_print_elaborated_type() {
+    let mut elaborated_type = Type::new(TypeKind::Elaborated, "const config".to_owned());
+    elaborated_type.is_const = true;
+    elaborated_type.elaborated_inner = Some(Box::new(Type::new(
+        TypeKind::Record,
+        "settings::config".to_owned(),
+    )));
+    assert_eq!(
+        elaborated_type
+            .elaborated_inner()
+            .map(|t| t.type_name.as_str()),
+        Some("settings::config")
+    );
+    assert_eq!(
+        TypePrinter::new(&pointer_to(elaborated_type)).print_declaration("current"),
+        "const settings::config *current"
+    );
+}
@@ -0,0 +1,96 @@
+use std::{collections::BTreeSet, env, fs, path::Path};
+use test_clang_rust::{
+    codegen::CHeaderEmitter, parse_file, EntityVisitor, ParseOptions, SourceFile, TypeDeclaration,
+};
+
+fn function_names(source_file: &SourceFile) -> BTreeSet<String> {
+    source_file
+        .function_declares
+        .iter()
+        .map(|function_declare| function_declare.function_name.to_owned())
+        .collect()
+}
+
+fn type_names(source_file: &SourceFile) -> BTreeSet<String> {
+    source_file
+        .type_declares
+        .iter()
+        .flat_map(|type_declare| {
+            type_declare
+                .name()
+                .into_iter()
+                .chain(type_declare.typedef_name())
+        })
+        .map(|name| name.to_owned())
+        .collect()
+}
+
+#[test]
+fn test_c_header_round_trip() {
+    for fixture_name in [
+        "api.h",
+        "bitfield.h",
+        "typedef_alias.h",
+        "recursive.h",
+        "mixed_union.h",
+        "nested_records.h",
+        "callbacks.h",
+    ]
+    .iter()
+    {
+        let fixture_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(fixture_name);
+        let source_file = parse_file(&fixture_path, ParseOptions::default()).unwrap();
+
+        let emitted_path = env::temp_dir().join(format!("round_trip_{}", fixture_name));
+        fs::write(&emitted_path, CHeaderEmitter::new(&source_file).emit()).unwrap();
+        let reparsed_source_file = parse_file(&emitted_path, ParseOptions::default()).unwrap();
+        fs::remove_file(&emitted_path).unwrap();
+
+        assert_eq!(
+            function_names(&source_file),
+            function_names(&reparsed_source_file),
+            "{}",
+            fixture_name
+        );
+        assert_eq!(
+            type_names(&source_file),
+            type_names(&reparsed_source_file),
+            "{}",
+            fixture_name
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_round_trip() {
+    for fixture_name in [
+        "api.h",
+        "bitfield.h",
+        "callbacks.h",
+        "constants.h",
+        "constexpr.hpp",
+        "enum_comments.h",
+        "forward_decl.h",
+        "macros.h",
+        "namespace.hpp",
+        "nested_records.h",
+        "templates.hpp",
+    ]
+    .iter()
+    {
+        let fixture_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(fixture_name);
+        let source_file = parse_file(&fixture_path, ParseOptions::default()).unwrap();
+
+        let json = serde_json::to_string(&source_file).unwrap();
+        let deserialized_source_file: SourceFile = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(source_file, deserialized_source_file, "{}", fixture_name);
+    }
+}
@@ -0,0 +1,32 @@
+use std::path::Path;
+use test_clang_rust::{parse_file, ParseOptions, SourceFile};
+
+fn parse_fixture(name: &str) -> SourceFile {
+    let fixture_path = Path::new("tests").join("fixtures").join(name);
+    parse_file(&fixture_path, ParseOptions::default()).unwrap()
+}
+
+#[test]
+fn test_simple_enum_snapshot() {
+    insta::assert_debug_snapshot!(parse_fixture("simple_enum.h"));
+}
+
+#[test]
+fn test_typedef_struct_snapshot() {
+    insta::assert_debug_snapshot!(parse_fixture("typedef_struct.h"));
+}
+
+#[test]
+fn test_pointer_params_snapshot() {
+    insta::assert_debug_snapshot!(parse_fixture("pointer_params.h"));
+}
+
+#[test]
+fn test_mixed_union_snapshot() {
+    insta::assert_debug_snapshot!(parse_fixture("mixed_union.h"));
+}
+
+#[test]
+fn test_typedef_chain_snapshot() {
+    insta::assert_debug_snapshot!(parse_fixture("typedef_chain.h"));
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use clang::TypeKind;
+use common::{pointer_to, record_type, struct_declare};
+use test_clang_rust::{EntityVisitor, SourceFile, StructDeclare, Type, TypeDeclare};
+
+fn sorted_names(source_file: &SourceFile) -> Vec<&str> {
+    source_file
+        .topological_sort_types()
+        .unwrap()
+        .into_iter()
+        .map(|type_declare| type_declare.name().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_value_fields_come_first() {
+    let mut source_file = SourceFile::new("test.h".to_owned());
+    source_file.type_declares = vec![
+        struct_declare("line", vec![record_type("point"), record_type("point")]),
+        struct_declare("point", vec![Type::new(TypeKind::Int, "int".to_owned())]),
+        struct_declare("shape", vec![record_type("line")]),
+    ];
+    assert_eq!(sorted_names(&source_file), vec!["point", "line", "shape"]);
+}
+
+#[test]
+fn test_pointer_fields_keep_declaration_order() {
+    let mut source_file = SourceFile::new("test.h".to_owned());
+    source_file.type_declares = vec![
+        struct_declare("list", vec![pointer_to(record_type("node"))]),
+        struct_declare(
+            "node",
+            vec![
+                pointer_to(record_type("node")),
+                pointer_to(record_type("list")),
+            ],
+        ),
+    ];
+    assert_eq!(sorted_names(&source_file), vec!["list", "node"]);
+}
+
+#[test]
+fn test_value_cycle_is_reported() {
+    let mut source_file = SourceFile::new("test.h".to_owned());
+    source_file.type_declares = vec![
+        struct_declare("a", vec![record_type("b")]),
+        struct_declare("b", vec![pointer_to(record_type("c")), record_type("a")]),
+        struct_declare("c", vec![record_type("b")]),
+    ];
+    let cycle_error = source_file.topological_sort_types().unwrap_err();
+    assert_eq!(cycle_error.type_names, vec!["a", "b", "a"]);
+}
+
+fn dependency_ordered(source_file: &SourceFile) -> Vec<(&str, bool)> {
+    source_file
+        .dependency_ordered_types()
+        .map(|type_declare| (type_declare.name().unwrap(), type_declare.is_definition()))
+        .collect()
+}
+
+#[test]
+fn test_dependency_order_puts_value_fields_first() {
+    let mut source_file = SourceFile::new("test.h".to_owned());
+    source_file.type_declares = vec![
+        struct_declare("line", vec![record_type("point"), record_type("point")]),
+        struct_declare("shape", vec![record_type("line")]),
+        struct_declare("point", vec![Type::new(TypeKind::Int, "int".to_owned())]),
+        struct_declare("color", vec![Type::new(TypeKind::Int, "int".to_owned())]),
+    ];
+    assert_eq!(
+        dependency_ordered(&source_file),
+        vec![
+            ("point", true),
+            ("line", true),
+            ("shape", true),
+            ("color", true)
+        ]
+    );
+}
+
+#[test]
+fn test_dependency_order_stubs_pointer_cycles() {
+    let mut source_file = SourceFile::new("test.h".to_owned());
+    source_file.type_declares = vec![
+        struct_declare("list", vec![pointer_to(record_type("node"))]),
+        struct_declare(
+            "node",
+            vec![
+                pointer_to(record_type("node")),
+                pointer_to(record_type("list")),
+            ],
+        ),
+        TypeDeclare::Struct(StructDeclare::new(Some("node".to_owned()), None)),
+        TypeDeclare::Struct(StructDeclare::new(Some("list".to_owned()), None)),
+    ];
+    assert_eq!(
+        dependency_ordered(&source_file),
+        vec![("node", false), ("list", true), ("node", true)]
+    );
+}
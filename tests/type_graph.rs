@@ -0,0 +1,80 @@
+mod common;
+
+use clang::TypeKind;
+use common::{pointer_to, record_type, struct_declare};
+use std::collections::HashSet;
+use test_clang_rust::{graph::TypeGraph, SourceFile, Type, TypeDeclare, TypedefDeclare};
+
+fn names(names: &[&str]) -> HashSet<String> {
+    names.iter().map(|&name| name.to_owned()).collect()
+}
+
+fn shapes() -> SourceFile {
+    let point_t = TypedefDeclare::new("point_t".to_owned(), record_type("point"));
+    let mut source_file = SourceFile::new("shapes.h".to_owned());
+    source_file.type_declares = vec![
+        struct_declare("point", vec![Type::new(TypeKind::Int, "int".to_owned())]),
+        TypeDeclare::Typedef(point_t),
+        struct_declare(
+            "line",
+            vec![
+                Type::new(TypeKind::Typedef, "point_t".to_owned()),
+                pointer_to(record_type("style")),
+            ],
+        ),
+        struct_declare("style", vec![pointer_to(record_type("style"))]),
+        struct_declare("shape", vec![record_type("line")]),
+    ];
+    source_file
+}
+
+#[test]
+fn test_edges() {
+    let type_graph = TypeGraph::new(&shapes());
+    assert_eq!(type_graph.value_edges["line"], vec!["point_t"]);
+    assert_eq!(type_graph.pointer_edges["line"], vec!["style"]);
+    assert_eq!(type_graph.value_edges["point_t"], vec!["point"]);
+    assert!(type_graph.value_edges["style"].is_empty());
+    assert!(type_graph.contains("shape"));
+    assert!(!type_graph.contains("color"));
+}
+
+#[test]
+fn test_reachable_from() {
+    let type_graph = TypeGraph::new(&shapes());
+    assert_eq!(
+        type_graph.reachable_from("shape"),
+        names(&["line", "point_t", "point", "style"])
+    );
+    assert_eq!(type_graph.reachable_from("style"), names(&["style"]));
+    assert!(type_graph.reachable_from("point").is_empty());
+}
+
+#[test]
+fn test_dependents_of() {
+    let type_graph = TypeGraph::new(&shapes());
+    assert_eq!(
+        type_graph.dependents_of("point"),
+        names(&["point_t", "line", "shape"])
+    );
+    assert_eq!(
+        type_graph.dependents_of("style"),
+        names(&["style", "line", "shape"])
+    );
+    assert!(type_graph.dependents_of("shape").is_empty());
+}
+
+#[test]
+fn test_value_cycles() {
+    assert!(TypeGraph::new(&shapes()).is_cycle_free());
+
+    let mut source_file = SourceFile::new("test.h".to_owned());
+    source_file.type_declares = vec![
+        struct_declare("a", vec![pointer_to(record_type("b"))]),
+        struct_declare("b", vec![record_type("c")]),
+        struct_declare("c", vec![record_type("b")]),
+    ];
+    let type_graph = TypeGraph::new(&source_file);
+    assert!(!type_graph.is_cycle_free());
+    assert_eq!(type_graph.value_cycle().unwrap(), vec!["b", "c", "b"]);
+}
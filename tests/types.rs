@@ -0,0 +1,207 @@
+mod common;
+
+use clang::TypeKind;
+use common::{array_of, int_type, pointer_to};
+use std::path::Path;
+use test_clang_rust::{
+    ClassDeclare, ConstructorDeclare, ConstructorKind, ConversionFunctionDeclare, FieldDeclare,
+    FunctionParameter, FunctionProtoInfo, LanguageStandard, MemberDeclare, ParameterDeclare,
+    SourceFile, StructDeclare, Type, TypeDeclare,
+};
+
+#[test]
+fn test_pointer_depth_of_plain_types() {
+    assert_eq!(int_type().pointer_depth(), 0);
+    assert_eq!(int_type().base_type().type_kind, TypeKind::Int);
+
+    let void_type = Type::new(TypeKind::Void, "void".to_owned());
+    let void_pointer_pointer = pointer_to(pointer_to(void_type));
+    assert_eq!(void_pointer_pointer.pointer_depth(), 2);
+    assert_eq!(void_pointer_pointer.base_type().type_kind, TypeKind::Void);
+}
+
+#[test]
+fn test_pointer_depth_with_arrays() {
+    let pointer_to_array = pointer_to(array_of(int_type(), 4));
+    assert_eq!(pointer_to_array.pointer_depth(), 1);
+    let base_type = pointer_to_array.base_type();
+    assert_eq!(base_type.type_kind, TypeKind::ConstantArray);
+    assert_eq!(base_type.array_size, Some(4));
+
+    let array_of_pointers = array_of(pointer_to(int_type()), 4);
+    assert_eq!(array_of_pointers.pointer_depth(), 0);
+    assert_eq!(
+        array_of_pointers.base_type().type_kind,
+        TypeKind::ConstantArray
+    );
+
+    let pointer_to_array_of_pointers = pointer_to(array_of(pointer_to(int_type()), 2));
+    assert_eq!(pointer_to_array_of_pointers.pointer_depth(), 1);
+}
+
+#[test]
+fn test_pointer_depth_of_function_pointer() {
+    let mut function_type = Type::new(TypeKind::FunctionPrototype, String::new());
+    function_type.function_proto = Some(FunctionProtoInfo {
+        return_type: Box::new(pointer_to(int_type())),
+        parameter_types: Vec::new(),
+    });
+    let function_pointer = pointer_to(function_type);
+    assert_eq!(function_pointer.pointer_depth(), 1);
+    assert_eq!(
+        function_pointer.base_type().type_kind,
+        TypeKind::FunctionPrototype
+    );
+}
+
+#[test]
+fn test_kind_predicates() {
+    let void_type = Type::new(TypeKind::Void, "void".to_owned());
+    assert!(void_type.is_void());
+    assert!(!void_type.is_primitive());
+
+    assert!(int_type().is_primitive());
+    assert!(Type::new(TypeKind::Double, "double".to_owned()).is_primitive());
+    assert!(!int_type().is_pointer());
+
+    let int_pointer = pointer_to(int_type());
+    assert!(int_pointer.is_pointer());
+    assert!(!int_pointer.is_primitive());
+
+    let record_type = Type::new(TypeKind::Record, "struct point".to_owned());
+    assert!(record_type.is_record());
+    assert!(!record_type.is_primitive());
+}
+
+#[test]
+fn test_requires_unsafe() {
+    assert!(!int_type().requires_unsafe());
+    assert!(pointer_to(int_type()).requires_unsafe());
+    assert!(array_of(pointer_to(int_type()), 4).requires_unsafe());
+    assert!(!array_of(int_type(), 4).requires_unsafe());
+    assert!(Type::new(TypeKind::FunctionPrototype, "void (int)".to_owned()).requires_unsafe());
+
+    let mut callback = Type::new(TypeKind::Typedef, "callback".to_owned());
+    callback.canonical_type = Some(Box::new(pointer_to(Type::new(
+        TypeKind::FunctionPrototype,
+        "void (int)".to_owned(),
+    ))));
+    assert!(callback.requires_unsafe());
+}
+
+#[test]
+fn test_type_requires_unsafe() {
+    let field = |name: &str, field_type: Type| {
+        let mut field_declare = FieldDeclare::new(Some(name.to_owned()));
+        field_declare.field_type = Some(field_type);
+        MemberDeclare::Field(field_declare)
+    };
+    let mut slice = StructDeclare::new(Some("slice".to_owned()), None);
+    slice.fields.push(field("data", pointer_to(int_type())));
+    slice.fields.push(field("length", int_type()));
+    let mut point = StructDeclare::new(Some("point".to_owned()), None);
+    point.fields.push(field("x", int_type()));
+    let mut message = StructDeclare::new(Some("message".to_owned()), None);
+    message.fields.push(field("id", int_type()));
+    message.fields.push(field(
+        "payloads",
+        array_of(Type::new(TypeKind::Record, "struct slice".to_owned()), 2),
+    ));
+    let mut source_file = SourceFile::new("message.h".to_owned());
+    source_file.type_declares.push(TypeDeclare::Struct(slice));
+    source_file.type_declares.push(TypeDeclare::Struct(point));
+    source_file.type_declares.push(TypeDeclare::Struct(message));
+
+    let record = |name: &str| Type::new(TypeKind::Record, format!("struct {}", name));
+    assert!(!record("slice").requires_unsafe());
+    assert!(source_file.type_requires_unsafe(&record("slice")));
+    assert!(source_file.type_requires_unsafe(&record("message")));
+    assert!(!source_file.type_requires_unsafe(&record("point")));
+    assert!(!source_file.type_requires_unsafe(&record("unknown")));
+    assert!(!source_file.type_requires_unsafe(&int_type()));
+}
+
+#[test]
+fn test_rust_type_name() {
+    assert_eq!(int_type().rust_type_name(), Some("i32"));
+    assert_eq!(
+        Type::new(TypeKind::ULong, "unsigned long".to_owned()).rust_type_name(),
+        Some("std::os::raw::c_ulong")
+    );
+    assert_eq!(pointer_to(int_type()).rust_type_name(), None);
+    assert_eq!(
+        Type::new(TypeKind::Record, "struct point".to_owned()).rust_type_name(),
+        None
+    );
+}
+
+#[test]
+fn test_detect_language_standard() {
+    assert_eq!(
+        LanguageStandard::detect(Path::new("api.h"), None),
+        LanguageStandard::C11
+    );
+    assert_eq!(
+        LanguageStandard::detect(Path::new("namespace.hpp"), None),
+        LanguageStandard::Cxx17
+    );
+    assert_eq!(
+        LanguageStandard::detect(Path::new("api.h"), Some("c++")),
+        LanguageStandard::Cxx17
+    );
+    assert_eq!(
+        LanguageStandard::detect(Path::new("api.m"), None),
+        LanguageStandard::Default
+    );
+}
+
+#[test]
+fn test_implicit_conversions() {
+    let double_type = Type::new(TypeKind::Double, "double".to_owned());
+    let mut class_declare = ClassDeclare::new(Some("celsius".to_owned()), None);
+    for (kind, is_explicit, parameter_type) in [
+        (ConstructorKind::Converting, false, double_type.clone()),
+        (ConstructorKind::Other, true, int_type()),
+    ] {
+        let mut constructor = ConstructorDeclare::new("celsius".to_owned());
+        constructor.kind = kind;
+        constructor.is_explicit = is_explicit;
+        let mut parameter_declare = ParameterDeclare::new(Some("degrees".to_owned()));
+        parameter_declare.parameter_type = Some(parameter_type);
+        constructor
+            .function
+            .parameters
+            .push(FunctionParameter::Named(parameter_declare));
+        class_declare.constructors.push(constructor);
+    }
+    let mut to_bool = ConversionFunctionDeclare::new(Type::new(TypeKind::Bool, "bool".to_owned()));
+    to_bool.is_explicit = true;
+    class_declare.conversion_functions =
+        vec![ConversionFunctionDeclare::new(double_type.clone()), to_bool];
+
+    assert_eq!(
+        class_declare.implicit_conversion_sources(),
+        vec![&double_type]
+    );
+    assert_eq!(
+        class_declare.implicit_conversion_targets(),
+        vec![&double_type]
+    );
+}
+
+#[test]
+fn test_type_declares_hash() {
+    let mut point = StructDeclare::new(Some("point".to_owned()), None);
+    let mut x = FieldDeclare::new(Some("x".to_owned()));
+    x.field_type = Some(int_type());
+    point.fields.push(MemberDeclare::Field(x));
+    let type_declares = vec![
+        TypeDeclare::Struct(point.clone()),
+        TypeDeclare::Struct(point.clone()),
+        TypeDeclare::Struct(StructDeclare::new(Some("point".to_owned()), None)),
+    ]
+    .into_iter()
+    .collect::<std::collections::HashSet<_>>();
+    assert_eq!(type_declares.len(), 2);
+    assert!(type_declares.contains(&TypeDeclare::Struct(point)));
+}
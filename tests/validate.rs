@@ -0,0 +1,97 @@
+mod common;
+
+use clang::TypeKind;
+use common::record_type;
+use test_clang_rust::{
+    validate::ValidationWarning, EnumConstantDeclare, EnumDeclare, FieldDeclare, FunctionDeclare,
+    FunctionParameter, MemberDeclare, ParameterDeclare, SourceFile, StructDeclare, Type,
+    TypeDeclare,
+};
+
+#[test]
+fn test_validate_reports_semantic_issues() {
+    let handle = StructDeclare::new(Some("handle".to_owned()), None);
+
+    let mut wrapper = StructDeclare::new(Some("wrapper".to_owned()), None);
+    wrapper.is_definition = true;
+    let mut inner = FieldDeclare::new(Some("inner".to_owned()));
+    inner.field_type = Some(record_type("handle"));
+    wrapper.fields.push(MemberDeclare::Field(inner));
+    let mut count = FieldDeclare::new(Some("count".to_owned()));
+    count.field_type = Some(Type::new(TypeKind::Int, "int".to_owned()));
+    count.size_bytes = Some(4);
+    wrapper.fields.push(MemberDeclare::Field(count));
+
+    let mut mode = EnumDeclare::new(Some("mode".to_owned()), None);
+    mode.constants
+        .push(EnumConstantDeclare::new("MODE_AUTO".to_owned()));
+
+    let mut close_handle = FunctionDeclare::new("close_handle".to_owned());
+    let mut parameter_declare = ParameterDeclare::new(Some("handle".to_owned()));
+    let mut pointer_type = Type::new(TypeKind::Pointer, "struct handle *".to_owned());
+    pointer_type.pointee_type = Some(Box::new(record_type("handle")));
+    parameter_declare.parameter_type = Some(pointer_type);
+    close_handle
+        .parameters
+        .push(FunctionParameter::Named(parameter_declare));
+    let mut copy_handle = FunctionDeclare::new("copy_handle".to_owned());
+    copy_handle.return_type = Some(record_type("handle"));
+
+    let mut source_file = SourceFile::new("handle.h".to_owned());
+    source_file.type_declares.push(TypeDeclare::Struct(handle));
+    source_file.type_declares.push(TypeDeclare::Struct(wrapper));
+    source_file.type_declares.push(TypeDeclare::Enum(mode));
+    source_file.function_declares.push(close_handle);
+    source_file.function_declares.push(copy_handle);
+
+    let warnings = source_file.validate();
+    assert_eq!(
+        warnings,
+        vec![
+            ValidationWarning::UnknownFieldSize {
+                record_name: "wrapper".to_owned(),
+                field_name: "inner".to_owned(),
+            },
+            ValidationWarning::ForwardDeclarationByValue {
+                entity_name: "wrapper.inner".to_owned(),
+                type_name: "handle".to_owned(),
+            },
+            ValidationWarning::MissingEnumValue {
+                enum_name: "mode".to_owned(),
+                constant_name: "MODE_AUTO".to_owned(),
+            },
+            ValidationWarning::MissingReturnType {
+                function_name: "close_handle".to_owned(),
+            },
+            ValidationWarning::ForwardDeclarationByValue {
+                entity_name: "copy_handle".to_owned(),
+                type_name: "handle".to_owned(),
+            },
+        ]
+    );
+    assert_eq!(
+        warnings[1].to_string(),
+        "wrapper.inner uses forward declared type handle by value"
+    );
+}
+
+#[test]
+fn test_validate_reports_undeduced_types() {
+    let mut deduced_type = Type::new(TypeKind::Auto, "__auto_type".to_owned());
+    deduced_type.is_deduced = true;
+    let mut pointer_type = Type::new(TypeKind::Pointer, "__auto_type *".to_owned());
+    pointer_type.pointee_type = Some(Box::new(deduced_type));
+    let mut make_value = FunctionDeclare::new("make_value".to_owned());
+    make_value.return_type = Some(pointer_type);
+
+    let mut source_file = SourceFile::new("value.h".to_owned());
+    source_file.function_declares.push(make_value);
+
+    assert_eq!(
+        source_file.validate(),
+        vec![ValidationWarning::UndeducedType {
+            entity_name: "make_value".to_owned(),
+            type_name: "__auto_type".to_owned(),
+        }]
+    );
+}